@@ -41,6 +41,19 @@ library MetadataType {
         uint64 max_contract_limit;
     }
 
+    struct ConsensusConfigV1 {
+        uint64 propose_ratio;
+        uint64 prevote_ratio;
+        uint64 precommit_ratio;
+        uint64 brake_ratio;
+        uint64 tx_num_limit;
+        uint64 max_tx_size;
+        uint64 gas_limit;
+        uint64 interval;
+        uint64 max_contract_limit;
+        uint64 max_call_depth;
+    }
+
     struct CkbRelatedInfo {
         bytes32 metadata_type_id;
         bytes32 checkpoint_type_id;
@@ -61,6 +74,10 @@ interface MetadataManager {
         MetadataType.ConsensusConfig memory config
     ) external;
 
+    function updateConsensusConfigV1(
+        MetadataType.ConsensusConfigV1 memory config
+    ) external;
+
     function setCkbRelatedInfo(
         MetadataType.CkbRelatedInfo memory info
     ) external;
@@ -17,6 +17,8 @@ use crate::parse_file;
 
 pub const DEFAULT_BROADCAST_TXS_SIZE: usize = 200;
 pub const DEFAULT_BROADCAST_TXS_INTERVAL: u64 = 200; // milliseconds
+pub const DEFAULT_MIN_GAS_PRICE: u64 = 1;
+pub const DEFAULT_MAX_NONCE_GAP: u64 = 64;
 pub const DEFAULT_SYNC_TXS_CHUNK_SIZE: usize = 5000;
 pub const DEFAULT_CACHE_SIZE: usize = 100;
 
@@ -230,6 +232,14 @@ fn default_broadcast_txs_interval() -> u64 {
     DEFAULT_BROADCAST_TXS_INTERVAL
 }
 
+fn default_min_gas_price() -> u64 {
+    DEFAULT_MIN_GAS_PRICE
+}
+
+fn default_max_nonce_gap() -> u64 {
+    DEFAULT_MAX_NONCE_GAP
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct ConfigMempool {
     pub pool_size:   u64,
@@ -239,6 +249,13 @@ pub struct ConfigMempool {
     pub broadcast_txs_size:     usize,
     #[serde(default = "default_broadcast_txs_interval")]
     pub broadcast_txs_interval: u64,
+    /// The transactions with a lower gas price are not admitted.
+    #[serde(default = "default_min_gas_price")]
+    pub min_gas_price:          u64,
+    /// The transactions whose nonce is ahead of the sender's by more than it
+    /// are rejected rather than queued.
+    #[serde(default = "default_max_nonce_gap")]
+    pub max_nonce_gap:          u64,
 }
 
 #[derive(Clone, Debug, Deserialize)]
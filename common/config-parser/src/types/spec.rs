@@ -6,7 +6,7 @@ use clap::{
 };
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
+use strum_macros::{Display, EnumIter, EnumString};
 
 use common_crypto::Secp256k1RecoverablePrivateKey;
 use protocol::{
@@ -263,12 +263,34 @@ impl From<HardforkInput> for HardforkInfoInner {
 }
 
 /// inspired by https://www.wikiwand.com/en/IAU_designated_constellations#List
-#[derive(Clone, Debug, Serialize, Deserialize, Copy, ValueEnum, EnumIter, PartialEq, Eq, Hash)]
+///
+/// The name is displayed as the variant name, such as `Andromeda`, and parsed
+/// from it case-insensitively, an unknown name fails to parse.
+#[derive(
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    Copy,
+    ValueEnum,
+    EnumIter,
+    Display,
+    EnumString,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+#[strum(ascii_case_insensitive)]
 pub enum HardforkName {
     None = 0b0,
     /// If this hardfork is activated, chain validators can modify the EVM
-    /// contract size limit.
+    /// contract size limit, and the transactions sent from an account with
+    /// code are rejected as EIP-3607.
     Andromeda = 0b1,
+    /// If this hardfork is activated, the EIP-1559 effective gas price is
+    /// charged and the base fee is burnt, and chain validators can modify the
+    /// EVM call stack limit.
+    Antlia = 0b10,
 }
 
 impl HardforkName {
@@ -280,3 +302,21 @@ impl HardforkName {
         res
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hardfork_name_string_round_trip() {
+        for name in HardforkName::iter() {
+            let s = name.to_string();
+            assert_eq!(s.parse::<HardforkName>().unwrap(), name);
+            assert_eq!(s.to_lowercase().parse::<HardforkName>().unwrap(), name);
+        }
+
+        assert_eq!(HardforkName::Andromeda.to_string(), "Andromeda");
+        assert!("Unknown".parse::<HardforkName>().is_err());
+        assert!("".parse::<HardforkName>().is_err());
+    }
+}
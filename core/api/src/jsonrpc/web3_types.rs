@@ -85,7 +85,7 @@ impl From<SignedTransaction> for Web3Transaction {
         let signature = stx.transaction.signature.clone().unwrap_or_default();
         let is_eip1559 = stx.transaction.unsigned.is_eip1559();
 
-        let sig_v = signature.add_chain_replay_protection(stx.transaction.chain_id);
+        let sig_v = signature.full_v(stx.transaction.chain_id);
         let (sig_r, sig_s) = if signature.is_eth_sig() {
             (
                 Either::Left(U256::from(&signature.r[..])),
@@ -104,8 +104,8 @@ impl From<SignedTransaction> for Web3Transaction {
             block_hash:               None,
             raw:                      Hex::encode(stx.transaction.encode().unwrap()),
             public_key:               stx.public,
-            gas:                      *stx.transaction.unsigned.gas_limit(),
-            gas_price:                stx.transaction.unsigned.gas_price(),
+            gas:                      stx.gas_limit(),
+            gas_price:                stx.gas_price(),
             max_fee_per_gas:          if is_eip1559 {
                 Some(U256::from(MAX_PRIORITY_FEE_PER_GAS))
             } else {
@@ -120,9 +120,9 @@ impl From<SignedTransaction> for Web3Transaction {
             from:                     stx.sender,
             to:                       stx.get_to(),
             input:                    Hex::encode(stx.transaction.unsigned.data()),
-            nonce:                    *stx.transaction.unsigned.nonce(),
+            nonce:                    stx.nonce(),
             transaction_index:        None,
-            value:                    *stx.transaction.unsigned.value(),
+            value:                    stx.value(),
             access_list:              Some(stx.transaction.unsigned.access_list()),
             chain_id:                 stx.transaction.chain_id.map(|id| id.into()),
             standard_v:               None,
@@ -26,8 +26,8 @@ pub fn get_random_bytes(len: usize) -> Bytes {
 }
 
 fn mock_sign_tx() -> SignedTransaction {
-    let mut utx = UnverifiedTransaction {
-        unsigned:  UnsignedTransaction::Eip1559(Eip1559Transaction {
+    let mut utx = UnverifiedTransaction::new(
+        UnsignedTransaction::Eip1559(Eip1559Transaction {
             nonce:                    Default::default(),
             max_priority_fee_per_gas: Default::default(),
             gas_price:                Default::default(),
@@ -37,14 +37,14 @@ fn mock_sign_tx() -> SignedTransaction {
             data:                     Bytes::new(),
             access_list:              vec![],
         }),
-        signature: Some(SignatureComponents {
+        Some(SignatureComponents {
             standard_v: 4,
             r:          Default::default(),
             s:          Default::default(),
         }),
-        chain_id:  Some(random::<u64>()),
-        hash:      mock_hash(),
-    }
+        Some(random::<u64>()),
+        mock_hash(),
+    )
     .calc_hash();
 
     let priv_key = Secp256k1RecoverablePrivateKey::generate(&mut OsRng);
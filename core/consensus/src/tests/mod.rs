@@ -107,8 +107,8 @@ fn _mock_validator() -> Validator {
 
 fn gen_tx(sender: H160, addr: H160, value: u64, data: Vec<u8>) -> SignedTransaction {
     SignedTransaction {
-        transaction: UnverifiedTransaction {
-            unsigned:  UnsignedTransaction::Eip1559(Eip1559Transaction {
+        transaction: UnverifiedTransaction::new(
+            UnsignedTransaction::Eip1559(Eip1559Transaction {
                 nonce:                    U256::default(),
                 max_priority_fee_per_gas: U256::default(),
                 gas_price:                U256::default(),
@@ -118,14 +118,14 @@ fn gen_tx(sender: H160, addr: H160, value: u64, data: Vec<u8>) -> SignedTransact
                 data:                     data.into(),
                 access_list:              Vec::new(),
             }),
-            signature: Some(SignatureComponents {
+            Some(SignatureComponents {
                 standard_v: 0,
                 r:          Bytes::default(),
                 s:          Bytes::default(),
             }),
-            chain_id:  Some(0u64),
-            hash:      H256::default(),
-        },
+            Some(0u64),
+            H256::default(),
+        ),
         sender,
         public: Some(Public::default()),
     }
@@ -264,16 +264,20 @@ impl CommonConsensusAdapter for MockSyncAdapter {
         signed_txs: &[SignedTransaction],
     ) -> ProtocolResult<ExecResp> {
         Ok(ExecResp {
-            state_root:   H256::from_str(
+            state_root:          H256::from_str(
                 "0xc2ca3b067635ecf9a5b17a398a2509a2bd93ed172bfb6699c7b046704ded529a",
             )
             .unwrap(),
-            receipt_root: H256::from_str(
+            receipt_root:        H256::from_str(
                 "0xc2ca3b067635ecf9a5b17a398a2509a2bd93ed172bfb6699c7b046704ded529a",
             )
             .unwrap(),
-            gas_used:     100,
-            tx_resp:      vec![],
+            gas_used:            100,
+            cumulative_gas_used: vec![],
+            encoded_receipts:    vec![],
+            tx_resp:             vec![],
+            fee_overflow:        false,
+            touched_accounts:    vec![],
         })
     }
 
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ethers::abi::AbiEncode;
+use evm::backend::{MemoryBackend, MemoryVicinity};
+use evm::executor::stack::PrecompileFn;
+use evm::Context;
+
+use common_config_parser::types::spec::HardforkName;
+use core_db::RocksAdapter;
+use core_executor::system_contract::image_cell::image_cell_abi;
+use core_executor::system_contract::{
+    init_system_contract_db, ImageCellContract, SystemContract, IMAGE_CELL_CONTRACT_ADDRESS,
+};
+use core_executor::{AXON_PRECOMPILE_BASE, HARDFORK_REGISTRY, PRECOMPILE_REGISTRY};
+use protocol::types::{
+    Eip1559Transaction, HardforkInfo, HardforkInfoInner, Public, SignatureComponents,
+    SignedTransaction, TransactionAction, UnsignedTransaction, UnverifiedTransaction, H160, H256,
+    U256,
+};
+
+const INPUT_SIZES: [usize; 5] = [0, 256, 1024, 4096, 16384];
+const CELL_DATA_SIZES: [usize; 5] = [0, 256, 1024, 4096, 16384];
+
+/// The precompiles whose cost depends on the input size.
+const INPUT_PRICED: [(&str, u64); 4] = [
+    ("sha256", 0x02),
+    ("ripemd160", 0x03),
+    ("identity", 0x04),
+    ("ckb_blake2b", AXON_PRECOMPILE_BASE as u64 | 0x06),
+];
+
+/// Get the precompile of the first block, all the hardforks are activated
+/// from the genesis.
+fn precompile(address: u64) -> PrecompileFn {
+    let mut registry = HardforkInfo::default();
+    registry.push(HardforkInfoInner {
+        block_number: 0,
+        flags:        H256::from_low_u64_be(HardforkName::all().to_be()),
+    });
+    HARDFORK_REGISTRY.store(Arc::new(registry));
+
+    *PRECOMPILE_REGISTRY
+        .build_at(1)
+        .get(&H160::from_low_u64_be(address))
+        .unwrap()
+}
+
+fn context() -> Context {
+    Context {
+        address:        Default::default(),
+        caller:         Default::default(),
+        apparent_value: Default::default(),
+    }
+}
+
+/// Execute the precompile, return the output length and the gas cost.
+fn exec(f: PrecompileFn, input: &[u8]) -> (usize, u64) {
+    let (output, gas) = f(input, None, &context(), false).unwrap();
+    (output.output.len(), gas)
+}
+
+fn words(len: usize) -> u64 {
+    ((len + 31) / 32) as u64
+}
+
+/// Assert that the gas grows linearly with the words and the cost of a word is
+/// positive, where each sample is `(words, gas)`.
+fn assert_linear(name: &str, samples: &[(u64, u64)]) {
+    let (w0, g0) = samples[0];
+    let (w1, g1) = samples[1];
+    assert!(g1 > g0, "{name}: the gas does not grow with the size");
+    let word_gas = (g1 - g0) / (w1 - w0);
+
+    for &(w, g) in samples {
+        assert_eq!(
+            g,
+            g0 + word_gas * (w - w0),
+            "{name}: the gas of {w} words is not linear"
+        );
+    }
+}
+
+fn criterion_input_priced(c: &mut Criterion) {
+    let mut group = c.benchmark_group("precompile_input");
+
+    for (name, address) in INPUT_PRICED {
+        let f = precompile(address);
+        let samples = INPUT_SIZES
+            .iter()
+            .map(|&size| (words(size), exec(f, &vec![0xab; size]).1))
+            .collect::<Vec<_>>();
+        assert_linear(name, &samples);
+
+        for size in INPUT_SIZES {
+            let input = vec![0xab; size];
+            group.throughput(Throughput::Bytes(size as u64));
+            group.bench_with_input(BenchmarkId::new(name, size), &input, |b, input| {
+                b.iter(|| exec(f, input))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+fn system_tx(data: Vec<u8>) -> SignedTransaction {
+    SignedTransaction {
+        transaction: UnverifiedTransaction::new(
+            UnsignedTransaction::Eip1559(Eip1559Transaction {
+                nonce:                    U256::zero(),
+                max_priority_fee_per_gas: U256::zero(),
+                gas_price:                U256::zero(),
+                gas_limit:                U256::from(u32::MAX),
+                action:                   TransactionAction::Call(IMAGE_CELL_CONTRACT_ADDRESS),
+                value:                    U256::zero(),
+                data:                     data.into(),
+                access_list:              Vec::new(),
+            }),
+            Some(SignatureComponents {
+                standard_v: 0,
+                r:          Default::default(),
+                s:          Default::default(),
+            }),
+            Some(0u64),
+            H256::default(),
+        ),
+        sender:      H160::random(),
+        public:      Some(Public::default()),
+    }
+}
+
+fn out_point(size: usize) -> image_cell_abi::OutPoint {
+    image_cell_abi::OutPoint {
+        tx_hash: [7u8; 32],
+        index:   size as u32,
+    }
+}
+
+/// Save a cell of each data size to the image cell store, the header cell root
+/// of the current thread is updated to read them.
+fn prepare_cells(backend: &mut MemoryBackend) {
+    let tx_outputs = CELL_DATA_SIZES
+        .iter()
+        .map(|&size| image_cell_abi::CellInfo {
+            out_point: out_point(size),
+            output:    image_cell_abi::CellOutput {
+                capacity: 0x34e62ce00,
+                lock:     image_cell_abi::Script {
+                    args:      vec![0u8; 20].into(),
+                    code_hash: [8u8; 32],
+                    hash_type: 1,
+                },
+                type_:    vec![],
+            },
+            data:      vec![0xab; size].into(),
+        })
+        .collect();
+    let data = image_cell_abi::UpdateCall {
+        blocks: vec![image_cell_abi::BlockUpdate {
+            block_number: 0x1,
+            tx_inputs: vec![],
+            tx_outputs,
+        }],
+    };
+
+    let r = ImageCellContract::default().exec_(backend, &system_tx(data.encode()));
+    assert!(r.exit_reason.is_succeed());
+}
+
+fn criterion_get_cell(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let inner_db = RocksAdapter::new(dir.path(), Default::default())
+        .unwrap()
+        .inner_db();
+    let vicinity = MemoryVicinity {
+        gas_price:              U256::zero(),
+        origin:                 H160::default(),
+        block_hashes:           Vec::new(),
+        block_number:           Default::default(),
+        block_coinbase:         Default::default(),
+        block_timestamp:        Default::default(),
+        block_difficulty:       Default::default(),
+        block_gas_limit:        Default::default(),
+        chain_id:               U256::one(),
+        block_base_fee_per_gas: U256::zero(),
+    };
+    let mut backend = MemoryBackend::new(&vicinity, BTreeMap::new());
+    init_system_contract_db(inner_db, &mut backend);
+    prepare_cells(&mut backend);
+
+    // The input is a fixed-size out point, so the gas is charged by the words
+    // of the returned cell.
+    let f = precompile(AXON_PRECOMPILE_BASE as u64 | 0x03);
+    let samples = CELL_DATA_SIZES
+        .iter()
+        .map(|&size| {
+            let (output_len, gas) = exec(f, &out_point(size).encode());
+            (words(output_len), gas)
+        })
+        .collect::<Vec<_>>();
+    assert_linear("get_cell", &samples);
+
+    let mut group = c.benchmark_group("precompile_get_cell");
+    for size in CELL_DATA_SIZES {
+        let input = out_point(size).encode();
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("get_cell", size), &input, |b, input| {
+            b.iter(|| exec(f, input))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_input_priced, criterion_get_cell);
+criterion_main!(benches);
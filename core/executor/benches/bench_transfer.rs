@@ -76,6 +76,7 @@ impl BenchAdapter {
                 block_gas_limit:        100_000_000_000u64.into(),
                 block_base_fee_per_gas: Default::default(),
                 extra_data:             Default::default(),
+                parent_hash:            Default::default(),
             },
         )
         .unwrap()
@@ -105,12 +106,12 @@ fn mock_transaction(nonce: u64) -> SignedTransaction {
         ),
     };
 
-    let mut utx = UnverifiedTransaction {
-        unsigned:  UnsignedTransaction::Eip1559(tx),
-        signature: None,
-        chain_id:  Some(0u64),
-        hash:      Default::default(),
-    };
+    let mut utx = UnverifiedTransaction::new(
+        UnsignedTransaction::Eip1559(tx),
+        None,
+        Some(0u64),
+        Default::default(),
+    );
 
     let raw = utx.signature_hash(true);
     let signature = Secp256k1Recoverable::sign_message(raw.as_bytes(), &PRIVATE_KEY.to_bytes())
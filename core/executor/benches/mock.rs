@@ -55,6 +55,7 @@ pub fn mock_executor_context() -> ExecutorContext {
         block_gas_limit:        100_000_000_000u64.into(),
         block_base_fee_per_gas: Default::default(),
         extra_data:             Default::default(),
+        parent_hash:            Default::default(),
     }
 }
 
@@ -86,12 +87,12 @@ pub fn mock_transactions(n: usize) -> Vec<SignedTransaction> {
             access_list:              vec![],
         };
         let tx = {
-            let mut utx = UnverifiedTransaction {
-                unsigned:  UnsignedTransaction::Eip1559(raw_tx),
-                signature: None,
-                chain_id:  Some(0u64),
-                hash:      Default::default(),
-            };
+            let mut utx = UnverifiedTransaction::new(
+                UnsignedTransaction::Eip1559(raw_tx),
+                None,
+                Some(0u64),
+                Default::default(),
+            );
             let hash = utx.signature_hash(true);
             let signature = Secp256k1Recoverable::sign_message(hash.as_bytes(), &sender_priv_key)
                 .unwrap()
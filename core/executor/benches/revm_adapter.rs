@@ -9,8 +9,8 @@ use core_executor::{code_address, MPTTrie};
 use protocol::traits::{Context, Storage};
 use protocol::trie::Trie as _;
 use protocol::types::{
-    Account, Address, Bytes, ExecResp, ExecutorContext, Hasher, SignedTransaction,
-    TransactionAction, TxResp, H160, H256, NIL_DATA, RLP_NULL, U256,
+    Account, Address, Bytes, ExecResp, ExecutorContext, Hasher, SignedTransaction, TxResp, H160,
+    H256, NIL_DATA, RLP_NULL, U256,
 };
 use protocol::{codec::ProtocolCodec, trie, ProtocolError};
 
@@ -287,6 +287,7 @@ where
     let mut tx_outputs = Vec::with_capacity(txs_len);
     let mut hashes = Vec::with_capacity(txs_len);
     let mut total_gas_used = 0u64;
+    let mut cumulative_gas_used = Vec::with_capacity(txs_len);
 
     txs.into_iter().for_each(|tx| {
         let old_nonce = evm
@@ -313,8 +314,9 @@ where
         };
         hashes.push(Hasher::digest(&ret));
         total_gas_used += res.gas_used;
+        cumulative_gas_used.push(total_gas_used);
 
-        let code_address = if tx.transaction.unsigned.action() == &TransactionAction::Create {
+        let code_address = if tx.transaction.unsigned.is_create() {
             Some(code_address(&tx.sender, &(old_nonce.into())))
         } else {
             None
@@ -348,6 +350,10 @@ where
         state_root: evm.db().unwrap().trie.commit().unwrap(),
         receipt_root,
         gas_used: total_gas_used,
+        cumulative_gas_used,
+        encoded_receipts: vec![],
         tx_resp: tx_outputs,
+        fee_overflow: false,
+        touched_accounts: vec![],
     }
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+use std::mem;
 use std::sync::Arc;
 
 use evm::backend::{Apply, Basic};
@@ -8,17 +10,27 @@ use protocol::traits::{
 };
 use protocol::trie::Trie;
 use protocol::types::{
-    Account, Bytes, ExecutorContext, Hasher, Log, MerkleRoot, H160, H256, NIL_DATA, RLP_NULL, U256,
+    Account, Bytes, ExecutorContext, Hasher, Log, MerkleRoot, SnapshotId, H160, H256, NIL_DATA,
+    RLP_NULL, U256,
 };
 use protocol::{codec::ProtocolCodec, trie, ProtocolResult};
 
+use crate::adapter::backend::read_only::record_code_size;
 use crate::blocking_async;
 use crate::system_contract::{METADATA_CONTRACT_ADDRESS, METADATA_ROOT_KEY};
-use crate::{adapter::AxonExecutorReadOnlyAdapter, MPTTrie};
+use crate::{
+    adapter::{AxonExecutorReadOnlyAdapter, GenesisBuilder, OverlayTrieDB},
+    MPTTrie,
+};
 
 pub struct AxonExecutorApplyAdapter<S, DB: trie::DB> {
-    inner: AxonExecutorReadOnlyAdapter<S, DB>,
-    logs:  Vec<Log>,
+    inner:   AxonExecutorReadOnlyAdapter<S, DB>,
+    logs:    Vec<Log>,
+    touched: BTreeSet<H160>,
+    /// The raw accounts in the state trie before they are modified, which are
+    /// restored in reverse order by `revert_to`. It is `None` until the first
+    /// snapshot is taken so that no account is recorded without a snapshot.
+    journal: Option<Vec<(H160, Option<Vec<u8>>)>>,
 }
 
 impl<S, DB> ExecutorReadOnlyAdapter for AxonExecutorApplyAdapter<S, DB>
@@ -37,6 +49,10 @@ where
     fn get_account(&self, address: &H160) -> Account {
         self.inner.get_account(address)
     }
+
+    fn code_size(&self, address: &H160) -> usize {
+        self.inner.code_size(address)
+    }
 }
 
 impl<S, DB> Backend for AxonExecutorApplyAdapter<S, DB>
@@ -128,7 +144,46 @@ where
         self.inner.trie.commit().unwrap()
     }
 
+    fn reset(&mut self, state_root: MerkleRoot) {
+        self.inner.trie = MPTTrie::from_root(state_root, Arc::clone(&self.inner.db)).unwrap();
+        self.logs.clear();
+        self.journal = None;
+    }
+
+    /// The snapshot is a position in the journal of the modified accounts, so
+    /// taking one writes nothing to the database. The journal is kept across
+    /// commits, and it is only dropped by `reset`.
+    fn snapshot(&mut self) -> SnapshotId {
+        SnapshotId(self.journal.get_or_insert_with(Vec::new).len())
+    }
+
+    fn revert_to(&mut self, id: SnapshotId) {
+        if let Some(journal) = self.journal.as_mut() {
+            let at = id.0.min(journal.len());
+            for (address, raw) in journal.drain(at..).rev() {
+                match raw {
+                    Some(raw) => self
+                        .inner
+                        .trie
+                        .insert(address.as_bytes().to_vec(), raw)
+                        .unwrap(),
+                    None => {
+                        self.inner.trie.remove(address.as_bytes()).unwrap();
+                    }
+                }
+            }
+        }
+
+        self.logs.clear();
+    }
+
+    fn take_touched_accounts(&mut self) -> Vec<H160> {
+        mem::take(&mut self.touched).into_iter().collect()
+    }
+
     fn save_account(&mut self, address: &H160, account: &Account) {
+        self.touched.insert(*address);
+        self.record(address);
         self.inner
             .trie
             .insert(
@@ -144,6 +199,33 @@ where
     S: Storage + 'static,
     DB: trie::DB + 'static,
 {
+    /// Start to seed the genesis state of the adapter.
+    pub fn genesis_builder(&mut self) -> GenesisBuilder<'_, S, DB> {
+        GenesisBuilder::new(self)
+    }
+
+    /// Open an adapter over the committed state of this one, whose trie nodes
+    /// are written to an [`OverlayTrieDB`] in memory rather than the database.
+    /// The pending changes of this adapter are committed first.
+    pub fn overlay(&mut self) -> ProtocolResult<AxonExecutorApplyAdapter<S, OverlayTrieDB<DB>>> {
+        let state_root = self.commit();
+        AxonExecutorApplyAdapter::from_root(
+            state_root,
+            Arc::new(OverlayTrieDB::new(Arc::clone(&self.inner.db))),
+            Arc::clone(&self.inner.storage),
+            self.inner.exec_ctx.clone(),
+        )
+    }
+
+    /// Record the account in the state trie before it is modified if there
+    /// is a snapshot to revert to.
+    fn record(&mut self, address: &H160) {
+        if let Some(journal) = self.journal.as_mut() {
+            let raw = self.inner.trie.get(address.as_bytes()).unwrap();
+            journal.push((*address, raw));
+        }
+    }
+
     fn apply<I: IntoIterator<Item = (H256, H256)>>(
         &mut self,
         address: H160,
@@ -152,6 +234,9 @@ where
         storage: I,
         reset_storage: bool,
     ) -> bool {
+        self.touched.insert(address);
+        self.record(&address);
+
         let old_account = match self.inner.trie.get(address.as_bytes()) {
             Ok(Some(raw)) => Account::decode(raw).unwrap(),
             _ => Account {
@@ -168,37 +253,44 @@ where
             old_account.storage_root
         };
 
-        let mut storage_trie = if storage_root == RLP_NULL {
-            MPTTrie::new(Arc::clone(&self.inner.db))
+        // The storage trie is left untouched if no slot is changed, so that a
+        // plain transfer writes no trie node to the database.
+        let mut storage = storage.into_iter().peekable();
+        let storage_root = if storage.peek().is_none() {
+            storage_root
         } else {
-            MPTTrie::from_root(old_account.storage_root, Arc::clone(&self.inner.db)).unwrap()
-        };
-
-        storage.into_iter().for_each(|(k, v)| {
-            // https://github.com/ethereum/go-ethereum/blob/ad16f11f841ab3a5fdedc8ddfc602f0717a34dd0/core/state/state_object.go#L306-L311
-            // if value is zero, delete it's key
-            if v == H256::zero() {
-                storage_trie
-                    .remove(k.as_bytes())
-                    .expect("Failed to remove entry with zero value from storage trie");
+            let mut storage_trie = if storage_root == RLP_NULL {
+                MPTTrie::new(Arc::clone(&self.inner.db))
             } else {
-                storage_trie
-                    .insert(
-                        k.as_bytes().to_vec(),
-                        // https://github.com/ethereum/go-ethereum/blob/ad16f11f841ab3a5fdedc8ddfc602f0717a34dd0/core/state/state_object.go#L314
-                        // Trim left zeroes and then rlp
-                        U256::from_big_endian(v.as_bytes())
-                            .encode()
-                            .unwrap()
-                            .to_vec(),
-                    )
-                    .expect("trie tree insert fail");
-            }
-        });
+                MPTTrie::from_root(old_account.storage_root, Arc::clone(&self.inner.db)).unwrap()
+            };
+
+            storage.for_each(|(k, v)| {
+                // https://github.com/ethereum/go-ethereum/blob/ad16f11f841ab3a5fdedc8ddfc602f0717a34dd0/core/state/state_object.go#L306-L311
+                // if value is zero, delete it's key
+                if v == H256::zero() {
+                    storage_trie
+                        .remove(k.as_bytes())
+                        .expect("Failed to remove entry with zero value from storage trie");
+                } else {
+                    storage_trie
+                        .insert(
+                            k.as_bytes().to_vec(),
+                            // https://github.com/ethereum/go-ethereum/blob/ad16f11f841ab3a5fdedc8ddfc602f0717a34dd0/core/state/state_object.go#L314
+                            // Trim left zeroes and then rlp
+                            U256::from_big_endian(v.as_bytes())
+                                .encode()
+                                .unwrap()
+                                .to_vec(),
+                        )
+                        .expect("trie tree insert fail");
+                }
+            });
 
-        let storage_root = storage_trie
-            .commit()
-            .unwrap_or_else(|err| panic!("failed to update the trie storage since {err}"));
+            storage_trie
+                .commit()
+                .unwrap_or_else(|err| panic!("failed to update the trie storage since {err}"));
+        };
 
         let mut new_account = Account {
             nonce: basic.nonce,
@@ -220,6 +312,7 @@ where
                     c.into()
                 );
 
+                record_code_size(new_code_hash, c.len());
                 new_account.code_hash = new_code_hash;
             }
         }
@@ -265,6 +358,8 @@ where
                     }
                 }
                 Apply::Delete { address } => {
+                    self.touched.insert(address);
+                    self.record(&address);
                     let _ = self.inner.trie.remove(address.as_bytes());
                 }
             }
@@ -281,8 +376,10 @@ where
 {
     pub fn new(db: Arc<DB>, storage: Arc<S>, exec_ctx: ExecutorContext) -> ProtocolResult<Self> {
         Ok(AxonExecutorApplyAdapter {
-            inner: AxonExecutorReadOnlyAdapter::new(db, storage, exec_ctx)?,
-            logs:  Vec::new(),
+            inner:   AxonExecutorReadOnlyAdapter::new(db, storage, exec_ctx)?,
+            logs:    Vec::new(),
+            touched: BTreeSet::new(),
+            journal: None,
         })
     }
 
@@ -293,8 +390,10 @@ where
         exec_ctx: ExecutorContext,
     ) -> ProtocolResult<Self> {
         Ok(AxonExecutorApplyAdapter {
-            inner: AxonExecutorReadOnlyAdapter::from_root(state_root, db, storage, exec_ctx)?,
-            logs:  Vec::new(),
+            inner:   AxonExecutorReadOnlyAdapter::from_root(state_root, db, storage, exec_ctx)?,
+            logs:    Vec::new(),
+            touched: BTreeSet::new(),
+            journal: None,
         })
     }
 
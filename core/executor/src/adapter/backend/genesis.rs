@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use evm::backend::{Apply, Basic};
+
+use protocol::traits::{ApplyBackend, Backend, ExecutorAdapter, Storage};
+use protocol::trie;
+use protocol::types::{MerkleRoot, H160, H256, U256};
+
+use crate::adapter::AxonExecutorApplyAdapter;
+
+/// Build the genesis state by seeding the balance, the nonce, the code and the
+/// storage of the accounts, which are applied to the adapter on
+/// [`commit`](GenesisBuilder::commit). The fields not set are kept as the ones
+/// in the adapter.
+pub struct GenesisBuilder<'a, S, DB: trie::DB> {
+    adapter:  &'a mut AxonExecutorApplyAdapter<S, DB>,
+    accounts: BTreeMap<H160, GenesisAccount>,
+}
+
+struct GenesisAccount {
+    basic:   Basic,
+    code:    Option<Vec<u8>>,
+    storage: BTreeMap<H256, H256>,
+}
+
+impl<'a, S, DB> GenesisBuilder<'a, S, DB>
+where
+    S: Storage + 'static,
+    DB: trie::DB + 'static,
+{
+    pub fn new(adapter: &'a mut AxonExecutorApplyAdapter<S, DB>) -> Self {
+        GenesisBuilder {
+            adapter,
+            accounts: BTreeMap::new(),
+        }
+    }
+
+    pub fn set_balance(mut self, address: H160, balance: U256) -> Self {
+        self.account(address).basic.balance = balance;
+        self
+    }
+
+    pub fn set_nonce(mut self, address: H160, nonce: U256) -> Self {
+        self.account(address).basic.nonce = nonce;
+        self
+    }
+
+    pub fn set_code(mut self, address: H160, code: Vec<u8>) -> Self {
+        self.account(address).code = Some(code);
+        self
+    }
+
+    pub fn set_storage(mut self, address: H160, key: H256, value: H256) -> Self {
+        self.account(address).storage.insert(key, value);
+        self
+    }
+
+    /// Apply the seeded accounts to the adapter and commit them, the genesis
+    /// state root is returned.
+    pub fn commit(self) -> MerkleRoot {
+        let changes = self
+            .accounts
+            .into_iter()
+            .map(|(address, account)| Apply::Modify {
+                address,
+                basic: account.basic,
+                code: account.code,
+                storage: account.storage,
+                reset_storage: false,
+            })
+            .collect::<Vec<_>>();
+
+        ApplyBackend::apply(&mut *self.adapter, changes, vec![], false);
+        ExecutorAdapter::commit(&mut *self.adapter)
+    }
+
+    fn account(&mut self, address: H160) -> &mut GenesisAccount {
+        let adapter = &*self.adapter;
+        self.accounts
+            .entry(address)
+            .or_insert_with(|| GenesisAccount {
+                basic:   adapter.basic(address),
+                code:    None,
+                storage: BTreeMap::new(),
+            })
+    }
+}
@@ -1,2 +1,4 @@
 pub mod apply;
+pub mod genesis;
 pub mod read_only;
+pub mod scratch;
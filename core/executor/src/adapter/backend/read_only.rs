@@ -1,6 +1,9 @@
-use std::sync::Arc;
+use std::cmp::Ordering;
+use std::{num::NonZeroUsize, sync::Arc};
 
 use evm::backend::Basic;
+use lru::LruCache;
+use parking_lot::Mutex;
 
 use protocol::traits::{Backend, Context, ExecutorReadOnlyAdapter, ReadOnlyStorage};
 use protocol::trie::Trie as _;
@@ -13,9 +16,47 @@ use protocol::{codec::ProtocolCodec, trie, ProtocolResult};
 use crate::system_contract::{
     HEADER_CELL_ROOT_KEY, IMAGE_CELL_CONTRACT_ADDRESS, METADATA_CONTRACT_ADDRESS, METADATA_ROOT_KEY,
 };
-use crate::{blocking_async, MPTTrie};
+use crate::{blocking_async, ExecutorError, MPTTrie};
 
 const GET_BLOCK_HASH_NUMBER_RANGE: u64 = 256;
+const CODE_SIZE_CACHE_SIZE: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(4096) };
+
+lazy_static::lazy_static! {
+    /// The code sizes keyed by the code hash, which never change for a hash.
+    /// The sizes are recorded when the codes are saved or loaded.
+    static ref CODE_SIZE_CACHE: Mutex<LruCache<H256, usize>> = Mutex::new(LruCache::new(CODE_SIZE_CACHE_SIZE));
+}
+
+/// Record the size of the code of the hash, so that
+/// [`ExecutorReadOnlyAdapter::code_size`] answers it without loading the
+/// code.
+pub(crate) fn record_code_size(code_hash: H256, size: usize) {
+    CODE_SIZE_CACHE.lock().put(code_hash, size);
+}
+
+/// The nonce of a transaction compared with the nonce of its sender.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NonceCheck {
+    /// The nonce equals the account nonce, which is ready to be executed.
+    Ready,
+    /// The nonce is above the account nonce by the gap, which is queued
+    /// until the transactions of the nonces in between are executed.
+    Queued(U256),
+    /// The nonce is below the account nonce, which has been used.
+    Stale,
+}
+
+impl NonceCheck {
+    /// Compare the nonce of a transaction with the `current` nonce of the
+    /// sender.
+    pub fn new(current: U256, nonce: U256) -> Self {
+        match nonce.cmp(&current) {
+            Ordering::Equal => NonceCheck::Ready,
+            Ordering::Greater => NonceCheck::Queued(nonce - current),
+            Ordering::Less => NonceCheck::Stale,
+        }
+    }
+}
 
 pub struct AxonExecutorReadOnlyAdapter<S, DB: trie::DB> {
     pub(crate) exec_ctx: ExecutorContext,
@@ -49,6 +90,24 @@ where
             code_hash:    NIL_DATA,
         }
     }
+
+    // The account without code is answered by the code hash, and the others
+    // are answered by the recorded size of the code hash. The code is loaded
+    // only if its size is not recorded yet.
+    fn code_size(&self, address: &H160) -> usize {
+        let code_hash = self.get_account(address).code_hash;
+        if code_hash == NIL_DATA {
+            return 0;
+        }
+
+        if let Some(size) = CODE_SIZE_CACHE.lock().get(&code_hash) {
+            return *size;
+        }
+
+        self.code_by_hash(&code_hash)
+            .map(|code| code.len())
+            .unwrap_or_default()
+    }
 }
 
 impl<S, DB> Backend for AxonExecutorReadOnlyAdapter<S, DB>
@@ -68,6 +127,8 @@ where
         self.exec_ctx.block_number
     }
 
+    // The parent hash is answered by the context, the others in range are read
+    // from the storage.
     fn block_hash(&self, number: U256) -> H256 {
         let current_number = self.block_number();
         if number >= current_number {
@@ -78,6 +139,10 @@ where
             return H256::default();
         }
 
+        if number + 1 == current_number && !self.exec_ctx.parent_hash.is_zero() {
+            return self.exec_ctx.parent_hash;
+        }
+
         let number = number.as_u64();
         blocking_async!(self, get_storage, get_block, Context::new(), number)
             .map(|b| b.hash())
@@ -139,19 +204,7 @@ where
             return Vec::new();
         };
 
-        if code_hash == NIL_DATA {
-            return Vec::new();
-        }
-
-        let res = blocking_async!(
-            self,
-            get_storage,
-            get_code_by_hash,
-            Context::new(),
-            &code_hash
-        );
-
-        res.unwrap_or_default().to_vec()
+        self.code_by_hash(&code_hash).unwrap_or_default().to_vec()
     }
 
     // ### Notes
@@ -225,10 +278,101 @@ where
         })
     }
 
+    /// Open a read only view of the historical state at `state_root`, which
+    /// is used to serve the state queries with a block tag. Return an error
+    /// if the root node is pruned or has never been committed.
+    pub fn at_root(db: Arc<DB>, storage: Arc<S>, state_root: MerkleRoot) -> ProtocolResult<Self> {
+        let trie = MPTTrie::from_root(state_root, Arc::clone(&db))
+            .map_err(|_| ExecutorError::StateRootUnavailable(state_root))?;
+
+        Ok(AxonExecutorReadOnlyAdapter {
+            trie,
+            db,
+            storage,
+            exec_ctx: ExecutorContext::default(),
+        })
+    }
+
     pub fn get_metadata_root(&self) -> H256 {
         self.storage(METADATA_CONTRACT_ADDRESS, *METADATA_ROOT_KEY)
     }
 
+    /// Get the code by its hash, which helps the tools holding the code hash of
+    /// an account to fetch the code without the address. `None` is returned
+    /// for the empty code hash or the code not stored.
+    pub fn code_by_hash(&self, code_hash: &H256) -> Option<Bytes> {
+        if *code_hash == NIL_DATA {
+            return None;
+        }
+
+        let code = blocking_async!(
+            self,
+            get_storage,
+            get_code_by_hash,
+            Context::new(),
+            code_hash
+        );
+        if let Some(code) = code.as_ref() {
+            record_code_size(*code_hash, code.len());
+        }
+        code
+    }
+
+    /// Compare the nonce of a transaction with the nonce of the sender.
+    pub fn check_nonce(&self, sender: &H160, nonce: U256) -> NonceCheck {
+        NonceCheck::new(self.get_account(sender).nonce, nonce)
+    }
+
+    /// Whether the nonce is the next nonce of the sender, see
+    /// [`check_nonce`](Self::check_nonce) to tell the queued and the stale
+    /// nonces apart.
+    pub fn is_valid_next_nonce(&self, sender: &H160, nonce: U256) -> bool {
+        self.check_nonce(sender, nonce) == NonceCheck::Ready
+    }
+
+    /// Build the Merkle proofs of an account and its storage slots for
+    /// `eth_getProof`, which are the trie nodes on the path from the state
+    /// root to the account and the ones from the storage root of the account
+    /// to each slot. The proof of a slot is empty if the account has no
+    /// storage.
+    #[allow(clippy::type_complexity)]
+    pub fn account_proof(
+        &self,
+        address: &H160,
+        storage_keys: &[H256],
+    ) -> ProtocolResult<(Vec<Bytes>, Vec<(H256, Vec<Bytes>)>)> {
+        let account_proof = self
+            .trie
+            .get_proof(address.as_bytes())?
+            .into_iter()
+            .map(Bytes::from)
+            .collect();
+
+        let storage_root = self.get_account(address).storage_root;
+        let storage_trie = if storage_root == RLP_NULL {
+            None
+        } else {
+            Some(MPTTrie::from_root(storage_root, Arc::clone(&self.db))?)
+        };
+
+        let storage_proofs = storage_keys
+            .iter()
+            .map(|key| {
+                let proof = match storage_trie.as_ref() {
+                    Some(trie) => trie
+                        .get_proof(key.as_bytes())?
+                        .into_iter()
+                        .map(Bytes::from)
+                        .collect(),
+                    None => Vec::new(),
+                };
+                Ok((*key, proof))
+            })
+            .collect::<ProtocolResult<Vec<_>>>()?;
+
+        Ok((account_proof, storage_proofs))
+    }
+
     pub fn get_image_cell_root(&self) -> H256 {
         self.storage(IMAGE_CELL_CONTRACT_ADDRESS, *HEADER_CELL_ROOT_KEY)
     }
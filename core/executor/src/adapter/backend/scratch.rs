@@ -0,0 +1,405 @@
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::mem;
+
+use evm::backend::{Apply, Basic, MemoryVicinity};
+
+use protocol::traits::{ApplyBackend, Backend, ExecutorAdapter, ExecutorReadOnlyAdapter};
+use protocol::types::{
+    Account, Bytes, ExecutorContext, Hasher, Log, MerkleRoot, SnapshotId, H160, H256, NIL_DATA,
+    RLP_NULL, U256,
+};
+
+/// An in-memory state holding the prefetched accounts and storage slots of a
+/// group of transactions, which is used to execute the group apart from the
+/// other groups. Any access beyond the prefetched state marks the scratch as
+/// escaped, and the execution result must be discarded in this case.
+///
+/// The scratch follows the same semantics as [`AxonExecutorApplyAdapter`],
+/// so merging the changes into the adapter results in the same state as
+/// executing the transactions on the adapter directly.
+///
+/// [`AxonExecutorApplyAdapter`]: crate::AxonExecutorApplyAdapter
+pub(crate) struct ScratchAdapter {
+    ctx:      ExecutorContext,
+    vicinity: MemoryVicinity,
+    accounts: BTreeMap<H160, ScratchAccount>,
+    logs:     Vec<Log>,
+    touched:  BTreeSet<H160>,
+    escaped:  Cell<bool>,
+}
+
+struct ScratchAccount {
+    origin:         Option<Account>,
+    origin_storage: BTreeMap<H256, H256>,
+    account:        Option<Account>,
+    code:           Vec<u8>,
+    storage:        BTreeMap<H256, H256>,
+    /// The account is deleted or its storage is reset, so all the storage
+    /// slots are known.
+    cleared:        bool,
+}
+
+impl ScratchAccount {
+    fn clear(&mut self) {
+        self.storage.clear();
+        self.cleared = true;
+    }
+}
+
+fn empty_account() -> Account {
+    Account {
+        nonce:        U256::zero(),
+        balance:      U256::zero(),
+        storage_root: RLP_NULL,
+        code_hash:    NIL_DATA,
+    }
+}
+
+impl ScratchAdapter {
+    /// Read the accounts and the storage slots from the adapter.
+    pub(crate) fn prefetch<Adapter: ExecutorAdapter>(
+        adapter: &Adapter,
+        slots: BTreeMap<H160, BTreeSet<H256>>,
+    ) -> Self {
+        let accounts = slots
+            .into_iter()
+            .map(|(address, keys)| {
+                let account = adapter
+                    .exists(address)
+                    .then(|| adapter.get_account(&address));
+                let code = match &account {
+                    Some(a) if a.code_hash != NIL_DATA => adapter.code(address),
+                    _ => Vec::new(),
+                };
+                let storage = keys
+                    .into_iter()
+                    .map(|key| (key, adapter.storage(address, key)))
+                    .collect::<BTreeMap<_, _>>();
+
+                (address, ScratchAccount {
+                    origin: account.clone(),
+                    origin_storage: storage.clone(),
+                    account,
+                    code,
+                    storage,
+                    cleared: false,
+                })
+            })
+            .collect();
+
+        ScratchAdapter {
+            ctx: adapter.get_ctx(),
+            vicinity: MemoryVicinity {
+                gas_price:              adapter.gas_price(),
+                origin:                 adapter.origin(),
+                chain_id:               adapter.chain_id(),
+                block_hashes:           Vec::new(),
+                block_number:           adapter.block_number(),
+                block_coinbase:         adapter.block_coinbase(),
+                block_timestamp:        adapter.block_timestamp(),
+                block_difficulty:       adapter.block_difficulty(),
+                block_gas_limit:        adapter.block_gas_limit(),
+                block_base_fee_per_gas: adapter.block_base_fee_per_gas(),
+            },
+            accounts,
+            logs: Vec::new(),
+            touched: BTreeSet::new(),
+            escaped: Cell::new(false),
+        }
+    }
+
+    /// Whether the execution has accessed the state beyond the prefetched one.
+    pub(crate) fn escaped(&self) -> bool {
+        self.escaped.get()
+    }
+
+    /// Apply the changes of the scratch to the adapter. An account deleted or
+    /// reset in the scratch is deleted from the adapter first, then its final
+    /// state is applied as a new account.
+    pub(crate) fn merge_into<Adapter: ExecutorAdapter>(self, adapter: &mut Adapter) {
+        let mut changes = Vec::new();
+
+        for (address, entry) in self.accounts.into_iter() {
+            let base = if entry.cleared {
+                if entry.origin.is_some() {
+                    changes.push(Apply::Delete { address });
+                }
+                None
+            } else {
+                entry.origin
+            };
+
+            let account = match entry.account {
+                Some(account) => account,
+                None => continue,
+            };
+
+            let storage = entry
+                .storage
+                .into_iter()
+                .filter(|(key, value)| {
+                    if entry.cleared {
+                        !value.is_zero()
+                    } else {
+                        entry.origin_storage.get(key) != Some(value)
+                    }
+                })
+                .collect::<Vec<_>>();
+            let base_account = base.clone().unwrap_or_else(empty_account);
+            let code = (account.code_hash != base_account.code_hash).then_some(entry.code);
+
+            if base.is_none()
+                || account.nonce != base_account.nonce
+                || account.balance != base_account.balance
+                || code.is_some()
+                || !storage.is_empty()
+            {
+                changes.push(Apply::Modify {
+                    address,
+                    basic: Basic {
+                        balance: account.balance,
+                        nonce:   account.nonce,
+                    },
+                    code,
+                    storage,
+                    reset_storage: false,
+                });
+            }
+        }
+
+        adapter.apply(changes, Vec::new(), false);
+    }
+
+    fn account(&self, address: &H160) -> Option<&ScratchAccount> {
+        let entry = self.accounts.get(address);
+        if entry.is_none() {
+            self.escaped.set(true);
+        }
+        entry
+    }
+
+    fn account_mut(&mut self, address: &H160) -> Option<&mut ScratchAccount> {
+        let entry = self.accounts.get_mut(address);
+        if entry.is_none() {
+            self.escaped.set(true);
+        }
+        entry
+    }
+}
+
+impl ExecutorReadOnlyAdapter for ScratchAdapter {
+    fn get_ctx(&self) -> ExecutorContext {
+        self.ctx.clone()
+    }
+
+    fn get(&self, _key: &[u8]) -> Option<Bytes> {
+        self.escaped.set(true);
+        None
+    }
+
+    // The storage root of the returned account is not updated by the changes
+    // in the scratch, it is never used while executing a transaction.
+    fn get_account(&self, address: &H160) -> Account {
+        self.account(address)
+            .and_then(|entry| entry.account.clone())
+            .unwrap_or_else(empty_account)
+    }
+}
+
+impl Backend for ScratchAdapter {
+    fn gas_price(&self) -> U256 {
+        self.vicinity.gas_price
+    }
+
+    fn origin(&self) -> H160 {
+        self.vicinity.origin
+    }
+
+    fn block_hash(&self, _number: U256) -> H256 {
+        self.escaped.set(true);
+        H256::default()
+    }
+
+    fn block_number(&self) -> U256 {
+        self.vicinity.block_number
+    }
+
+    fn block_coinbase(&self) -> H160 {
+        self.vicinity.block_coinbase
+    }
+
+    fn block_timestamp(&self) -> U256 {
+        self.vicinity.block_timestamp
+    }
+
+    fn block_difficulty(&self) -> U256 {
+        self.vicinity.block_difficulty
+    }
+
+    fn block_gas_limit(&self) -> U256 {
+        self.vicinity.block_gas_limit
+    }
+
+    fn block_base_fee_per_gas(&self) -> U256 {
+        self.vicinity.block_base_fee_per_gas
+    }
+
+    fn chain_id(&self) -> U256 {
+        self.vicinity.chain_id
+    }
+
+    fn exists(&self, address: H160) -> bool {
+        self.account(&address)
+            .map(|entry| entry.account.is_some())
+            .unwrap_or_default()
+    }
+
+    fn basic(&self, address: H160) -> Basic {
+        self.account(&address)
+            .and_then(|entry| entry.account.as_ref())
+            .map(|account| Basic {
+                balance: account.balance,
+                nonce:   account.nonce,
+            })
+            .unwrap_or_default()
+    }
+
+    fn code(&self, address: H160) -> Vec<u8> {
+        self.account(&address)
+            .map(|entry| entry.code.clone())
+            .unwrap_or_default()
+    }
+
+    fn storage(&self, address: H160, index: H256) -> H256 {
+        let entry = match self.account(&address) {
+            Some(entry) if entry.account.is_some() => entry,
+            _ => return H256::default(),
+        };
+
+        match entry.storage.get(&index) {
+            Some(value) => *value,
+            None => {
+                if !entry.cleared {
+                    self.escaped.set(true);
+                }
+                H256::default()
+            }
+        }
+    }
+
+    fn original_storage(&self, address: H160, index: H256) -> Option<H256> {
+        Some(self.storage(address, index))
+    }
+}
+
+impl ApplyBackend for ScratchAdapter {
+    fn apply<A, I, L>(&mut self, values: A, logs: L, delete_empty: bool)
+    where
+        A: IntoIterator<Item = Apply<I>>,
+        I: IntoIterator<Item = (H256, H256)>,
+        L: IntoIterator<Item = Log>,
+    {
+        for apply in values.into_iter() {
+            match apply {
+                Apply::Modify {
+                    address,
+                    basic,
+                    code,
+                    storage,
+                    reset_storage,
+                } => {
+                    self.touched.insert(address);
+                    let entry = match self.account_mut(&address) {
+                        Some(entry) => entry,
+                        None => continue,
+                    };
+                    let old_account = entry.account.clone().unwrap_or_else(empty_account);
+
+                    if reset_storage {
+                        entry.clear();
+                    }
+                    entry.storage.extend(storage);
+
+                    let mut new_account = Account {
+                        nonce:        basic.nonce,
+                        balance:      basic.balance,
+                        storage_root: old_account.storage_root,
+                        code_hash:    old_account.code_hash,
+                    };
+
+                    if let Some(c) = code {
+                        let new_code_hash = Hasher::digest(&c);
+                        if new_code_hash != old_account.code_hash {
+                            new_account.code_hash = new_code_hash;
+                            entry.code = c;
+                        }
+                    }
+
+                    // The same emptiness check as the apply adapter.
+                    let is_empty = new_account.balance == U256::zero()
+                        && new_account.nonce == U256::zero()
+                        && new_account.code_hash.is_zero();
+                    entry.account = Some(new_account);
+
+                    if is_empty && delete_empty {
+                        entry.account = None;
+                        entry.code = Vec::new();
+                        entry.clear();
+                    }
+                }
+                Apply::Delete { address } => {
+                    self.touched.insert(address);
+                    if let Some(entry) = self.account_mut(&address) {
+                        entry.account = None;
+                        entry.code = Vec::new();
+                        entry.clear();
+                    }
+                }
+            }
+        }
+
+        self.logs = logs.into_iter().collect::<Vec<_>>();
+    }
+}
+
+impl ExecutorAdapter for ScratchAdapter {
+    fn set_origin(&mut self, origin: H160) {
+        self.vicinity.origin = origin;
+    }
+
+    fn set_gas_price(&mut self, gas_price: U256) {
+        self.vicinity.gas_price = gas_price;
+    }
+
+    fn save_account(&mut self, address: &H160, account: &Account) {
+        self.touched.insert(*address);
+        if let Some(entry) = self.account_mut(address) {
+            entry.account = Some(account.clone());
+        }
+    }
+
+    fn commit(&mut self) -> MerkleRoot {
+        unreachable!("the scratch is merged into the adapter rather than committed")
+    }
+
+    fn reset(&mut self, _state_root: MerkleRoot) {
+        unreachable!("the scratch is merged into the adapter rather than reset")
+    }
+
+    fn snapshot(&mut self) -> SnapshotId {
+        unreachable!("the scratch is discarded as a whole rather than reverted")
+    }
+
+    fn revert_to(&mut self, _id: SnapshotId) {
+        unreachable!("the scratch is discarded as a whole rather than reverted")
+    }
+
+    fn take_logs(&mut self) -> Vec<Log> {
+        mem::take(&mut self.logs)
+    }
+
+    fn take_touched_accounts(&mut self) -> Vec<H160> {
+        mem::take(&mut self.touched).into_iter().collect()
+    }
+}
@@ -1,8 +1,18 @@
 mod backend;
 mod trie;
 
-pub use backend::{apply::AxonExecutorApplyAdapter, read_only::AxonExecutorReadOnlyAdapter};
-pub use trie::{db::RocksTrieDB, wrapped::MPTTrie};
+pub(crate) use backend::scratch::ScratchAdapter;
+pub use backend::{
+    apply::AxonExecutorApplyAdapter,
+    genesis::GenesisBuilder,
+    read_only::{AxonExecutorReadOnlyAdapter, NonceCheck},
+};
+pub use trie::{
+    db::{RocksTrieDB, RocksTrieDBSnapshot},
+    diff::{state_diff, AccountDiff, DiffKind},
+    overlay::OverlayTrieDB,
+    wrapped::MPTTrie,
+};
 
 #[macro_export]
 macro_rules! blocking_async {
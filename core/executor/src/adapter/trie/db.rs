@@ -1,20 +1,20 @@
-use std::{collections::HashMap, io, sync::Arc};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::{io, sync::Arc};
 
-use parking_lot::RwLock;
-use rocksdb::ops::{GetCF, GetColumnFamilys, PutCF, WriteOps};
-use rocksdb::{ColumnFamily, WriteBatch, DB};
+use lru::LruCache;
+use parking_lot::Mutex;
+use rocksdb::ops::{GetCF, GetColumnFamilys, PutCF, SnapshotOps, WriteOps};
+use rocksdb::{ColumnFamily, Snapshot, WriteBatch, DB};
 
 use common_apm::metrics::storage::{on_storage_get_state, on_storage_put_state};
 use common_apm::Instant;
-use protocol::rand::{rngs::SmallRng, Rng, SeedableRng};
 use protocol::traits::StateStorageCategory;
 use protocol::trie;
+use protocol::types::H256;
 
 use core_db::map_category;
 
-// 49999 is the largest prime number within 50000.
-const RAND_SEED: u64 = 49999;
-
 macro_rules! db {
     ($db:expr, $op:ident, $column:expr$ (, $args: expr)*) => {
         $db.$op($column, $($args,)*).map_err(|e| {
@@ -27,41 +27,45 @@ macro_rules! db {
 }
 
 pub struct RocksTrieDB {
-    db:         Arc<DB>,
-    category:   StateStorageCategory,
-    cache:      RwLock<HashMap<Vec<u8>, Vec<u8>>>,
-    cache_size: usize,
+    db:           Arc<DB>,
+    category:     StateStorageCategory,
+    /// The LRU cache of the trie nodes keyed by the node hash.
+    cache:        Mutex<LruCache<H256, Arc<Vec<u8>>>>,
+    cache_hits:   AtomicU64,
+    cache_misses: AtomicU64,
 }
 
 impl trie::DB for RocksTrieDB {
     fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, io::Error> {
-        let res = { self.cache.read().get(key).cloned() };
-
-        if res.is_none() {
-            let inst = Instant::now();
-            let ret = db!(self.db, get_cf, self.get_column(), key);
-            on_storage_get_state(inst.elapsed(), 1.0);
+        let cache_key = cache_key(key);
+        if let Some(val) = cache_key.and_then(|k| self.cache.lock().get(&k).cloned()) {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(Some(val.to_vec()));
+        }
 
-            if let Some(val) = &ret {
-                {
-                    self.cache.write().insert(key.to_owned(), val.to_vec());
-                }
-                self.flush()?;
-            }
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+        let inst = Instant::now();
+        let ret = db!(self.db, get_cf, self.get_column(), key);
+        on_storage_get_state(inst.elapsed(), 1.0);
 
-            return Ok(ret.map(|r| r.to_vec()));
+        let ret = ret.map(|r| r.to_vec());
+        if let (Some(k), Some(val)) = (cache_key, &ret) {
+            self.cache.lock().put(k, Arc::new(val.clone()));
         }
 
-        Ok(res)
+        Ok(ret)
     }
 
     fn contains(&self, key: &[u8]) -> Result<bool, io::Error> {
-        let res = { self.cache.read().contains_key(key) };
+        let cache_key = cache_key(key);
+        if cache_key.map_or(false, |k| self.cache.lock().contains(&k)) {
+            return Ok(true);
+        }
 
-        if res {
-            Ok(true)
-        } else if let Some(val) = db!(self.db, get_cf, self.get_column(), key) {
-            self.cache.write().insert(key.to_owned(), val.to_vec());
+        if let Some(val) = db!(self.db, get_cf, self.get_column(), key) {
+            if let Some(k) = cache_key {
+                self.cache.lock().put(k, Arc::new(val.to_vec()));
+            }
             Ok(true)
         } else {
             Ok(false)
@@ -74,12 +78,12 @@ impl trie::DB for RocksTrieDB {
 
         db!(self.db, put_cf, self.get_column(), &key, &value);
 
-        {
-            self.cache.write().insert(key, value);
+        if let Some(k) = cache_key(&key) {
+            self.cache.lock().put(k, Arc::new(value));
         }
 
         on_storage_put_state(inst.elapsed(), size as f64);
-        self.flush()
+        Ok(())
     }
 
     fn insert_batch(&self, keys: Vec<Vec<u8>>, values: Vec<Vec<u8>>) -> Result<(), io::Error> {
@@ -94,14 +98,16 @@ impl trie::DB for RocksTrieDB {
         let mut batch = WriteBatch::default();
 
         {
-            let mut cache = self.cache.write();
+            let mut cache = self.cache.lock();
             for (key, val) in keys.into_iter().zip(values.into_iter()) {
                 total_size += key.len();
                 total_size += val.len();
 
                 let column = self.get_column();
                 db!(batch, put_cf, column, &key, &val);
-                cache.insert(key, val);
+                if let Some(k) = cache_key(&key) {
+                    cache.put(k, Arc::new(val));
+                }
             }
         }
 
@@ -111,7 +117,7 @@ impl trie::DB for RocksTrieDB {
             .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("rocksdb error: {:?}", e)))?;
         on_storage_put_state(inst.elapsed(), total_size as f64);
 
-        self.flush()
+        Ok(())
     }
 
     fn remove(&self, _key: &[u8]) -> Result<(), io::Error> {
@@ -123,23 +129,6 @@ impl trie::DB for RocksTrieDB {
     }
 
     fn flush(&self) -> Result<(), io::Error> {
-        let mut cache = self.cache.write();
-
-        let len = cache.len();
-
-        if len <= self.cache_size * 2 {
-            return Ok(());
-        }
-
-        let remove_list = {
-            let keys = cache.iter().map(|(k, _)| k).collect::<Vec<_>>();
-            rand_remove_list(keys, len - self.cache_size)
-        };
-
-        for item in remove_list {
-            cache.remove(&item);
-        }
-
         Ok(())
     }
 }
@@ -158,35 +147,170 @@ impl RocksTrieDB {
     }
 
     fn new(db: Arc<DB>, category: StateStorageCategory, cache_size: usize) -> Self {
-        let cache = RwLock::new(HashMap::with_capacity(cache_size));
+        let cache_size = NonZeroUsize::new(cache_size).unwrap_or(NonZeroUsize::MIN);
         RocksTrieDB {
             db,
             category,
-            cache,
-            cache_size,
+            cache: Mutex::new(LruCache::new(cache_size)),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The number of the node reads served by the cache.
+    pub fn cache_hits(&self) -> u64 {
+        self.cache_hits.load(Ordering::Relaxed)
+    }
+
+    /// The number of the node reads that fall through to the RocksDB.
+    pub fn cache_misses(&self) -> u64 {
+        self.cache_misses.load(Ordering::Relaxed)
+    }
+
+    /// Capture a consistent point-in-time view of the database, the writes
+    /// after the snapshot is created are invisible to it. The snapshot reads
+    /// the RocksDB directly without the cache of this `RocksTrieDB`.
+    ///
+    /// The snapshot borrows the database and pins the data of that point in
+    /// RocksDB, which can not be compacted until the snapshot is dropped. So
+    /// it should be dropped as soon as the reading is done.
+    pub fn snapshot(&self) -> RocksTrieDBSnapshot<'_> {
+        RocksTrieDBSnapshot {
+            db:       &self.db,
+            snapshot: self.db.snapshot(),
+            category: self.category,
         }
     }
 
     fn get_column(&self) -> &ColumnFamily {
-        let category = map_category(self.category.into());
-        self.db
-            .cf_handle(category)
-            .unwrap_or_else(|| panic!("Column Family {:?} not found", category))
+        get_column(&self.db, self.category)
     }
 }
 
-fn rand_remove_list<T: Clone>(keys: Vec<&T>, num: usize) -> impl Iterator<Item = T> {
-    let mut len = keys.len() - 1;
-    let mut idx_list = (0..len).collect::<Vec<_>>();
-    let mut rng = SmallRng::seed_from_u64(RAND_SEED);
-    let mut ret = Vec::with_capacity(num);
+/// A read-only snapshot of [`RocksTrieDB`], which is created by
+/// [`RocksTrieDB::snapshot`]. All the write operations return an error.
+pub struct RocksTrieDBSnapshot<'a> {
+    db:       &'a DB,
+    snapshot: Snapshot<'a>,
+    category: StateStorageCategory,
+}
 
-    for _ in 0..num {
-        let tmp = rng.gen_range(0, len);
-        let idx = idx_list.remove(tmp);
-        ret.push(keys[idx].clone());
-        len -= 1;
+impl<'a> trie::DB for RocksTrieDBSnapshot<'a> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, io::Error> {
+        let inst = Instant::now();
+        let ret = db!(
+            self.snapshot,
+            get_cf,
+            get_column(self.db, self.category),
+            key
+        );
+        on_storage_get_state(inst.elapsed(), 1.0);
+
+        Ok(ret.map(|r| r.to_vec()))
     }
 
-    ret.into_iter()
+    fn contains(&self, key: &[u8]) -> Result<bool, io::Error> {
+        Ok(self.get(key)?.is_some())
+    }
+
+    fn insert(&self, _key: Vec<u8>, _value: Vec<u8>) -> Result<(), io::Error> {
+        Err(read_only_error())
+    }
+
+    fn insert_batch(&self, _keys: Vec<Vec<u8>>, _values: Vec<Vec<u8>>) -> Result<(), io::Error> {
+        Err(read_only_error())
+    }
+
+    fn remove(&self, _key: &[u8]) -> Result<(), io::Error> {
+        Err(read_only_error())
+    }
+
+    fn remove_batch(&self, _keys: &[Vec<u8>]) -> Result<(), io::Error> {
+        Err(read_only_error())
+    }
+
+    fn flush(&self) -> Result<(), io::Error> {
+        Ok(())
+    }
+}
+
+fn get_column(db: &DB, category: StateStorageCategory) -> &ColumnFamily {
+    let category = map_category(category.into());
+    db.cf_handle(category)
+        .unwrap_or_else(|| panic!("Column Family {:?} not found", category))
+}
+
+fn cache_key(key: &[u8]) -> Option<H256> {
+    (key.len() == H256::len_bytes()).then(|| H256::from_slice(key))
+}
+
+fn read_only_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "rocksdb snapshot is read-only")
+}
+
+#[cfg(test)]
+mod tests {
+    use core_db::RocksAdapter;
+    use protocol::trie::{Trie, DB as TrieDB};
+
+    use super::*;
+    use crate::adapter::MPTTrie;
+
+    #[test]
+    fn test_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner_db =
+            Arc::new(RocksAdapter::new(dir.path(), Default::default()).unwrap()).inner_db();
+        let db = RocksTrieDB::new_evm(inner_db, 100);
+
+        db.insert(vec![1], vec![1]).unwrap();
+        let snapshot = db.snapshot();
+        db.insert(vec![2], vec![2]).unwrap();
+
+        assert_eq!(snapshot.get(&[1]).unwrap(), Some(vec![1]));
+        assert!(snapshot.get(&[2]).unwrap().is_none());
+        assert!(!snapshot.contains(&[2]).unwrap());
+        assert!(snapshot.insert(vec![3], vec![3]).is_err());
+        assert_eq!(db.get(&[2]).unwrap(), Some(vec![2]));
+
+        drop(snapshot);
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn test_node_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner_db =
+            Arc::new(RocksAdapter::new(dir.path(), Default::default()).unwrap()).inner_db();
+
+        let mut mpt = MPTTrie::new(Arc::new(RocksTrieDB::new_evm(Arc::clone(&inner_db), 10)));
+        let keys = (0u32..1000)
+            .map(|i| i.to_be_bytes().to_vec())
+            .collect::<Vec<_>>();
+        for key in keys.iter() {
+            mpt.insert(key.clone(), key.clone()).unwrap();
+        }
+        let root = mpt.commit().unwrap();
+
+        // Read all the keys twice from a deep trie, the hot nodes near the root
+        // are served by the cache.
+        let read_twice = |cache_size: usize| {
+            let db = Arc::new(RocksTrieDB::new_evm(Arc::clone(&inner_db), cache_size));
+            let mpt = MPTTrie::from_root(root, Arc::clone(&db)).unwrap();
+            for _ in 0..2 {
+                for key in keys.iter() {
+                    assert_eq!(mpt.get(key).unwrap().as_ref(), Some(key));
+                }
+            }
+            (db.cache_hits(), db.cache_misses())
+        };
+
+        let (small_hits, small_misses) = read_twice(1);
+        let (large_hits, large_misses) = read_twice(10_000);
+        assert!(large_misses < small_misses);
+        assert!(large_hits > small_hits);
+        assert_eq!(small_hits + small_misses, large_hits + large_misses);
+
+        dir.close().unwrap();
+    }
 }
@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+
+use rlp::Rlp;
+
+use protocol::codec::ProtocolCodec;
+use protocol::trie::DB as TrieDB;
+use protocol::types::{Account, MerkleRoot, H256, NIL_DATA, RLP_NULL, U256};
+use protocol::ProtocolResult;
+
+use crate::ExecutorError;
+
+/// How an account differs between two state roots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// The field-level deltas of an account between two state roots, each of
+/// which is `(before, after)` and `None` if the field is unchanged. An added
+/// or removed account is compared with an empty account.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountDiff {
+    pub kind:         DiffKind,
+    pub balance:      Option<(U256, U256)>,
+    pub nonce:        Option<(U256, U256)>,
+    pub code_hash:    Option<(H256, H256)>,
+    pub storage_root: Option<(H256, H256)>,
+}
+
+impl AccountDiff {
+    fn new(before: Option<Account>, after: Option<Account>) -> Self {
+        let kind = match (&before, &after) {
+            (None, _) => DiffKind::Added,
+            (_, None) => DiffKind::Removed,
+            _ => DiffKind::Changed,
+        };
+        let (before, after) = (
+            before.unwrap_or_else(empty_account),
+            after.unwrap_or_else(empty_account),
+        );
+
+        AccountDiff {
+            kind,
+            balance: delta(before.balance, after.balance),
+            nonce: delta(before.nonce, after.nonce),
+            code_hash: delta(before.code_hash, after.code_hash),
+            storage_root: delta(before.storage_root, after.storage_root),
+        }
+    }
+}
+
+/// Compare the account tries of two state roots and report the accounts which
+/// are added, removed or changed, which helps to find where the states of two
+/// nodes diverge. The subtrees with the same node hash are skipped, so only
+/// the nodes on the paths to the changed accounts are loaded.
+///
+/// The accounts are keyed by the hash of the address since the account trie
+/// holds no preimage of its keys, and they are sorted by the key.
+pub fn state_diff<DB: TrieDB>(
+    db: &DB,
+    root_a: MerkleRoot,
+    root_b: MerkleRoot,
+) -> ProtocolResult<Vec<(H256, AccountDiff)>> {
+    let (node_a, node_b) = (root_ref(db, root_a)?, root_ref(db, root_b)?);
+    let mut changes = BTreeMap::new();
+    diff_node(db, node_a, node_b, &mut Vec::new(), &mut changes)?;
+
+    changes
+        .into_iter()
+        .map(|(nibbles, (before, after))| {
+            if nibbles.len() != 64 {
+                return Err(ExecutorError::InvalidTrieNode(format!(
+                    "key of {} nibbles",
+                    nibbles.len()
+                ))
+                .into());
+            }
+            let key = H256::from_slice(&pack_nibbles(&nibbles));
+            let before = before.map(Account::decode).transpose()?;
+            let after = after.map(Account::decode).transpose()?;
+            Ok((key, AccountDiff::new(before, after)))
+        })
+        .collect()
+}
+
+type Changes = BTreeMap<Vec<u8>, (Option<Vec<u8>>, Option<Vec<u8>>)>;
+
+/// A reference to a trie node, which is either stored by its hash or inlined
+/// in its parent if the encoding is shorter than a hash.
+enum NodeRef {
+    Empty,
+    Hash(H256),
+    Inline(Vec<u8>),
+    Virtual(Box<Node>),
+}
+
+enum Node {
+    Leaf(Vec<u8>, Vec<u8>),
+    Extension(Vec<u8>, NodeRef),
+    Branch(Vec<NodeRef>, Option<Vec<u8>>),
+}
+
+fn root_ref<DB: TrieDB>(db: &DB, root: MerkleRoot) -> ProtocolResult<NodeRef> {
+    if root == RLP_NULL {
+        return Ok(NodeRef::Empty);
+    }
+    if !db
+        .contains(root.as_bytes())
+        .map_err(|e| ExecutorError::ReadTrieNode {
+            hash:   root,
+            reason: e.to_string(),
+        })?
+    {
+        return Err(ExecutorError::StateRootUnavailable(root).into());
+    }
+    Ok(NodeRef::Hash(root))
+}
+
+fn diff_node<DB: TrieDB>(
+    db: &DB,
+    a: NodeRef,
+    b: NodeRef,
+    path: &mut Vec<u8>,
+    changes: &mut Changes,
+) -> ProtocolResult<()> {
+    match (&a, &b) {
+        (NodeRef::Empty, NodeRef::Empty) => return Ok(()),
+        (NodeRef::Hash(x), NodeRef::Hash(y)) if x == y => return Ok(()),
+        (NodeRef::Inline(x), NodeRef::Inline(y)) if x == y => return Ok(()),
+        _ => (),
+    }
+
+    // Both nodes are expanded to a branch one nibble deeper, so the tries of
+    // different shapes are walked in the same steps.
+    let (children_a, value_a) = expand(db, a)?;
+    let (children_b, value_b) = expand(db, b)?;
+
+    if value_a != value_b {
+        changes.insert(path.clone(), (value_a, value_b));
+    }
+
+    for (nibble, (a, b)) in children_a.into_iter().zip(children_b).enumerate() {
+        path.push(nibble as u8);
+        diff_node(db, a, b, path, changes)?;
+        path.pop();
+    }
+
+    Ok(())
+}
+
+fn expand<DB: TrieDB>(db: &DB, node: NodeRef) -> ProtocolResult<(Vec<NodeRef>, Option<Vec<u8>>)> {
+    let mut children = empty_children();
+    let node = match node {
+        NodeRef::Empty => return Ok((children, None)),
+        NodeRef::Hash(hash) => {
+            let raw = db
+                .get(hash.as_bytes())
+                .map_err(|e| e.to_string())
+                .and_then(|raw| raw.ok_or_else(|| "missing".to_string()))
+                .map_err(|reason| ExecutorError::ReadTrieNode { hash, reason })?;
+            decode_node(&raw)?
+        }
+        NodeRef::Inline(raw) => decode_node(&raw)?,
+        NodeRef::Virtual(node) => *node,
+    };
+
+    match node {
+        Node::Branch(children, value) => return Ok((children, value)),
+        Node::Leaf(path, value) if path.is_empty() => return Ok((children, Some(value))),
+        Node::Leaf(mut path, value) => {
+            let nibble = path.remove(0);
+            children[nibble as usize] = NodeRef::Virtual(Box::new(Node::Leaf(path, value)));
+        }
+        Node::Extension(path, child) if path.is_empty() => return expand(db, child),
+        Node::Extension(mut path, child) => {
+            let nibble = path.remove(0);
+            children[nibble as usize] = if path.is_empty() {
+                child
+            } else {
+                NodeRef::Virtual(Box::new(Node::Extension(path, child)))
+            };
+        }
+    }
+
+    Ok((children, None))
+}
+
+fn decode_node(raw: &[u8]) -> ProtocolResult<Node> {
+    let rlp = Rlp::new(raw);
+    let node = match rlp.item_count().map_err(invalid_node)? {
+        2 => {
+            let (path, is_leaf) = decode_path(rlp.at(0).and_then(|r| r.data()))?;
+            if is_leaf {
+                let value = rlp.at(1).and_then(|r| r.data()).map_err(invalid_node)?;
+                Node::Leaf(path, value.to_vec())
+            } else {
+                Node::Extension(path, decode_ref(&rlp, 1)?)
+            }
+        }
+        17 => {
+            let children = (0..16)
+                .map(|i| decode_ref(&rlp, i))
+                .collect::<ProtocolResult<Vec<_>>>()?;
+            let value = rlp.at(16).and_then(|r| r.data()).map_err(invalid_node)?;
+            Node::Branch(children, (!value.is_empty()).then(|| value.to_vec()))
+        }
+        count => {
+            return Err(ExecutorError::InvalidTrieNode(format!("node of {count} items")).into())
+        }
+    };
+
+    Ok(node)
+}
+
+fn decode_ref(rlp: &Rlp, index: usize) -> ProtocolResult<NodeRef> {
+    let item = rlp.at(index).map_err(invalid_node)?;
+    if item.is_list() {
+        return Ok(NodeRef::Inline(item.as_raw().to_vec()));
+    }
+
+    match item.data().map_err(invalid_node)? {
+        [] => Ok(NodeRef::Empty),
+        hash if hash.len() == 32 => Ok(NodeRef::Hash(H256::from_slice(hash))),
+        _ => Err(ExecutorError::InvalidTrieNode("invalid child reference".to_string()).into()),
+    }
+}
+
+/// Decode the hex-prefix encoded path of a leaf or an extension node into
+/// nibbles, and whether the node is a leaf.
+fn decode_path(data: Result<&[u8], rlp::DecoderError>) -> ProtocolResult<(Vec<u8>, bool)> {
+    let data = data.map_err(invalid_node)?;
+    let (first, rest) = data
+        .split_first()
+        .ok_or_else(|| ExecutorError::InvalidTrieNode("empty path".to_string()))?;
+    let flag = first >> 4;
+
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    for byte in rest {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+
+    Ok((nibbles, flag & 2 == 2))
+}
+
+fn invalid_node(e: rlp::DecoderError) -> ExecutorError {
+    ExecutorError::InvalidTrieNode(e.to_string())
+}
+
+fn pack_nibbles(nibbles: &[u8]) -> Vec<u8> {
+    nibbles.chunks(2).map(|n| (n[0] << 4) | n[1]).collect()
+}
+
+fn empty_children() -> Vec<NodeRef> {
+    (0..16).map(|_| NodeRef::Empty).collect()
+}
+
+fn empty_account() -> Account {
+    Account {
+        nonce:        U256::zero(),
+        balance:      U256::zero(),
+        storage_root: RLP_NULL,
+        code_hash:    NIL_DATA,
+    }
+}
+
+fn delta<T: PartialEq>(before: T, after: T) -> Option<(T, T)> {
+    (before != after).then_some((before, after))
+}
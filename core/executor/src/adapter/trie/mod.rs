@@ -1,2 +1,4 @@
 pub mod db;
+pub mod diff;
+pub mod overlay;
 pub mod wrapped;
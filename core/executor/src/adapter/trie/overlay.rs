@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::{io, sync::Arc};
+
+use parking_lot::RwLock;
+
+use protocol::trie;
+
+/// A trie database layered over another one, whose reads fall through to the
+/// inner database while the writes are kept in memory. The state root of a
+/// block can be calculated over it without persisting any trie node, and the
+/// written nodes are dropped along with it.
+pub struct OverlayTrieDB<DB> {
+    db:      Arc<DB>,
+    overlay: RwLock<HashMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl<DB: trie::DB> trie::DB for OverlayTrieDB<DB> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, io::Error> {
+        if let Some(val) = self.overlay.read().get(key) {
+            return Ok(Some(val.clone()));
+        }
+
+        self.db.get(key)
+    }
+
+    fn contains(&self, key: &[u8]) -> Result<bool, io::Error> {
+        if self.overlay.read().contains_key(key) {
+            return Ok(true);
+        }
+
+        self.db.contains(key)
+    }
+
+    fn insert(&self, key: Vec<u8>, value: Vec<u8>) -> Result<(), io::Error> {
+        self.overlay.write().insert(key, value);
+        Ok(())
+    }
+
+    fn insert_batch(&self, keys: Vec<Vec<u8>>, values: Vec<Vec<u8>>) -> Result<(), io::Error> {
+        if keys.len() != values.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "keys and values length not match",
+            ));
+        }
+
+        self.overlay.write().extend(keys.into_iter().zip(values));
+        Ok(())
+    }
+
+    // The nodes are keyed by their hashes and may be shared, so they are never
+    // removed the same as `RocksTrieDB`.
+    fn remove(&self, _key: &[u8]) -> Result<(), io::Error> {
+        Ok(())
+    }
+
+    fn remove_batch(&self, _keys: &[Vec<u8>]) -> Result<(), io::Error> {
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), io::Error> {
+        Ok(())
+    }
+}
+
+impl<DB> OverlayTrieDB<DB> {
+    pub fn new(db: Arc<DB>) -> Self {
+        OverlayTrieDB {
+            db,
+            overlay: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use protocol::trie::{MemoryDB, DB as _};
+
+    #[test]
+    fn test_overlay() {
+        let db = Arc::new(MemoryDB::new(false));
+        db.insert(vec![1], vec![1]).unwrap();
+
+        let overlay = OverlayTrieDB::new(Arc::clone(&db));
+        overlay.insert_batch(vec![vec![2]], vec![vec![2]]).unwrap();
+        assert_eq!(overlay.get(&[1]).unwrap(), Some(vec![1]));
+        assert_eq!(overlay.get(&[2]).unwrap(), Some(vec![2]));
+        assert!(overlay.contains(&[2]).unwrap());
+
+        // Nothing is written to the inner database.
+        assert!(db.get(&[2]).unwrap().is_none());
+        assert!(!db.contains(&[2]).unwrap());
+    }
+}
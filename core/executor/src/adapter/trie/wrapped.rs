@@ -57,6 +57,14 @@ impl<DB: TrieDB> MPTTrie<DB> {
         )?))
     }
 
+    /// Iterate all the key-value pairs in the trie in the ascending order of
+    /// the keys. Note that the keys are the hashes of the inserted keys since
+    /// they are hashed before inserting. The nodes are loaded from the database
+    /// one by one while iterating, so the whole trie is never held in memory.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec<u8>, Vec<u8>)> + '_ {
+        self.0.iter()
+    }
+
     pub fn commit(&mut self) -> ProtocolResult<MerkleRoot> {
         self.0
             .root()
@@ -106,4 +114,31 @@ mod tests {
 
         dir.close().unwrap();
     }
+
+    #[test]
+    fn test_mpt_iter() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner_db =
+            Arc::new(RocksAdapter::new(dir.path(), Default::default()).unwrap()).inner_db();
+        let db = Arc::new(RocksTrieDB::new_evm(inner_db, 100));
+        let mut mpt = MPTTrie::new(Arc::clone(&db));
+
+        let mut expect = (0..20)
+            .map(|_| (rand_bytes(8), rand_bytes(16)))
+            .map(|(k, v)| {
+                mpt.insert(k.clone(), v.clone()).unwrap();
+                (Hasher::digest(k).0.to_vec(), v)
+            })
+            .collect::<Vec<_>>();
+        expect.sort();
+        let root = mpt.commit().unwrap();
+
+        assert_eq!(mpt.iter().collect::<Vec<_>>(), expect);
+
+        // The nodes are loaded from the database while iterating.
+        let mpt = MPTTrie::from_root(root, db).unwrap();
+        assert_eq!(mpt.iter().collect::<Vec<_>>(), expect);
+
+        dir.close().unwrap();
+    }
 }
@@ -96,8 +96,9 @@ impl EvmDebugger {
             origin:                 H160::random(),
             gas_price:              1u64.into(),
             block_gas_limit:        4294967295000u64.into(),
-            block_base_fee_per_gas: 1337u64.into(),
+            block_base_fee_per_gas: U256::zero(),
             extra_data:             Default::default(),
+            parent_hash:            Default::default(),
         };
 
         AxonExecutorApplyAdapter::from_root(
@@ -124,12 +125,12 @@ impl EventListener for EvmListener {
 }
 
 pub fn mock_signed_tx(tx: Eip1559Transaction, sender: H160) -> SignedTransaction {
-    let utx = UnverifiedTransaction {
-        unsigned:  UnsignedTransaction::Eip1559(tx),
-        hash:      Hash::default(),
-        chain_id:  Some(5u64),
-        signature: None,
-    };
+    let utx = UnverifiedTransaction::new(
+        UnsignedTransaction::Eip1559(tx),
+        None,
+        Some(5u64),
+        Hash::default(),
+    );
 
     SignedTransaction {
         transaction: utx,
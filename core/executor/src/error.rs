@@ -0,0 +1,42 @@
+use thiserror::Error;
+
+use protocol::{
+    types::{H160, H256, U256},
+    ProtocolError, ProtocolErrorKind,
+};
+
+#[derive(Error, Debug)]
+pub enum ExecutorError {
+    #[error("Allocated fee {allocated} exceeds collected fee {collected}")]
+    ExcessiveFeeAllocation { allocated: U256, collected: U256 },
+
+    #[error("Allocated fee overflow")]
+    FeeAllocationOverflow,
+
+    #[error("Transaction {tx_hash:#x} can not be executed: {reason}")]
+    InvalidTransaction { tx_hash: H256, reason: String },
+
+    #[error("Precompile address {0:#x} is reserved")]
+    ReservedPrecompileAddress(H160),
+
+    #[error("State root {0:#x} is unavailable")]
+    StateRootUnavailable(H256),
+
+    #[error("Invalid replay fixture: {0}")]
+    InvalidReplayFixture(String),
+
+    #[error("Replayed state root {actual:#x} differs from the recorded {expect:#x}")]
+    ReplayStateRootMismatch { expect: H256, actual: H256 },
+
+    #[error("Read trie node {hash:#x} failed: {reason}")]
+    ReadTrieNode { hash: H256, reason: String },
+
+    #[error("Invalid trie node: {0}")]
+    InvalidTrieNode(String),
+}
+
+impl From<ExecutorError> for ProtocolError {
+    fn from(error: ExecutorError) -> ProtocolError {
+        ProtocolError::new(ProtocolErrorKind::Executor, Box::new(error))
+    }
+}
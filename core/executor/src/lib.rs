@@ -1,51 +1,178 @@
 pub mod adapter;
 #[cfg(test)]
 mod debugger;
+mod error;
+mod parallel;
 mod precompiles;
+pub mod replay;
 pub mod system_contract;
 #[cfg(test)]
 mod tests;
+#[cfg(any(test, feature = "tracing"))]
+mod tracer;
 mod utils;
 
 pub use crate::adapter::{
-    AxonExecutorApplyAdapter, AxonExecutorReadOnlyAdapter, MPTTrie, RocksTrieDB,
+    state_diff, AccountDiff, AxonExecutorApplyAdapter, AxonExecutorReadOnlyAdapter, DiffKind,
+    GenesisBuilder, MPTTrie, NonceCheck, OverlayTrieDB, RocksTrieDB, RocksTrieDBSnapshot,
+};
+pub use crate::error::ExecutorError;
+pub use crate::precompiles::{
+    PrecompileError, PrecompileRegistry, AXON_PRECOMPILE_BASE, PRECOMPILE_REGISTRY,
 };
 pub use crate::system_contract::{
     is_call_system_script, is_system_contract_address_format,
-    metadata::{MetadataHandle, HARDFORK_INFO},
-    DataProvider,
+    metadata::{MetadataHandle, HARDFORK_INFO, HARDFORK_REGISTRY},
+    system_contract_kind, DataProvider, SystemContractKind,
+};
+#[cfg(any(test, feature = "tracing"))]
+pub use crate::tracer::{NoopTracer, Tracer};
+pub use crate::utils::{
+    code_address, code_address_create2, explain_contract_limit, predicted_create_address,
+    DefaultFeeAllocator, FeeInlet, FeeWeight, WeightedFeeAllocator,
 };
-pub use crate::utils::{code_address, decode_revert_msg, DefaultFeeAllocator, FeeInlet};
+pub use protocol::types::{decode_revert_msg, RevertReason};
 
 use std::cell::RefCell;
 use std::collections::BTreeMap;
+use std::sync::Arc;
+#[cfg(any(test, feature = "tracing"))]
+use std::{cell::Cell, rc::Rc};
 
-use arc_swap::ArcSwap;
+use arc_swap::{ArcSwap, ArcSwapOption};
 use common_config_parser::types::spec::HardforkName;
-use evm::executor::stack::{MemoryStackState, PrecompileFn, StackExecutor, StackSubstateMetadata};
-use evm::CreateScheme;
+use evm::executor::stack::{
+    MemoryStackState, PrecompileFn, StackExecutor, StackState, StackSubstateMetadata,
+};
+use strum::IntoEnumIterator;
 
 use common_merkle::TrieMerkle;
-use protocol::traits::{Backend, Executor, ExecutorAdapter};
+use protocol::traits::{Backend, Executor, ExecutorAdapter, Storage};
 use protocol::types::{
-    logs_bloom, Config, ExecResp, SignedTransaction, TransactionAction, TxResp, ValidatorExtend,
-    H160, H256, RLP_NULL, U256,
+    logs_bloom, normalize_access_list, AccessList, Account, Bytes, Config, ExecResp, ExitError,
+    ExitReason, MerkleRoot, SignedTransaction, TransactionAction, TxResp, ValidatorExtend, H160,
+    H256, MIN_TRANSACTION_GAS_LIMIT, NIL_DATA, RLP_NULL, U256,
 };
+use protocol::{trie, ProtocolResult};
 
-use crate::precompiles::build_precompile_set;
+use crate::precompiles::build_precompile_set_at;
 use crate::system_contract::{
     after_block_hook, before_block_hook, system_contract_dispatch,
     CKB_LIGHT_CLIENT_CONTRACT_ADDRESS, HEADER_CELL_ROOT_KEY, METADATA_CONTRACT_ADDRESS,
     METADATA_ROOT_KEY,
 };
+#[cfg(any(test, feature = "tracing"))]
+use crate::tracer::{AccessListRecorder, GasListener, StepLimitedState, StepLimiter, StepListener};
+use crate::utils::{checked_prepay_gas, deduct_prepay_gas, refund_remaining_gas};
 
 lazy_static::lazy_static! {
     pub static ref FEE_ALLOCATOR: ArcSwap<Box<dyn FeeAllocate>> = ArcSwap::from_pointee(Box::new(DefaultFeeAllocator));
+    /// Override the contract size limit of the EVM config, `Some(None)` means
+    /// unlimited. This is only used for local dev chains and is ignored unless
+    /// the `contract_limit_override` feature is enabled, so the mainnet builds
+    /// always follow the limit in metadata.
+    pub static ref CONTRACT_SIZE_LIMIT_OVERRIDE: ArcSwapOption<Option<usize>> = ArcSwapOption::empty();
+    /// The observer of the block hooks, nothing is observed if it is empty.
+    pub static ref BLOCK_HOOK_OBSERVER: ArcSwapOption<Box<dyn BlockHookObserver>> = ArcSwapOption::empty();
+    static ref CONFIG_CACHE: ConfigCache = ConfigCache::default();
+}
+
+/// The EVM config built for a hardfork at a metadata root. The config of the
+/// Andromeda hardfork reads the consensus config in metadata, so caching it
+/// saves a metadata trie read for each call. The config is rebuilt once the
+/// hardfork or the metadata root changes.
+#[derive(Default)]
+pub(crate) struct ConfigCache {
+    inner: ArcSwapOption<(HardforkName, H256, Config)>,
+}
+
+impl ConfigCache {
+    pub(crate) fn get_or_build<F: FnOnce() -> Config>(
+        &self,
+        name: HardforkName,
+        metadata_root: H256,
+        build: F,
+    ) -> Config {
+        if let Some(cached) = self.inner.load().as_deref() {
+            if cached.0 == name && cached.1 == metadata_root {
+                return cached.2.clone();
+            }
+        }
+
+        let config = build();
+        self.inner
+            .store(Some(Arc::new((name, metadata_root, config.clone()))));
+        config
+    }
 }
 
 thread_local! {
     pub(crate) static CURRENT_HEADER_CELL_ROOT: RefCell<H256> = RefCell::new(H256::default());
     pub(crate) static CURRENT_METADATA_ROOT: RefCell<H256> = RefCell::new(H256::default());
+    pub(crate) static CURRENT_BLOCK_NUMBER: RefCell<u64> = RefCell::new(0);
+    pub(crate) static CURRENT_CHAIN_ID: RefCell<U256> = RefCell::new(U256::zero());
+    pub(crate) static CURRENT_BLOCK_ENV: RefCell<BlockEnv> = RefCell::new(BlockEnv::default());
+}
+
+/// The roots of the system contract stores that the precompiles and the system
+/// contracts read. They are kept in thread-locals, so a thread executing the
+/// transactions other than the one initialized by the executor, such as a
+/// parallel worker, must [`install`](SystemContractRoots::install) them
+/// explicitly before executing, otherwise the default roots are seen.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SystemContractRoots {
+    pub header_cell_root: H256,
+    pub metadata_root:    H256,
+}
+
+impl SystemContractRoots {
+    pub fn from_backend<B: Backend>(backend: &B) -> Self {
+        SystemContractRoots {
+            header_cell_root: backend
+                .storage(CKB_LIGHT_CLIENT_CONTRACT_ADDRESS, *HEADER_CELL_ROOT_KEY),
+            metadata_root:    backend.storage(METADATA_CONTRACT_ADDRESS, *METADATA_ROOT_KEY),
+        }
+    }
+
+    /// The roots installed in the current thread.
+    pub fn current() -> Self {
+        SystemContractRoots {
+            header_cell_root: CURRENT_HEADER_CELL_ROOT.with(|r| *r.borrow()),
+            metadata_root:    CURRENT_METADATA_ROOT.with(|r| *r.borrow()),
+        }
+    }
+
+    /// Install the roots to the current thread.
+    pub fn install(&self) {
+        CURRENT_HEADER_CELL_ROOT.with(|r| *r.borrow_mut() = self.header_cell_root);
+        CURRENT_METADATA_ROOT.with(|r| *r.borrow_mut() = self.metadata_root);
+    }
+}
+
+/// The environment of the block being executed. The precompiles can not access
+/// the backend, so they read it by [`BlockEnv::current`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockEnv {
+    pub number:    u64,
+    pub timestamp: U256,
+    pub coinbase:  H160,
+    pub base_fee:  U256,
+}
+
+impl BlockEnv {
+    pub fn from_backend<B: Backend>(backend: &B) -> Self {
+        BlockEnv {
+            number:    backend.block_number().low_u64(),
+            timestamp: backend.block_timestamp(),
+            coinbase:  backend.block_coinbase(),
+            base_fee:  backend.block_base_fee_per_gas(),
+        }
+    }
+
+    /// The environment of the block being executed in the current thread.
+    pub fn current() -> Self {
+        CURRENT_BLOCK_ENV.with(|env| *env.borrow())
+    }
 }
 
 pub trait FeeAllocate: Sync + Send {
@@ -55,7 +182,46 @@ pub trait FeeAllocate: Sync + Send {
         fee_collect: U256,
         proposer: H160,
         validators: &[ValidatorExtend],
-    ) -> Vec<FeeInlet>;
+    ) -> ProtocolResult<Vec<FeeInlet>>;
+
+    /// Whether the allocator pays inlets not funded by the collected fee, such
+    /// as a fixed block reward. The empty blocks skip the allocation unless
+    /// the allocator returns `true`. The inlets of such an allocator may
+    /// exceed the collected fee, and the excess is minted to the receivers,
+    /// which increases the total supply of the native token.
+    fn allocate_zero_fee(&self) -> bool {
+        false
+    }
+}
+
+/// Observe the system contract hooks around the transactions of a block,
+/// such as the metadata updates and the CKB header sync. The hooks are only
+/// observed when an observer is set to [`BLOCK_HOOK_OBSERVER`].
+pub trait BlockHookObserver: Sync + Send {
+    /// Invoked after the before block hook with the metadata root and the
+    /// header cell root that the transactions are executed with.
+    fn on_before(&self, _block_number: U256, _metadata_root: H256, _header_cell_root: H256) {}
+
+    /// Invoked after the after block hook with the committed state root.
+    fn on_after(&self, _block_number: U256, _new_root: MerkleRoot) {}
+}
+
+/// Whether [`AxonExecutor::exec_streaming`] should go on executing the rest
+/// transactions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExecControl {
+    Continue,
+    Stop,
+}
+
+/// The charge of an EVM transaction which passes
+/// [`AxonExecutor::check_evm_tx`].
+pub(crate) struct EvmCharge {
+    gas_price:  U256,
+    gas_limit:  u64,
+    prepay_gas: U256,
+    /// The sender nonce before executing the transaction.
+    nonce:      U256,
 }
 
 #[derive(Default)]
@@ -72,56 +238,17 @@ impl Executor for AxonExecutor {
         value: U256,
         data: Vec<u8>,
     ) -> TxResp {
-        self.init_local_system_contract_roots(backend);
-        let config = {
-            let mut config = self.config();
-            // run the gasometer in estimate mode
-            config.estimate = true;
-            config
-        };
-        let metadata = StackSubstateMetadata::new(gas_limit, &config);
-        let state = MemoryStackState::new(metadata, backend);
-        let precompiles = build_precompile_set();
-        let mut executor = StackExecutor::new_with_precompiles(state, &config, &precompiles);
-
-        let (exit, res) = if let Some(addr) = &to {
-            executor.transact_call(
-                from.unwrap_or_default(),
-                *addr,
-                value,
-                data,
-                gas_limit,
-                Vec::new(),
-            )
-        } else {
-            executor.transact_create(from.unwrap_or_default(), value, data, gas_limit, Vec::new())
-        };
-
-        let used_gas = executor.used_gas();
-
-        TxResp {
-            exit_reason:  exit,
-            ret:          res,
-            remain_gas:   executor.gas(),
-            gas_used:     used_gas,
-            fee_cost:     backend
-                .gas_price()
-                .checked_mul(used_gas.into())
-                .unwrap_or(U256::max_value()),
-            logs:         vec![],
-            code_address: if to.is_none() {
-                Some(
-                    executor
-                        .create_address(CreateScheme::Legacy {
-                            caller: from.unwrap_or_default(),
-                        })
-                        .into(),
-                )
-            } else {
-                None
-            },
-            removed:      false,
-        }
+        self.inner_call(
+            backend,
+            gas_limit,
+            from,
+            to,
+            value,
+            data,
+            false,
+            None,
+            Vec::new(),
+        )
     }
 
     // Function execute returns exit_reason, ret_data and remain_gas.
@@ -131,78 +258,7 @@ impl Executor for AxonExecutor {
         txs: &[SignedTransaction],
         validators: &[ValidatorExtend],
     ) -> ExecResp {
-        let txs_len = txs.len();
-        let block_number = adapter.block_number();
-        let mut res = Vec::with_capacity(txs_len);
-        let mut encode_receipts = Vec::with_capacity(txs_len);
-        let (mut gas, mut fee) = (0u64, U256::zero());
-        let precompiles = build_precompile_set();
-        self.init_local_system_contract_roots(adapter);
-        let config = self.config();
-
-        // Execute system contracts before block hook.
-        before_block_hook(adapter);
-
-        for tx in txs.iter() {
-            adapter.set_gas_price(tx.transaction.unsigned.gas_price());
-            adapter.set_origin(tx.sender);
-
-            // Execute a transaction, if system contract dispatch return None, means the
-            // transaction called EVM
-            let mut r = system_contract_dispatch(adapter, tx)
-                .unwrap_or_else(|| Self::evm_exec(adapter, &config, &precompiles, tx));
-
-            r.logs = adapter.take_logs();
-            gas += r.gas_used;
-            fee = fee.checked_add(r.fee_cost).unwrap_or(U256::max_value());
-
-            let logs_bloom = logs_bloom(r.logs.iter());
-            let receipt = tx.encode_receipt(&r, logs_bloom);
-            encode_receipts.push(receipt);
-
-            res.push(r);
-        }
-
-        // Allocate collected fee for validators
-        if !block_number.is_zero() {
-            let alloc =
-                (*FEE_ALLOCATOR)
-                    .load()
-                    .allocate(block_number, fee, adapter.origin(), validators);
-
-            for i in alloc.iter() {
-                if !i.amount.is_zero() {
-                    let mut account = adapter.get_account(&i.address);
-                    account.balance += i.amount;
-                    adapter.save_account(&i.address, &account);
-                }
-            }
-        }
-
-        // Execute system contracts after block hook.
-        after_block_hook(adapter);
-
-        // commit changes by all txs included in this block only once
-        let new_state_root = adapter.commit();
-
-        // self.update_system_contract_roots_for_external_module();
-
-        let receipt_root = if encode_receipts.is_empty() {
-            RLP_NULL
-        } else {
-            TrieMerkle::from_receipts(&encode_receipts)
-                .root_hash()
-                .unwrap_or_else(|err| {
-                    panic!("failed to calculate trie root hash for receipts since {err}")
-                })
-        };
-
-        ExecResp {
-            state_root: new_state_root,
-            receipt_root,
-            gas_used: gas,
-            tx_resp: res,
-        }
+        self.exec_streaming(adapter, txs, validators, |_, _| ExecControl::Continue)
     }
 }
 
@@ -223,12 +279,12 @@ fn test_receipt() {
         access_list:              Default::default(),
     };
     let unsigned_tx = UnsignedTransaction::Eip1559(eip1559_tx);
-    let unverified_tx = UnverifiedTransaction {
-        unsigned:  unsigned_tx,
-        signature: Default::default(),
-        chain_id:  Default::default(),
-        hash:      Default::default(),
-    };
+    let unverified_tx = UnverifiedTransaction::new(
+        unsigned_tx,
+        Default::default(),
+        Default::default(),
+        Default::default(),
+    );
     let tx = SignedTransaction {
         transaction: unverified_tx,
         sender:      Default::default(),
@@ -273,53 +329,372 @@ fn test_receipt() {
 
     let encode_receipts = vec![receipt];
 
-    let receipt_root = if encode_receipts.is_empty() {
-        RLP_NULL
-    } else {
-        TrieMerkle::from_receipts(&encode_receipts)
-            .root_hash()
-            .unwrap_or_else(|err| {
-                panic!("failed to calculate trie root hash for receipts since {err}")
-            })
-    };
+    let receipt_root = receipts_root(&encode_receipts);
 
     let reference_root = [
         197u8, 180, 204, 76, 181, 157, 142, 152, 246, 237, 148, 126, 24, 207, 94, 119, 119, 205,
         11, 16, 193, 17, 102, 157, 61, 7, 166, 133, 173, 208, 124, 6,
     ];
     assert_eq!(receipt_root, H256::from(reference_root));
+    assert_eq!(receipts_root(&[]), EMPTY_RECEIPTS_ROOT);
+    assert_eq!(
+        EMPTY_RECEIPTS_ROOT,
+        TrieMerkle::default().root_hash().unwrap()
+    );
 }
 
 impl AxonExecutor {
+    /// The same as [`Executor::call`], but the logs emitted during the call
+    /// are collected into the returned `TxResp`. The state changes are still
+    /// discarded.
+    pub fn call_with_logs<B: Backend>(
+        &self,
+        backend: &B,
+        gas_limit: u64,
+        from: Option<H160>,
+        to: Option<H160>,
+        value: U256,
+        data: Vec<u8>,
+    ) -> TxResp {
+        self.inner_call(
+            backend,
+            gas_limit,
+            from,
+            to,
+            value,
+            data,
+            true,
+            None,
+            Vec::new(),
+        )
+    }
+
+    /// The same as [`Executor::call`], but the call is aborted once more than
+    /// `step_limit` opcodes are executed, which guards the RPC server against
+    /// the payloads spinning the interpreter with a huge gas limit. An aborted
+    /// call fails with [`ExitError::OutOfGas`] and consumes all the gas. It
+    /// requires the `tracing` feature.
+    #[cfg(any(test, feature = "tracing"))]
+    pub fn call_with_step_limit<B: Backend>(
+        &self,
+        backend: &B,
+        gas_limit: u64,
+        from: Option<H160>,
+        to: Option<H160>,
+        value: U256,
+        data: Vec<u8>,
+        step_limit: Option<u64>,
+    ) -> TxResp {
+        self.inner_call(
+            backend,
+            gas_limit,
+            from,
+            to,
+            value,
+            data,
+            false,
+            step_limit,
+            Vec::new(),
+        )
+    }
+
+    /// Estimate the minimal gas limit for a call by binary searching between
+    /// the gas used by the call and `gas_cap`. The failing `TxResp` is
+    /// returned if the call can not succeed even with `gas_cap`.
+    pub fn estimate_gas<B: Backend>(
+        &self,
+        backend: &B,
+        from: Option<H160>,
+        to: Option<H160>,
+        value: U256,
+        data: Vec<u8>,
+        gas_cap: u64,
+    ) -> Result<u64, TxResp> {
+        let resp = self.call(backend, gas_cap, from, to, value, data.clone());
+        if !resp.exit_reason.is_succeed() {
+            return Err(resp);
+        }
+
+        // The gas used by a successful call is a lower bound of the gas limit, so
+        // try it first and fall back to binary search.
+        let mut lo = resp.gas_used.max(MIN_TRANSACTION_GAS_LIMIT) - 1;
+        let mut hi = gas_cap;
+        let mut probe = lo + 1;
+
+        while lo + 1 < hi {
+            if self
+                .call(backend, probe, from, to, value, data.clone())
+                .exit_reason
+                .is_succeed()
+            {
+                hi = probe;
+            } else {
+                lo = probe;
+            }
+
+            probe = lo + (hi - lo) / 2;
+        }
+
+        Ok(hi)
+    }
+
+    /// Generate the access list of a call for `eth_createAccessList`. The call
+    /// is executed once to record the accounts and the storage slots accessed,
+    /// then executed again with the recorded access list to get the gas used
+    /// including the access list cost. The caller, the callee and the
+    /// precompiles are always warm, so they are listed only if their storage
+    /// slots are accessed. It requires the `tracing` feature.
+    #[cfg(any(test, feature = "tracing"))]
+    pub fn create_access_list<B: Backend>(
+        &self,
+        backend: &B,
+        from: Option<H160>,
+        to: Option<H160>,
+        value: U256,
+        data: Vec<u8>,
+        gas_limit: u64,
+    ) -> (AccessList, u64) {
+        let caller = from.unwrap_or_default();
+        let callee =
+            to.unwrap_or_else(|| predicted_create_address(caller, backend.basic(caller).nonce));
+
+        let mut recorder = AccessListRecorder::default();
+        evm_runtime::tracing::using(&mut recorder, || {
+            let data = data.clone();
+            self.inner_call(
+                backend,
+                gas_limit,
+                from,
+                to,
+                value,
+                data,
+                false,
+                None,
+                Vec::new(),
+            )
+        });
+
+        let precompiles =
+            build_precompile_set_at(backend.block_number().low_u64().saturating_add(1));
+        let access_list = recorder.into_access_list(|address| {
+            *address == caller || *address == callee || precompiles.contains_key(address)
+        });
+
+        let resp = self.inner_call(
+            backend,
+            gas_limit,
+            from,
+            to,
+            value,
+            data,
+            false,
+            None,
+            access_list
+                .iter()
+                .map(|item| (item.address, item.storage_keys.clone()))
+                .collect(),
+        );
+        (access_list, resp.gas_used)
+    }
+
+    fn inner_call<B: Backend>(
+        &self,
+        backend: &B,
+        gas_limit: u64,
+        from: Option<H160>,
+        to: Option<H160>,
+        value: U256,
+        data: Vec<u8>,
+        with_logs: bool,
+        step_limit: Option<u64>,
+        access_list: Vec<(H160, Vec<H256>)>,
+    ) -> TxResp {
+        self.init_local_system_contract_roots(backend);
+        // The call is executed on the state after the block of the backend as the
+        // transactions of the next block are, so the hardforks enabled in the next
+        // block apply.
+        let block_number = backend.block_number().low_u64().saturating_add(1);
+        let config = {
+            let mut config = self.config(block_number);
+            // run the gasometer in estimate mode
+            config.estimate = true;
+            config
+        };
+        let metadata = StackSubstateMetadata::new(gas_limit, &config);
+        let state = MemoryStackState::new(metadata, backend);
+        let precompiles = build_precompile_set_at(block_number);
+        // The nonce of the caller is increased by the creation, so the address
+        // is predicted before executing.
+        let caller = from.unwrap_or_default();
+        let create_address = to
+            .is_none()
+            .then(|| predicted_create_address(caller, backend.basic(caller).nonce));
+
+        let (exit, res, used_gas, gas, state) = match step_limit {
+            #[cfg(any(test, feature = "tracing"))]
+            Some(limit) => {
+                let exceeded = Rc::new(Cell::new(false));
+                let mut limiter = StepLimiter::new(limit, Rc::clone(&exceeded));
+                let executor = StackExecutor::new_with_precompiles(
+                    StepLimitedState::new(state, Rc::clone(&exceeded)),
+                    &config,
+                    precompiles.as_ref(),
+                );
+                let (exit, res, used_gas, gas, state) =
+                    evm_runtime::tracing::using(&mut limiter, || {
+                        transact(executor, caller, to, value, data, gas_limit, access_list)
+                    });
+
+                if exceeded.get() {
+                    return TxResp {
+                        exit_reason: ExitReason::Error(ExitError::OutOfGas),
+                        gas_used: gas_limit,
+                        fee_cost: backend
+                            .gas_price()
+                            .checked_mul(gas_limit.into())
+                            .unwrap_or(U256::max_value()),
+                        ..Default::default()
+                    };
+                }
+                (exit, res, used_gas, gas, state.into_inner())
+            }
+            #[cfg(not(any(test, feature = "tracing")))]
+            Some(_) => unreachable!("the step limit is only set with the tracing feature"),
+            None => transact(
+                StackExecutor::new_with_precompiles(state, &config, precompiles.as_ref()),
+                caller,
+                to,
+                value,
+                data,
+                gas_limit,
+                access_list,
+            ),
+        };
+
+        // The remaining gas includes the capped storage refund since the Antlia
+        // hardfork, the same as `evm_exec`.
+        let remain_gas = if is_enabled_in_block(HardforkName::Antlia, block_number) {
+            gas_limit - used_gas
+        } else {
+            gas
+        };
+        let code_address = create_address.filter(|_| exit.is_succeed()).map(Into::into);
+        let exit = explain_contract_limit(exit, &config);
+
+        let logs = if with_logs {
+            let (_values, logs) = state.deconstruct();
+            logs.into_iter().collect()
+        } else {
+            vec![]
+        };
+
+        TxResp {
+            exit_reason: exit,
+            ret: res,
+            remain_gas,
+            gas_used: used_gas,
+            fee_cost: backend
+                .gas_price()
+                .checked_mul(used_gas.into())
+                .unwrap_or(U256::max_value()),
+            logs,
+            code_address,
+            removed: false,
+        }
+    }
+
+    /// The checks of an EVM transaction before it is charged. A transaction
+    /// failing them is not executed and charges nothing, the returned exit
+    /// reason explains the failure.
+    pub(crate) fn check_evm_tx<Adapter: ExecutorAdapter>(
+        adapter: &Adapter,
+        tx: &SignedTransaction,
+    ) -> Result<EvmCharge, ExitReason> {
+        if tx.transaction.unsigned.is_eip4844() {
+            return Err(ExitReason::Error(ExitError::Other(
+                "blob transaction not supported".into(),
+            )));
+        }
+
+        // Since the `Antlia` hardfork the EIP-1559 effective gas price is charged,
+        // and the transaction whose max fee per gas can not cover the base fee is
+        // rejected by the mempool. Before it the gas price is charged as is.
+        let block_number = adapter.block_number().low_u64();
+        let gas_price = if is_enabled_in_block(HardforkName::Antlia, block_number) {
+            tx.transaction
+                .unsigned
+                .effective_gas_price(&adapter.block_base_fee_per_gas())
+                .ok_or_else(|| {
+                    ExitReason::Error(ExitError::Other(
+                        "max fee per gas less than block base fee".into(),
+                    ))
+                })?
+        } else {
+            tx.transaction.unsigned.gas_price()
+        };
+
+        let account = adapter.get_account(&tx.sender);
+        if is_rejected_sender(
+            &account,
+            is_enabled_in_block(HardforkName::Andromeda, block_number),
+        ) {
+            return Err(ExitReason::Error(ExitError::Other(
+                "sender not an eoa".into(),
+            )));
+        }
+
+        // The sender nonce can not be increased beyond the max nonce (EIP-2681).
+        if account.nonce >= U256::from(u64::MAX) {
+            return Err(ExitReason::Error(ExitError::Other("nonce too high".into())));
+        }
+
+        let (gas_limit, prepay_gas) =
+            checked_prepay_gas(*tx.transaction.unsigned.gas_limit(), gas_price)?;
+        Ok(EvmCharge {
+            gas_price,
+            gas_limit,
+            prepay_gas,
+            nonce: account.nonce,
+        })
+    }
+
     pub fn evm_exec<Adapter: ExecutorAdapter>(
         adapter: &mut Adapter,
         config: &Config,
         precompiles: &BTreeMap<H160, PrecompileFn>,
         tx: &SignedTransaction,
     ) -> TxResp {
-        // Deduct pre-pay gas
-        let sender = tx.sender;
-        let tx_gas_price = adapter.gas_price();
-        let gas_limit = tx.transaction.unsigned.gas_limit();
-        let prepay_gas = tx_gas_price * gas_limit;
+        let EvmCharge {
+            gas_price: tx_gas_price,
+            gas_limit,
+            prepay_gas,
+            nonce: old_nonce,
+        } = match Self::check_evm_tx(adapter, tx) {
+            Ok(charge) => charge,
+            Err(exit_reason) => {
+                return TxResp {
+                    exit_reason,
+                    ..Default::default()
+                }
+            }
+        };
+        adapter.set_gas_price(tx_gas_price);
 
-        let mut account = adapter.get_account(&sender);
-        let old_nonce = account.nonce;
+        let is_antlia = is_enabled_in_block(HardforkName::Antlia, adapter.block_number().low_u64());
+        let base_fee = adapter.block_base_fee_per_gas();
+        let sender = tx.sender;
 
-        account.balance = account.balance.saturating_sub(prepay_gas);
-        adapter.save_account(&sender, &account);
+        // Deduct pre-pay gas
+        deduct_prepay_gas(adapter, sender, prepay_gas);
 
-        let metadata = StackSubstateMetadata::new(gas_limit.as_u64(), config);
+        let metadata = StackSubstateMetadata::new(gas_limit, config);
         let mut executor = StackExecutor::new_with_precompiles(
             MemoryStackState::new(metadata, adapter),
             config,
             precompiles,
         );
 
-        let access_list = tx
-            .transaction
-            .unsigned
-            .access_list()
+        let access_list = normalize_access_list(tx.transaction.unsigned.access_list())
             .into_iter()
             .map(|x| (x.address, x.storage_keys))
             .collect::<Vec<_>>();
@@ -330,24 +705,39 @@ impl AxonExecutor {
                 *addr,
                 *tx.transaction.unsigned.value(),
                 tx.transaction.unsigned.data().to_vec(),
-                gas_limit.as_u64(),
+                gas_limit,
                 access_list,
             ),
+            // The address of the created contract is warmed by the EVM once the
+            // creation starts as EIP-2929 requires, so it is not injected into the
+            // access list which would charge the access list cost for it. The
+            // storage keys of the created contract can still be warmed by listing
+            // them under `code_address(&sender, &old_nonce)`.
             TransactionAction::Create => executor.transact_create(
                 tx.sender,
                 *tx.transaction.unsigned.value(),
                 tx.transaction.unsigned.data().to_vec(),
-                gas_limit.as_u64(),
+                gas_limit,
                 access_list,
             ),
         };
 
-        let remained_gas = executor.gas();
+        // The storage refund is capped at `used_gas / max_refund_quotient` of the
+        // config, which is one fifth since London (EIP-3529). Since the Antlia
+        // hardfork the remaining gas is derived from the capped used gas, so it
+        // includes the storage refund and the sender is charged the same as the
+        // gas used in the receipt. Before it the remaining gas of the gasometer
+        // excludes the refund.
         let used_gas = executor.used_gas();
+        let remained_gas = if is_antlia {
+            gas_limit - used_gas
+        } else {
+            executor.gas()
+        };
 
-        let code_addr = if tx.transaction.unsigned.action() == &TransactionAction::Create
-            && exit.is_succeed()
-        {
+        // A failed creation deploys nothing, so the receipt has no contract address
+        // while the sender nonce is still increased below.
+        let code_addr = if tx.transaction.unsigned.is_create() && exit.is_succeed() {
             Some(code_address(&tx.sender, &old_nonce))
         } else {
             None
@@ -360,26 +750,25 @@ impl AxonExecutor {
 
         let mut account = adapter.get_account(&tx.sender);
         account.nonce = old_nonce + U256::one();
+        adapter.save_account(&tx.sender, &account);
 
         // Add remain gas
-        if remained_gas != 0 {
-            let remain_gas = U256::from(remained_gas)
-                .checked_mul(tx_gas_price)
-                .unwrap_or_else(U256::max_value);
-            account.balance = account
-                .balance
-                .checked_add(remain_gas)
-                .unwrap_or_else(U256::max_value);
-        }
+        refund_remaining_gas(adapter, tx.sender, remained_gas, tx_gas_price);
 
-        adapter.save_account(&tx.sender, &account);
+        // Since the `Antlia` hardfork the base fee part is burnt and only the
+        // priority fee goes to the fee pool. Before it the whole gas price does.
+        let fee_price = if is_antlia {
+            tx.effective_priority_fee(base_fee)
+        } else {
+            tx_gas_price
+        };
 
         TxResp {
-            exit_reason:  exit,
+            exit_reason:  explain_contract_limit(exit, config),
             ret:          res,
             remain_gas:   remained_gas,
             gas_used:     used_gas,
-            fee_cost:     tx_gas_price
+            fee_cost:     fee_price
                 .checked_mul(used_gas.into())
                 .unwrap_or(U256::max_value()),
             logs:         vec![],
@@ -388,127 +777,746 @@ impl AxonExecutor {
         }
     }
 
-    /// The `exec()` function is run in `tokio::task::block_in_place()` and all
-    /// the read or write operations are in the scope of exec function. The
-    /// thread context is not switched during exec function.
-    fn init_local_system_contract_roots<Adapter: Backend>(&self, adapter: &Adapter) {
-        CURRENT_HEADER_CELL_ROOT.with(|root| {
-            *root.borrow_mut() =
-                adapter.storage(CKB_LIGHT_CLIENT_CONTRACT_ADDRESS, *HEADER_CELL_ROOT_KEY);
-        });
-
-        CURRENT_METADATA_ROOT.with(|root| {
-            *root.borrow_mut() = adapter.storage(METADATA_CONTRACT_ADDRESS, *METADATA_ROOT_KEY);
-        });
+    /// The same as [`Executor::exec`], but the `callback` is invoked with the
+    /// index and the response after each transaction is applied. Returning
+    /// [`ExecControl::Stop`] from the callback terminates the execution early,
+    /// then only the executed transactions are included and committed, and
+    /// the partial response is returned. This helps the block producer to
+    /// build a block before a deadline.
+    pub fn exec_streaming<Adapter, F>(
+        &self,
+        adapter: &mut Adapter,
+        txs: &[SignedTransaction],
+        validators: &[ValidatorExtend],
+        callback: F,
+    ) -> ExecResp
+    where
+        Adapter: ExecutorAdapter,
+        F: FnMut(usize, &TxResp) -> ExecControl,
+    {
+        self.exec_block(adapter, txs, validators, true, callback)
     }
 
-    fn config(&self) -> Config {
-        let mut evm_config = Config::london();
-        let create_contract_limit = {
-            if enable_hardfork(HardforkName::Andromeda) {
-                let handle = MetadataHandle::new(CURRENT_METADATA_ROOT.with(|r| *r.borrow()));
-                let consensus_config = handle.get_consensus_config().unwrap();
-                Some(consensus_config.max_contract_limit as usize)
-            } else {
-                // If the hardfork is not enabled, the limit is set to 0x6000
-                evm_config.create_contract_limit
-            }
-        };
-        evm_config.create_contract_limit = create_contract_limit;
-        evm_config
+    /// The same as [`Executor::exec`], but the before and after block hooks
+    /// of the system contracts are skipped if `hooks` is `false`. This helps
+    /// the offline tooling which replays only the EVM transactions.
+    ///
+    /// Disabling the hooks produces a state root different from the consensus
+    /// one, so it must never be used to execute a block to be committed.
+    pub fn exec_with_hooks<Adapter: ExecutorAdapter>(
+        &self,
+        adapter: &mut Adapter,
+        txs: &[SignedTransaction],
+        validators: &[ValidatorExtend],
+        hooks: bool,
+    ) -> ExecResp {
+        self.exec_block(adapter, txs, validators, hooks, |_, _| {
+            ExecControl::Continue
+        })
     }
 
-    #[cfg(test)]
-    fn test_exec<Adapter: ExecutorAdapter>(
+    fn exec_block<Adapter, F>(
         &self,
         adapter: &mut Adapter,
         txs: &[SignedTransaction],
         validators: &[ValidatorExtend],
-    ) -> ExecResp {
-        let txs_len = txs.len();
+        hooks: bool,
+        mut callback: F,
+    ) -> ExecResp
+    where
+        Adapter: ExecutorAdapter,
+        F: FnMut(usize, &TxResp) -> ExecControl,
+    {
         let block_number = adapter.block_number();
-        let mut res = Vec::with_capacity(txs_len);
-        let mut encode_receipts = Vec::with_capacity(txs_len);
-        let (mut gas, mut fee) = (0u64, U256::zero());
-        let precompiles = build_precompile_set();
-        let config = Config::london();
+        let precompiles = build_precompile_set_at(block_number.low_u64());
+        self.init_local_system_contract_roots(adapter);
+        let config = self.config(block_number.low_u64());
 
-        for tx in txs.iter() {
-            adapter.set_gas_price(tx.transaction.unsigned.gas_price());
-            adapter.set_origin(tx.sender);
+        // Execute system contracts before block hook.
+        if hooks {
+            before_block_hook(adapter);
+        }
+        observe_before_block(adapter);
+
+        let mut res = Vec::with_capacity(txs.len());
+        for (idx, tx) in txs.iter().enumerate() {
+            let r = Self::exec_tx(adapter, &config, &precompiles, tx);
+            let control = callback(idx, &r);
+            res.push(r);
 
-            // Execute a transaction, if system contract dispatch return None, means the
-            // transaction called EVM
-            let mut r = system_contract_dispatch(adapter, tx)
-                .unwrap_or_else(|| Self::evm_exec(adapter, &config, &precompiles, tx));
+            if control == ExecControl::Stop {
+                break;
+            }
+        }
+
+        Self::end_block(adapter, txs, res, validators, hooks, true)
+            .unwrap_or_else(|err| panic!("failed to allocate fee since {err}"))
+    }
 
-            r.logs = adapter.take_logs();
+    /// Execute a transaction in a block, the transaction calling a system
+    /// contract is dispatched to the system contract.
+    fn exec_tx<Adapter: ExecutorAdapter>(
+        adapter: &mut Adapter,
+        config: &Config,
+        precompiles: &BTreeMap<H160, PrecompileFn>,
+        tx: &SignedTransaction,
+    ) -> TxResp {
+        adapter.set_gas_price(tx.transaction.unsigned.gas_price());
+        adapter.set_origin(tx.sender);
+
+        // Execute a transaction, if system contract dispatch return None, means the
+        // transaction called EVM
+        let mut r = system_contract_dispatch(adapter, tx)
+            .unwrap_or_else(|| Self::evm_exec(adapter, config, precompiles, tx));
+        r.logs = adapter.take_logs();
+        r
+    }
+
+    /// Finish a block with the responses of the executed transactions, which
+    /// are the leading ones of `txs`. The collected fee is allocated and the
+    /// changes are committed. The after block hook is skipped if `hooks` is
+    /// `false`, and the [`BLOCK_HOOK_OBSERVER`] is not notified if `observe`
+    /// is `false`. An error is returned if the fee allocation fails, and
+    /// nothing is committed in this case.
+    fn end_block<Adapter: ExecutorAdapter>(
+        adapter: &mut Adapter,
+        txs: &[SignedTransaction],
+        res: Vec<TxResp>,
+        validators: &[ValidatorExtend],
+        hooks: bool,
+        observe: bool,
+    ) -> ProtocolResult<ExecResp> {
+        let block_number = adapter.block_number();
+        let mut encode_receipts = Vec::with_capacity(res.len());
+        let mut cumulative_gas_used = Vec::with_capacity(res.len());
+        let (mut gas, mut fee, mut fee_overflow) = (0u64, U256::zero(), false);
+
+        for (tx, r) in txs.iter().zip(res.iter()) {
             gas += r.gas_used;
-            fee = fee.checked_add(r.fee_cost).unwrap_or(U256::max_value());
+            cumulative_gas_used.push(gas);
+            fee = accumulate_fee(fee, r.fee_cost, &mut fee_overflow);
 
             let logs_bloom = logs_bloom(r.logs.iter());
-            let receipt = tx.encode_receipt(&r, logs_bloom);
+            let receipt = tx.encode_receipt_with_cumulative(r, logs_bloom, gas.into());
             encode_receipts.push(receipt);
-
-            res.push(r);
         }
 
         // Allocate collected fee for validators
         if !block_number.is_zero() {
-            let alloc =
-                (*FEE_ALLOCATOR)
-                    .load()
-                    .allocate(block_number, fee, adapter.origin(), validators);
-
-            for i in alloc.iter() {
-                if !i.amount.is_zero() {
-                    let mut account = adapter.get_account(&i.address);
-                    account.balance += i.amount;
-                    adapter.save_account(&i.address, &account);
-                }
-            }
+            let allocator = FEE_ALLOCATOR.load();
+            Self::allocate_fee(adapter, &***allocator, block_number, fee, validators)?;
+        }
+
+        // Execute system contracts after block hook.
+        if hooks {
+            after_block_hook(adapter);
         }
 
         // commit changes by all txs included in this block only once
         let new_state_root = adapter.commit();
+        let touched_accounts = adapter.take_touched_accounts();
 
-        let receipt_root = if encode_receipts.is_empty() {
-            RLP_NULL
-        } else {
-            TrieMerkle::from_receipts(&encode_receipts)
-                .root_hash()
-                .unwrap_or_else(|err| {
-                    panic!("failed to calculate trie root hash for receipts since {err}")
-                })
-        };
+        if observe {
+            if let Some(observer) = BLOCK_HOOK_OBSERVER.load().as_ref() {
+                observer.on_after(block_number, new_state_root);
+            }
+        }
+
+        // self.update_system_contract_roots_for_external_module();
+
+        let receipt_root = receipts_root(&encode_receipts);
 
-        ExecResp {
+        Ok(ExecResp {
             state_root: new_state_root,
             receipt_root,
             gas_used: gas,
+            cumulative_gas_used,
+            encoded_receipts: encode_receipts,
             tx_resp: res,
+            fee_overflow,
+            touched_accounts,
+        })
+    }
+
+    /// The same as [`Executor::exec`], but the transactions are included only
+    /// while the block gas limit of the adapter can cover them. A transaction
+    /// is included if its gas limit is no more than the block gas limit minus
+    /// the gas used by the previous ones, otherwise the execution stops and
+    /// the rest transactions are returned as un-included. This helps the block
+    /// producer to build a valid-sized block.
+    pub fn exec_within_block_gas_limit<'a, Adapter: ExecutorAdapter>(
+        &self,
+        adapter: &mut Adapter,
+        txs: &'a [SignedTransaction],
+        validators: &[ValidatorExtend],
+    ) -> (ExecResp, &'a [SignedTransaction]) {
+        let block_gas_limit = adapter.block_gas_limit();
+        let fits = |gas_used: U256, tx: &SignedTransaction| {
+            gas_used.saturating_add(*tx.transaction.unsigned.gas_limit()) <= block_gas_limit
+        };
+
+        let txs_to_exec: &[SignedTransaction] = match txs.first() {
+            Some(tx) if fits(U256::zero(), tx) => txs,
+            _ => &[],
+        };
+        let (mut gas_used, mut included) = (U256::zero(), 0);
+        let resp = self.exec_streaming(adapter, txs_to_exec, validators, |idx, r| {
+            gas_used += r.gas_used.into();
+            included = idx + 1;
+
+            match txs.get(included) {
+                Some(next) if !fits(gas_used, next) => ExecControl::Stop,
+                _ => ExecControl::Continue,
+            }
+        });
+
+        (resp, &txs[included..])
+    }
+
+    /// Execute a block the same as [`Executor::exec`] and return the response
+    /// with the would-be state root, while the adapter is left untouched.
+    /// This helps the tooling to preview the effect of a block.
+    ///
+    /// The block is executed on an adapter over the committed state of
+    /// `adapter`, whose trie nodes are written to an [`OverlayTrieDB`] in
+    /// memory, so no node of the block is persisted. The fee is allocated,
+    /// while the before and after block hooks are skipped and the
+    /// [`BLOCK_HOOK_OBSERVER`] is not notified, since the hooks update the
+    /// system contract tries and the hardfork flags out of the adapter. So
+    /// the state root is the same as [`AxonExecutor::exec_with_hooks`]
+    /// without hooks. Note that the deployed code is still saved to the
+    /// storage, and the system contract transactions write their trie nodes
+    /// to their own databases, which are unreachable from the committed
+    /// roots.
+    pub fn dry_run_block<S, DB>(
+        &self,
+        adapter: &mut AxonExecutorApplyAdapter<S, DB>,
+        txs: &[SignedTransaction],
+        validators: &[ValidatorExtend],
+    ) -> ProtocolResult<ExecResp>
+    where
+        S: Storage + 'static,
+        DB: trie::DB + 'static,
+    {
+        let mut scratch = adapter.overlay()?;
+        let block_number = scratch.block_number();
+        let precompiles = build_precompile_set_at(block_number.low_u64());
+        self.init_local_system_contract_roots(&scratch);
+        let config = self.config(block_number.low_u64());
+
+        let res = txs
+            .iter()
+            .map(|tx| Self::exec_tx(&mut scratch, &config, &precompiles, tx))
+            .collect();
+        let resp = Self::end_block(&mut scratch, txs, res, validators, false, false);
+
+        self.init_local_system_contract_roots(adapter);
+        resp
+    }
+
+    /// Re-execute a single transaction of a committed block on the adapter at
+    /// the state right before the transaction, which is the foundation of
+    /// `debug_traceTransaction` and the receipt backfills. The block hooks and
+    /// the fee allocation are skipped, so the changes are applied to the
+    /// adapter without being committed. The logs are included in the returned
+    /// response.
+    pub fn replay_tx<Adapter: ExecutorAdapter>(
+        &self,
+        adapter: &mut Adapter,
+        tx: &SignedTransaction,
+    ) -> TxResp {
+        let block_number = adapter.block_number();
+        let precompiles = build_precompile_set_at(block_number.low_u64());
+        self.init_local_system_contract_roots(adapter);
+        let config = self.config(block_number.low_u64());
+
+        Self::exec_tx(adapter, &config, &precompiles, tx)
+    }
+
+    /// The same as [`Executor::exec`], and the `tracer` is called before each
+    /// opcode executed in the block. The tracer is returned along with the
+    /// response so that the collected trace can be taken out of it.
+    ///
+    /// A tracer whose [`Tracer::ENABLED`] is `false`, such as
+    /// [`NoopTracer`], is not installed and costs nothing. It requires the
+    /// `tracing` feature.
+    #[cfg(any(test, feature = "tracing"))]
+    pub fn exec_with_tracer<Adapter, T>(
+        &self,
+        adapter: &mut Adapter,
+        txs: &[SignedTransaction],
+        validators: &[ValidatorExtend],
+        tracer: T,
+    ) -> (ExecResp, T)
+    where
+        Adapter: ExecutorAdapter,
+        T: Tracer + 'static,
+    {
+        if !T::ENABLED {
+            return (self.exec(adapter, txs, validators), tracer);
+        }
+
+        let gas_remaining = Rc::new(Cell::new(0u64));
+        let mut gas_listener = GasListener {
+            gas_remaining: Rc::clone(&gas_remaining),
+        };
+        let mut step_listener = StepListener {
+            tracer,
+            gas_remaining,
+        };
+
+        let resp = evm_gasometer::tracing::using(&mut gas_listener, || {
+            evm_runtime::tracing::using(&mut step_listener, || self.exec(adapter, txs, validators))
+        });
+        (resp, step_listener.tracer)
+    }
+
+    /// The same as [`Executor::exec`], but a transaction that can not be
+    /// executed is excluded from the block instead of being included as a
+    /// failed one. Such transactions are returned with their indexes, which
+    /// are the ones calling a reserved system contract address or failing
+    /// [`AxonExecutor::check_evm_tx`]. An error is returned instead of
+    /// panicking if the collected fee can not be allocated. It is designed
+    /// for fuzzing harnesses and replay tools.
+    pub fn try_exec<Adapter: ExecutorAdapter>(
+        &self,
+        adapter: &mut Adapter,
+        txs: &[SignedTransaction],
+        validators: &[ValidatorExtend],
+    ) -> ProtocolResult<(ExecResp, Vec<(usize, ExecutorError)>)> {
+        let block_number = adapter.block_number();
+        let precompiles = build_precompile_set_at(block_number.low_u64());
+        self.init_local_system_contract_roots(adapter);
+        let config = self.config(block_number.low_u64());
+
+        // Execute system contracts before block hook.
+        before_block_hook(adapter);
+        observe_before_block(adapter);
+
+        let (mut included, mut res, mut failures) = (Vec::new(), Vec::new(), Vec::new());
+        for (idx, tx) in txs.iter().enumerate() {
+            let checked = match is_call_system_script(tx.transaction.unsigned.action()) {
+                Ok(true) => Ok(()),
+                Ok(false) => Self::check_evm_tx(adapter, tx)
+                    .map(|_| ())
+                    .map_err(|exit_reason| format!("{exit_reason:?}")),
+                Err(e) => Err(e.to_string()),
+            };
+
+            if let Err(reason) = checked {
+                failures.push((idx, ExecutorError::InvalidTransaction {
+                    tx_hash: tx.transaction.hash,
+                    reason,
+                }));
+                continue;
+            }
+
+            res.push(Self::exec_tx(adapter, &config, &precompiles, tx));
+            included.push(tx.clone());
+        }
+
+        let resp = Self::end_block(adapter, &included, res, validators, true, true)?;
+        Ok((resp, failures))
+    }
+
+    /// Allocate the collected fee of a block by the given allocator. The
+    /// allocation is rejected if the sum of the inlets exceeds the collected
+    /// fee, and no account is modified in this case. The inlets to the same
+    /// address are merged by [`FeeInlet::aggregate`] before crediting. The
+    /// zero fee is not allocated unless [`FeeAllocate::allocate_zero_fee`],
+    /// and such an allocator is allowed to mint beyond the collected fee.
+    pub(crate) fn allocate_fee<Adapter: ExecutorAdapter>(
+        adapter: &mut Adapter,
+        allocator: &dyn FeeAllocate,
+        block_number: U256,
+        fee: U256,
+        validators: &[ValidatorExtend],
+    ) -> ProtocolResult<()> {
+        if fee.is_zero() && !allocator.allocate_zero_fee() {
+            return Ok(());
+        }
+
+        let alloc = allocator.allocate(block_number, fee, adapter.origin(), validators)?;
+
+        let allocated = alloc.iter().try_fold(U256::zero(), |sum, i| {
+            sum.checked_add(i.amount)
+                .ok_or(ExecutorError::FeeAllocationOverflow)
+        })?;
+
+        if allocated > fee && !allocator.allocate_zero_fee() {
+            return Err(ExecutorError::ExcessiveFeeAllocation {
+                allocated,
+                collected: fee,
+            }
+            .into());
+        }
+
+        for i in FeeInlet::aggregate(alloc).iter() {
+            if !i.amount.is_zero() {
+                let mut account = adapter.get_account(&i.address);
+                account.balance += i.amount;
+                adapter.save_account(&i.address, &account);
+            }
         }
+
+        Ok(())
+    }
+
+    /// The `exec()` function is run in `tokio::task::block_in_place()` and all
+    /// the read or write operations are in the scope of exec function. The
+    /// thread context is not switched during exec function.
+    fn init_local_system_contract_roots<Adapter: Backend>(&self, adapter: &Adapter) {
+        SystemContractRoots::from_backend(adapter).install();
+
+        CURRENT_BLOCK_NUMBER.with(|number| {
+            *number.borrow_mut() = adapter.block_number().low_u64();
+        });
+
+        CURRENT_CHAIN_ID.with(|id| {
+            *id.borrow_mut() = adapter.chain_id();
+        });
+
+        CURRENT_BLOCK_ENV.with(|env| {
+            *env.borrow_mut() = BlockEnv::from_backend(adapter);
+        });
+    }
+
+    /// The EVM config to execute the block of the given number, see
+    /// [`hardfork_of_block`].
+    fn config(&self, block_number: u64) -> Config {
+        self.config_for(hardfork_of_block(block_number))
+    }
+
+    /// Build the EVM config of the given hardfork. The base config is decided
+    /// by the EVM version that the hardfork follows, then the contract size
+    /// limit and the call stack limit are applied if the hardfork allows the
+    /// validators to modify them by metadata.
+    pub fn config_for(&self, name: HardforkName) -> Config {
+        let metadata_root = CURRENT_METADATA_ROOT.with(|r| *r.borrow());
+        let mut evm_config = CONFIG_CACHE.get_or_build(name, metadata_root, || {
+            let mut evm_config = match name {
+                HardforkName::None | HardforkName::Andromeda | HardforkName::Antlia => {
+                    Config::london()
+                }
+            };
+
+            // The contract size limit is modifiable since the `Andromeda` hardfork,
+            // which the later hardforks follow. Before it the contract size limit
+            // is 0x6000 and the call stack limit is 1024. The call stack limit is
+            // only modifiable since the `Antlia` hardfork, and the config stored
+            // before it has the default depth.
+            if name != HardforkName::None {
+                let handle = MetadataHandle::new(metadata_root);
+                let consensus_config = handle.get_consensus_config().unwrap();
+                evm_config.create_contract_limit =
+                    Some(consensus_config.max_contract_limit as usize);
+                // A zero depth means the depth is not set in metadata.
+                if consensus_config.max_call_depth != 0 {
+                    evm_config.call_stack_limit = consensus_config.max_call_depth as usize;
+                }
+            }
+
+            evm_config
+        });
+
+        if let Some(limit) = contract_size_limit_override() {
+            evm_config.create_contract_limit = limit;
+        }
+
+        evm_config
+    }
+
+    #[cfg(test)]
+    fn test_exec<Adapter: ExecutorAdapter>(
+        &self,
+        adapter: &mut Adapter,
+        txs: &[SignedTransaction],
+        validators: &[ValidatorExtend],
+    ) -> ExecResp {
+        let precompiles = build_precompile_set_at(adapter.block_number().low_u64());
+        let config = Config::london();
+        let res = txs
+            .iter()
+            .map(|tx| Self::exec_tx(adapter, &config, &precompiles, tx))
+            .collect();
+
+        Self::end_block(adapter, txs, res, validators, false, true)
+            .unwrap_or_else(|err| panic!("failed to allocate fee since {err}"))
     }
 }
 
+fn observe_before_block<Adapter: Backend>(adapter: &Adapter) {
+    if let Some(observer) = BLOCK_HOOK_OBSERVER.load().as_ref() {
+        observer.on_before(
+            adapter.block_number(),
+            adapter.storage(METADATA_CONTRACT_ADDRESS, *METADATA_ROOT_KEY),
+            adapter.storage(CKB_LIGHT_CLIENT_CONTRACT_ADDRESS, *HEADER_CELL_ROOT_KEY),
+        );
+    }
+}
+
+/// Run a call or a creation of `AxonExecutor::inner_call` and return the exit
+/// reason, the return value, the used gas, the remaining gas and the state.
+fn transact<'config, S: StackState<'config>>(
+    mut executor: StackExecutor<'config, '_, S, BTreeMap<H160, PrecompileFn>>,
+    caller: H160,
+    to: Option<H160>,
+    value: U256,
+    data: Vec<u8>,
+    gas_limit: u64,
+    access_list: Vec<(H160, Vec<H256>)>,
+) -> (ExitReason, Vec<u8>, u64, u64, S) {
+    let (exit, res) = if let Some(addr) = to {
+        executor.transact_call(caller, addr, value, data, gas_limit, access_list)
+    } else {
+        executor.transact_create(caller, value, data, gas_limit, access_list)
+    };
+    let used_gas = executor.used_gas();
+    let gas = executor.gas();
+    (exit, res, used_gas, gas, executor.into_state())
+}
+
+/// Add the fee cost of a transaction to the collected fee of a block. The sum
+/// saturates at `U256::MAX` and sets `overflow`, which never happens unless
+/// the fee calculation is wrong.
+fn accumulate_fee(fee: U256, fee_cost: U256, overflow: &mut bool) -> U256 {
+    fee.checked_add(fee_cost).unwrap_or_else(|| {
+        log::error!("collected fee overflows, {fee} + {fee_cost}");
+        *overflow = true;
+        U256::max_value()
+    })
+}
+
+/// The receipts root of a block without transaction. Axon follows the
+/// Ethereum convention that it is the root of an empty Merkle Patricia trie,
+/// which is `keccak256(rlp(""))`, the same value as [`RLP_NULL`], rather than
+/// a zero hash.
+pub const EMPTY_RECEIPTS_ROOT: H256 = RLP_NULL;
+
+/// Calculate the receipts root of a block from the encoded receipts, which is
+/// [`EMPTY_RECEIPTS_ROOT`] for a block without transaction.
+pub fn receipts_root(encoded: &[Bytes]) -> H256 {
+    if encoded.is_empty() {
+        return EMPTY_RECEIPTS_ROOT;
+    }
+
+    TrieMerkle::from_receipts(encoded)
+        .root_hash()
+        .unwrap_or_else(|err| panic!("failed to calculate trie root hash for receipts since {err}"))
+}
+
 pub fn is_transaction_call(action: &TransactionAction, addr: &H160) -> bool {
     action == &TransactionAction::Call(*addr)
 }
 
+#[cfg(feature = "contract_limit_override")]
+fn contract_size_limit_override() -> Option<Option<usize>> {
+    CONTRACT_SIZE_LIMIT_OVERRIDE.load().as_deref().copied()
+}
+
+#[cfg(not(feature = "contract_limit_override"))]
+fn contract_size_limit_override() -> Option<Option<usize>> {
+    None
+}
+
+/// Whether the hardfork is enabled after the latest executed block. The
+/// execution should check [`is_enabled_in_block`] instead, which does not
+/// depend on when the flags are updated.
 pub fn enable_hardfork(name: HardforkName) -> bool {
-    let latest_hardfork_info = &**HARDFORK_INFO.load();
-    let enable_flag = H256::from_low_u64_be((name as u64).to_be());
+    is_enabled_in(**HARDFORK_INFO.load(), name)
+}
+
+/// The hardforks enabled in [`HARDFORK_INFO`] in the order of activation,
+/// which are checked the same as [`enable_hardfork`]. `HardforkName::None`
+/// is never included.
+pub fn enabled_hardforks() -> Vec<HardforkName> {
+    enabled_hardforks_in(**HARDFORK_INFO.load())
+}
+
+/// The latest hardfork enabled at the given block number.
+pub fn hardfork_at(block_number: u64) -> HardforkName {
+    latest_hardfork_in(HARDFORK_REGISTRY.load().flags_at(block_number))
+}
 
-    latest_hardfork_info & &enable_flag == enable_flag
+/// Whether the hardfork is enabled at the given block number.
+pub fn is_enabled_at(name: HardforkName, block_number: u64) -> bool {
+    is_enabled_in(HARDFORK_REGISTRY.load().flags_at(block_number), name)
+}
+
+/// The latest hardfork enabled when executing the block of the given number.
+/// The hardfork flags are updated in the after block hook, so a block is
+/// executed with the hardforks enabled at its parent block. Every decision of
+/// the execution depending on the hardforks is made by this or
+/// [`is_enabled_in_block`].
+pub fn hardfork_of_block(block_number: u64) -> HardforkName {
+    hardfork_at(block_number.saturating_sub(1))
+}
+
+/// Whether the hardfork is enabled when executing the block of the given
+/// number, see [`hardfork_of_block`].
+pub fn is_enabled_in_block(name: HardforkName, block_number: u64) -> bool {
+    is_enabled_at(name, block_number.saturating_sub(1))
+}
+
+/// The block number at which the hardfork is activated, return `None` if the
+/// hardfork is not scheduled.
+pub fn hardfork_activation_number(name: HardforkName) -> Option<u64> {
+    if name == HardforkName::None {
+        return Some(0);
+    }
+
+    HARDFORK_REGISTRY
+        .load()
+        .activation_number(hardfork_flag(name))
+}
+
+fn hardfork_flag(name: HardforkName) -> H256 {
+    H256::from_low_u64_be((name as u64).to_be())
+}
+
+fn is_enabled_in(flags: H256, name: HardforkName) -> bool {
+    let enable_flag = hardfork_flag(name);
+    flags & enable_flag == enable_flag
+}
+
+fn enabled_hardforks_in(flags: H256) -> Vec<HardforkName> {
+    HardforkName::iter()
+        .filter(|name| *name != HardforkName::None && is_enabled_in(flags, *name))
+        .collect()
+}
+
+/// The latest hardfork enabled in the flags, the hardforks are declared in the
+/// order of activation.
+fn latest_hardfork_in(flags: H256) -> HardforkName {
+    HardforkName::iter()
+        .filter(|name| is_enabled_in(flags, *name))
+        .last()
+        .unwrap_or(HardforkName::None)
+}
+
+/// Whether the transaction from the sender should be rejected. Since the
+/// `Andromeda` hardfork, the sender with deployed code is rejected as
+/// [`EIP-3607`](https://eips.ethereum.org/EIPS/eip-3607) requires.
+fn is_rejected_sender(sender: &Account, is_andromeda: bool) -> bool {
+    is_andromeda && sender.code_hash != NIL_DATA
 }
 
 #[cfg(test)]
 mod test {
+    use protocol::types::{HardforkInfo, HardforkInfoInner};
+
     use super::*;
 
+    #[test]
+    fn test_is_rejected_sender() {
+        let mut account = Account {
+            nonce:        U256::zero(),
+            balance:      U256::zero(),
+            storage_root: RLP_NULL,
+            code_hash:    NIL_DATA,
+        };
+        assert!(!is_rejected_sender(&account, false));
+        assert!(!is_rejected_sender(&account, true));
+
+        account.code_hash = H256::random();
+        assert!(!is_rejected_sender(&account, false));
+        assert!(is_rejected_sender(&account, true));
+    }
+
+    #[test]
+    fn test_config_cache() {
+        let cache = ConfigCache::default();
+        let builds = Cell::new(0usize);
+        let build = |limit: usize| {
+            let builds = &builds;
+            move || {
+                builds.set(builds.get() + 1);
+                let mut config = Config::london();
+                config.create_contract_limit = Some(limit);
+                config
+            }
+        };
+        let (root_a, root_b) = (H256::random(), H256::random());
+
+        let config = cache.get_or_build(HardforkName::Andromeda, root_a, build(0x6000));
+        assert_eq!(config.create_contract_limit, Some(0x6000));
+        assert_eq!(builds.get(), 1);
+
+        // The cached config is reused while the metadata root is unchanged.
+        let config = cache.get_or_build(HardforkName::Andromeda, root_a, build(0x8000));
+        assert_eq!(config.create_contract_limit, Some(0x6000));
+        assert_eq!(builds.get(), 1);
+
+        // The config is rebuilt once the metadata root or the hardfork changes.
+        let config = cache.get_or_build(HardforkName::Andromeda, root_b, build(0x8000));
+        assert_eq!(config.create_contract_limit, Some(0x8000));
+        assert_eq!(builds.get(), 2);
+        let config = cache.get_or_build(HardforkName::None, root_b, build(0x6000));
+        assert_eq!(config.create_contract_limit, Some(0x6000));
+        assert_eq!(builds.get(), 3);
+    }
+
+    #[test]
+    fn test_enabled_hardforks() {
+        assert!(enabled_hardforks_in(H256::zero()).is_empty());
+
+        let andromeda = hardfork_flag(HardforkName::Andromeda);
+        assert_eq!(enabled_hardforks_in(andromeda), vec![
+            HardforkName::Andromeda
+        ]);
+
+        // The bits of the unknown hardforks are ignored.
+        let unknown = H256::from_low_u64_be((1u64 << 63).to_be());
+        assert_eq!(enabled_hardforks_in(andromeda | unknown), vec![
+            HardforkName::Andromeda
+        ]);
+        assert!(enabled_hardforks_in(unknown).is_empty());
+    }
+
+    #[test]
+    fn test_hardfork_at_boundary() {
+        let mut registry = HardforkInfo::default();
+        registry.push(HardforkInfoInner {
+            block_number: 10,
+            flags:        hardfork_flag(HardforkName::Andromeda),
+        });
+
+        assert_eq!(latest_hardfork_in(registry.flags_at(9)), HardforkName::None);
+        assert_eq!(
+            latest_hardfork_in(registry.flags_at(10)),
+            HardforkName::Andromeda
+        );
+        assert!(!is_enabled_in(
+            registry.flags_at(9),
+            HardforkName::Andromeda
+        ));
+        assert!(is_enabled_in(
+            registry.flags_at(10),
+            HardforkName::Andromeda
+        ));
+        assert!(is_enabled_in(registry.flags_at(0), HardforkName::None));
+        assert_eq!(
+            registry.activation_number(hardfork_flag(HardforkName::Andromeda)),
+            Some(10)
+        );
+    }
+
     #[test]
     fn test_config_contract_limit() {
         let config = Config::london();
         assert_eq!(config.create_contract_limit, Some(0x6000));
     }
+
+    #[cfg(feature = "contract_limit_override")]
+    #[test]
+    fn test_config_contract_limit_override() {
+        CONTRACT_SIZE_LIMIT_OVERRIDE.store(Some(Arc::new(None)));
+        assert_eq!(AxonExecutor.config(0).create_contract_limit, None);
+
+        CONTRACT_SIZE_LIMIT_OVERRIDE.store(Some(Arc::new(Some(0x10000))));
+        assert_eq!(AxonExecutor.config(0).create_contract_limit, Some(0x10000));
+
+        CONTRACT_SIZE_LIMIT_OVERRIDE.store(None);
+        assert_eq!(AxonExecutor.config(0).create_contract_limit, Some(0x6000));
+    }
 }
@@ -0,0 +1,220 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::num::NonZeroUsize;
+use std::thread;
+
+use evm::executor::stack::PrecompileFn;
+
+use protocol::traits::{Backend, Executor, ExecutorAdapter};
+use protocol::types::{
+    normalize_access_list, ExecResp, SignedTransaction, TransactionAction, TxResp, ValidatorExtend,
+    H160, H256,
+};
+
+use crate::adapter::ScratchAdapter;
+use crate::precompiles::build_precompile_set_at;
+use crate::system_contract::{before_block_hook, is_system_contract_address_format};
+use crate::{
+    observe_before_block, AxonExecutor, BlockEnv, SystemContractRoots, CURRENT_BLOCK_ENV,
+    CURRENT_BLOCK_NUMBER, CURRENT_CHAIN_ID,
+};
+
+/// A group of transactions which touch no account of the other groups, with
+/// the accounts and the storage slots declared by them.
+struct Group {
+    txs:   Vec<usize>,
+    slots: BTreeMap<H160, BTreeSet<H256>>,
+}
+
+/// Split the transactions into the groups that touch disjoint accounts. Two
+/// transactions are in the same group if they share any of the sender, the
+/// callee and the addresses in the access list. Returns `None` if any
+/// transaction cannot be grouped, which is a creation, a call to a system
+/// contract or a precompile, or a transaction without an access list.
+fn partition(
+    txs: &[SignedTransaction],
+    precompiles: &BTreeMap<H160, PrecompileFn>,
+) -> Option<Vec<Group>> {
+    let mut parents: Vec<usize> = Vec::new();
+    let mut indexes: HashMap<H160, usize> = HashMap::new();
+    let mut slots: Vec<(H160, Vec<H256>)> = Vec::new();
+
+    fn find(parents: &mut [usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            parents[i] = parents[parents[i]];
+            i = parents[i];
+        }
+        i
+    }
+
+    let mut tx_roots = Vec::with_capacity(txs.len());
+    for tx in txs.iter() {
+        let to = match tx.transaction.unsigned.action() {
+            TransactionAction::Call(to) => *to,
+            TransactionAction::Create => return None,
+        };
+        let access_list = normalize_access_list(tx.transaction.unsigned.access_list());
+        if access_list.is_empty() {
+            return None;
+        }
+
+        let mut accounts = vec![(tx.sender, Vec::new()), (to, Vec::new())];
+        accounts.extend(access_list.into_iter().map(|i| (i.address, i.storage_keys)));
+
+        let mut root = None;
+        for (address, keys) in accounts.into_iter() {
+            if is_system_contract_address_format(&address) || precompiles.contains_key(&address) {
+                return None;
+            }
+
+            let idx = *indexes.entry(address).or_insert_with(|| {
+                parents.push(parents.len());
+                parents.len() - 1
+            });
+            slots.push((address, keys));
+
+            let r = find(&mut parents, idx);
+            match root {
+                Some(root) if root != r => parents[r] = root,
+                Some(_) => (),
+                None => root = Some(r),
+            }
+        }
+        tx_roots.push(root.expect("a transaction touches the sender at least"));
+    }
+
+    let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+    let mut groups: Vec<Group> = Vec::new();
+    for (i, root) in tx_roots.into_iter().enumerate() {
+        let root = find(&mut parents, root);
+        let next = groups.len();
+        let g = *group_of_root.entry(root).or_insert(next);
+        if g == next {
+            groups.push(Group {
+                txs:   Vec::new(),
+                slots: BTreeMap::new(),
+            });
+        }
+        groups[g].txs.push(i);
+    }
+
+    for (address, keys) in slots.into_iter() {
+        let root = find(&mut parents, indexes[&address]);
+        groups[group_of_root[&root]]
+            .slots
+            .entry(address)
+            .or_default()
+            .extend(keys);
+    }
+
+    Some(groups)
+}
+
+impl AxonExecutor {
+    /// The same as [`Executor::exec`], but the transactions touching disjoint
+    /// accounts are executed in parallel. The transactions are grouped by
+    /// the sender, the callee and the access list, and each group is executed
+    /// on a scratch state prefetched from the adapter, then the changes are
+    /// merged back. The result is the same as [`Executor::exec`].
+    ///
+    /// The execution falls back to the sequential one if any transaction has
+    /// no access list or cannot be grouped, or if any group accesses a state
+    /// out of its access list.
+    pub fn exec_parallel<Adapter: ExecutorAdapter>(
+        &self,
+        adapter: &mut Adapter,
+        txs: &[SignedTransaction],
+        validators: &[ValidatorExtend],
+    ) -> ExecResp {
+        let precompiles = build_precompile_set_at(adapter.block_number().low_u64());
+        let groups = match partition(txs, &precompiles) {
+            Some(groups) if groups.len() > 1 => groups,
+            _ => return self.exec(adapter, txs, validators),
+        };
+
+        let block_number = adapter.block_number();
+        self.init_local_system_contract_roots(adapter);
+        let config = self.config(block_number.low_u64());
+
+        // Execute system contracts before block hook.
+        before_block_hook(adapter);
+        observe_before_block(adapter);
+
+        // The workers are fresh threads, so the roots of the current thread are
+        // installed to them explicitly.
+        let roots = SystemContractRoots::current();
+        let chain_id = adapter.chain_id();
+        let block_env = BlockEnv::current();
+        let mut jobs = groups
+            .into_iter()
+            .map(|g| (g.txs, ScratchAdapter::prefetch(adapter, g.slots)))
+            .collect::<Vec<_>>();
+
+        let workers = thread::available_parallelism()
+            .map(NonZeroUsize::get)
+            .unwrap_or(1)
+            .min(jobs.len());
+        let chunk_size = (jobs.len() + workers - 1) / workers;
+
+        let results = thread::scope(|s| {
+            let handles = jobs
+                .chunks_mut(chunk_size)
+                .map(|chunk| {
+                    let (config, precompiles) = (&config, &precompiles);
+                    s.spawn(move || {
+                        roots.install();
+                        CURRENT_BLOCK_NUMBER.with(|n| *n.borrow_mut() = block_number.low_u64());
+                        CURRENT_CHAIN_ID.with(|id| *id.borrow_mut() = chain_id);
+                        CURRENT_BLOCK_ENV.with(|env| *env.borrow_mut() = block_env);
+
+                        let mut res = Vec::new();
+                        for (indexes, scratch) in chunk.iter_mut() {
+                            for i in indexes.iter() {
+                                let r = Self::exec_tx(scratch, config, precompiles, &txs[*i]);
+                                res.push((*i, r));
+
+                                if scratch.escaped() {
+                                    return None;
+                                }
+                            }
+                        }
+                        Some(res)
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                .collect::<Option<Vec<_>>>()
+        });
+
+        let mut res = match results {
+            Some(res) => res.into_iter().flatten().collect::<Vec<_>>(),
+            None => {
+                log::debug!("parallel execution escaped, fall back to sequential execution");
+                let res = txs
+                    .iter()
+                    .map(|tx| Self::exec_tx(adapter, &config, &precompiles, tx))
+                    .collect::<Vec<_>>();
+                return Self::end_block(adapter, txs, res, validators, true, true)
+                    .unwrap_or_else(|err| panic!("failed to allocate fee since {err}"));
+            }
+        };
+
+        // The origin and the gas price are left by the last transaction in the
+        // sequential execution, which are used by the after block hook.
+        let last = txs.len() - 1;
+        for (indexes, scratch) in jobs.into_iter() {
+            if indexes.last() == Some(&last) {
+                adapter.set_origin(scratch.origin());
+                adapter.set_gas_price(scratch.gas_price());
+            }
+            scratch.merge_into(adapter);
+        }
+
+        res.sort_unstable_by_key(|(i, _)| *i);
+        let res = res.into_iter().map(|(_, r)| r).collect::<Vec<TxResp>>();
+        Self::end_block(adapter, txs, res, validators, true, true)
+            .unwrap_or_else(|err| panic!("failed to allocate fee since {err}"))
+    }
+}
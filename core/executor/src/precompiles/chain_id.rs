@@ -0,0 +1,49 @@
+use evm::executor::stack::{PrecompileFailure, PrecompileOutput};
+use evm::{Context, ExitSucceed};
+
+use protocol::types::{H160, H256};
+
+use crate::precompiles::{axon_precompile_address, PrecompileContract, PrecompileError};
+use crate::CURRENT_CHAIN_ID;
+
+/// Return the chain id of the block being executed as a 32-byte big-endian
+/// word, the input is ignored. The chain id is read from the backend before
+/// executing, so the precompiles can behave per chain.
+#[derive(Default, Clone)]
+pub struct ChainId;
+
+impl PrecompileContract for ChainId {
+    const ADDRESS: H160 = axon_precompile_address(0x09);
+    // The same as the `CHAINID` opcode.
+    const MIN_GAS: u64 = 2;
+
+    fn exec_fn(
+        input: &[u8],
+        gas_limit: Option<u64>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> Result<(PrecompileOutput, u64), PrecompileFailure> {
+        let gas = Self::gas_cost(input);
+        if let Some(limit) = gas_limit {
+            if gas > limit {
+                return Err(PrecompileError::OutOfGas { cost: gas, limit }.into());
+            }
+        }
+
+        let chain_id = CURRENT_CHAIN_ID.with(|id| *id.borrow());
+        let mut output = H256::default();
+        chain_id.to_big_endian(output.as_bytes_mut());
+
+        Ok((
+            PrecompileOutput {
+                exit_status: ExitSucceed::Returned,
+                output:      output.as_bytes().to_vec(),
+            },
+            gas,
+        ))
+    }
+
+    fn gas_cost(_input: &[u8]) -> u64 {
+        Self::MIN_GAS
+    }
+}
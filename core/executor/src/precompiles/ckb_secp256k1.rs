@@ -0,0 +1,61 @@
+use evm::executor::stack::{PrecompileFailure, PrecompileOutput};
+use evm::{Context, ExitSucceed};
+
+use common_crypto::{secp256k1_recover, Secp256k1RecoverableSignature, Signature};
+use protocol::{ckb_blake2b_256, types::H160};
+
+use crate::precompiles::{axon_precompile_address, PrecompileContract, PrecompileError};
+
+/// Recover the CKB secp256k1 lock arg from a signature. The input is the
+/// 32 bytes message hash followed by the 65 bytes signature in CKB format,
+/// `r || s || recovery_id`. The output is the blake160 of the compressed
+/// public key, left padded to 32 bytes.
+#[derive(Default, Clone)]
+pub struct CkbSecp256k1;
+
+impl PrecompileContract for CkbSecp256k1 {
+    const ADDRESS: H160 = axon_precompile_address(0x07);
+    const MIN_GAS: u64 = 3000;
+
+    fn exec_fn(
+        input: &[u8],
+        gas_limit: Option<u64>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> Result<(PrecompileOutput, u64), PrecompileFailure> {
+        let gas = Self::gas_cost(input);
+        if let Some(limit) = gas_limit {
+            if gas > limit {
+                return Err(PrecompileError::OutOfGas { cost: gas, limit }.into());
+            }
+        }
+
+        if input.len() != 97 {
+            return Err(PrecompileError::InvalidInput(format!(
+                "expect a 97-byte message hash and signature, got {} bytes",
+                input.len()
+            ))
+            .into());
+        }
+
+        let sig = Secp256k1RecoverableSignature::try_from(&input[32..97])
+            .map_err(|e| PrecompileError::InvalidInput(format!("invalid signature: {e}")))?;
+        let pubkey = secp256k1_recover(&input[0..32], &sig.to_bytes())
+            .map_err(|e| PrecompileError::InvalidInput(format!("recover failed: {e}")))?;
+
+        let mut output = [0u8; 32];
+        output[12..].copy_from_slice(&ckb_blake2b_256(pubkey.serialize())[0..20]);
+
+        Ok((
+            PrecompileOutput {
+                exit_status: ExitSucceed::Returned,
+                output:      output.to_vec(),
+            },
+            gas,
+        ))
+    }
+
+    fn gas_cost(_input: &[u8]) -> u64 {
+        Self::MIN_GAS
+    }
+}
@@ -0,0 +1,28 @@
+use evm::executor::stack::PrecompileFailure;
+use evm::ExitError;
+use thiserror::Error;
+
+/// The reason of a precompile failure. It is converted into an
+/// [`ExitError::Other`] so the reason shows up in the revert message.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PrecompileError {
+    #[error("Precompile out of gas, cost {cost}, limit {limit}")]
+    OutOfGas { cost: u64, limit: u64 },
+
+    #[error("Invalid precompile input: {0}")]
+    InvalidInput(String),
+
+    #[error("{0} not found")]
+    NotFound(String),
+
+    #[error("Precompile internal error: {0}")]
+    Internal(String),
+}
+
+impl From<PrecompileError> for PrecompileFailure {
+    fn from(error: PrecompileError) -> Self {
+        PrecompileFailure::Error {
+            exit_status: ExitError::Other(error.to_string().into()),
+        }
+    }
+}
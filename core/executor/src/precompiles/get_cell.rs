@@ -3,13 +3,15 @@ use ethers::abi::{AbiDecode, AbiEncode};
 use ethers::contract::{EthAbiCodec, EthAbiType};
 use ethers::core::types::Bytes as EthBytes;
 use evm::executor::stack::{PrecompileFailure, PrecompileOutput};
-use evm::{Context, ExitError, ExitSucceed};
+use evm::{Context, ExitSucceed};
 
 use protocol::types::{H160, H256};
 
-use crate::precompiles::{axon_precompile_address, PrecompileContract};
+use crate::precompiles::{axon_precompile_address, PrecompileContract, PrecompileError};
 use crate::system_contract::image_cell::{image_cell_abi, CellKey, ImageCellReader};
-use crate::{err, CURRENT_HEADER_CELL_ROOT};
+use crate::CURRENT_HEADER_CELL_ROOT;
+
+const OUTPUT_WORD_GAS: u64 = 3;
 
 #[derive(Default, Clone)]
 pub struct GetCell;
@@ -27,7 +29,7 @@ impl PrecompileContract for GetCell {
         let gas = Self::gas_cost(input);
         if let Some(limit) = gas_limit {
             if gas > limit {
-                return err!();
+                return Err(PrecompileError::OutOfGas { cost: gas, limit }.into());
             }
         }
 
@@ -36,7 +38,7 @@ impl PrecompileContract for GetCell {
         let root = CURRENT_HEADER_CELL_ROOT.with(|r| *r.borrow());
         let cell_opt = ImageCellReader
             .get_cell(root, &CellKey { tx_hash, index })
-            .map_err(|_| err!(_, "get cell"))?
+            .map_err(|e| PrecompileError::Internal(e.to_string()))?
             .map(|c| Cell {
                 cell_output:     packed::CellOutput::new_unchecked(c.cell_output).into(),
                 cell_data:       c.cell_data.into(),
@@ -45,27 +47,49 @@ impl PrecompileContract for GetCell {
                 consumed_number: c.consumed_number.unwrap_or(0),
             });
 
-        if cell_opt.is_none() {
-            return err!("get cell return None");
+        let output = cell_opt
+            .ok_or_else(|| PrecompileError::NotFound(format!("cell {tx_hash:#x}:{index}")))?
+            .encode();
+        let gas = Self::gas_cost_with_output(input, Some(output.len()));
+        if let Some(limit) = gas_limit {
+            if gas > limit {
+                return Err(PrecompileError::OutOfGas { cost: gas, limit }.into());
+            }
         }
 
         Ok((
             PrecompileOutput {
                 exit_status: ExitSucceed::Returned,
-                output:      cell_opt.unwrap().encode(),
+                output,
             },
             gas,
         ))
     }
 
-    fn gas_cost(_input: &[u8]) -> u64 {
-        Self::MIN_GAS
+    fn gas_cost(input: &[u8]) -> u64 {
+        Self::gas_cost_with_output(input, None)
+    }
+}
+
+impl GetCell {
+    /// The gas cost is `MIN_GAS + OUTPUT_WORD_GAS * word_size`, where the word
+    /// size is of the longer one of the input and the returned cell, like the
+    /// cost of copying the return data. Only `MIN_GAS` is charged if the
+    /// output length is unknown.
+    pub fn gas_cost_with_output(input: &[u8], output_len: Option<usize>) -> u64 {
+        match output_len {
+            Some(len) => {
+                let word_size = (input.len().max(len) + 31) / 32;
+                Self::MIN_GAS + word_size as u64 * OUTPUT_WORD_GAS
+            }
+            None => Self::MIN_GAS,
+        }
     }
 }
 
 fn parse_input(input: &[u8]) -> Result<(H256, u32), PrecompileFailure> {
     let out_point = <image_cell_abi::OutPoint as AbiDecode>::decode(input)
-        .map_err(|_| err!(_, "decode input"))?;
+        .map_err(|e| PrecompileError::InvalidInput(e.to_string()))?;
 
     Ok((H256(out_point.tx_hash), out_point.index))
 }
@@ -0,0 +1,89 @@
+use ethers::abi::AbiEncode;
+use ethers::contract::{EthAbiCodec, EthAbiType};
+use evm::executor::stack::{PrecompileFailure, PrecompileOutput};
+use evm::{Context, ExitSucceed};
+
+use protocol::types::H160;
+
+use crate::precompiles::{axon_precompile_address, PrecompileContract, PrecompileError};
+use crate::system_contract::metadata::MetadataHandle;
+use crate::{CURRENT_BLOCK_NUMBER, CURRENT_METADATA_ROOT};
+
+/// The gas to read a validator, the same as a cold `SLOAD`.
+const VALIDATOR_GAS: u64 = 2100;
+
+/// Return the epoch and the validators of the block being executed. The input
+/// is ignored and the output is the ABI encoded [`EpochValidators`].
+#[derive(Default, Clone)]
+pub struct GetMetadata;
+
+impl PrecompileContract for GetMetadata {
+    const ADDRESS: H160 = axon_precompile_address(0x08);
+    const MIN_GAS: u64 = 42000;
+
+    fn exec_fn(
+        input: &[u8],
+        gas_limit: Option<u64>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> Result<(PrecompileOutput, u64), PrecompileFailure> {
+        let gas = Self::gas_cost(input);
+        if let Some(limit) = gas_limit {
+            if gas > limit {
+                return Err(PrecompileError::OutOfGas { cost: gas, limit }.into());
+            }
+        }
+
+        let root = CURRENT_METADATA_ROOT.with(|r| *r.borrow());
+        let block_number = CURRENT_BLOCK_NUMBER.with(|n| *n.borrow());
+        let metadata = MetadataHandle::new(root)
+            .get_metadata_by_block_number(block_number)
+            .map_err(|e| PrecompileError::Internal(e.to_string()))?;
+
+        let gas = gas + metadata.verifier_list.len() as u64 * VALIDATOR_GAS;
+        if let Some(limit) = gas_limit {
+            if gas > limit {
+                return Err(PrecompileError::OutOfGas { cost: gas, limit }.into());
+            }
+        }
+
+        let output = EpochValidators {
+            epoch:      metadata.epoch,
+            validators: metadata
+                .verifier_list
+                .iter()
+                .map(|v| Validator {
+                    address:        v.address,
+                    propose_weight: v.propose_weight,
+                    vote_weight:    v.vote_weight,
+                })
+                .collect(),
+        }
+        .encode();
+
+        Ok((
+            PrecompileOutput {
+                exit_status: ExitSucceed::Returned,
+                output,
+            },
+            gas,
+        ))
+    }
+
+    fn gas_cost(_input: &[u8]) -> u64 {
+        Self::MIN_GAS
+    }
+}
+
+#[derive(EthAbiType, EthAbiCodec, Default, Clone, Debug, PartialEq, Eq)]
+pub struct Validator {
+    pub address:        H160,
+    pub propose_weight: u32,
+    pub vote_weight:    u32,
+}
+
+#[derive(EthAbiType, EthAbiCodec, Default, Clone, Debug, PartialEq, Eq)]
+pub struct EpochValidators {
+    pub epoch:      u64,
+    pub validators: Vec<Validator>,
+}
@@ -1,35 +1,59 @@
 mod blake2_f;
 mod call_ckb_vm;
+mod chain_id;
 mod ckb_blake2b;
+mod ckb_secp256k1;
 mod ec_add;
 mod ec_mul;
 mod ec_pairing;
 mod ecrecover;
+mod error;
 mod get_cell;
 mod get_header;
+mod get_metadata;
 mod identity;
 mod modexp;
 mod ripemd160;
 mod rsa;
 mod secp256r1;
 mod sha256;
+mod verify_ckb_header;
 
 #[cfg(test)]
 mod tests;
 
+pub use error::PrecompileError;
+pub(crate) use get_metadata::GetMetadata;
+pub(crate) use verify_ckb_header::VerifyCkbHeader;
+
+#[cfg(test)]
+pub(crate) use get_cell::{Cell, GetCell};
+#[cfg(test)]
+pub(crate) use get_metadata::{EpochValidators, Validator};
+
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
+use arc_swap::ArcSwap;
 use bn::{AffineG1, Fq, Fr, Group, G1};
 use evm::executor::stack::{PrecompileFailure, PrecompileFn, PrecompileOutput};
 use evm::{Context, ExitError};
 
-use protocol::types::H160;
+use common_config_parser::types::spec::HardforkName;
+use protocol::{types::H160, ProtocolResult};
 
+use crate::error::ExecutorError;
+use crate::is_enabled_in_block;
 use crate::precompiles::{
-    blake2_f::Blake2F, call_ckb_vm::CallCkbVM, ckb_blake2b::CkbBlake2b, ec_add::EcAdd,
-    ec_mul::EcMul, ec_pairing::EcPairing, ecrecover::EcRecover, identity::Identity, modexp::ModExp,
-    ripemd160::Ripemd160, sha256::Sha256,
+    blake2_f::Blake2F, call_ckb_vm::CallCkbVM, chain_id::ChainId, ckb_blake2b::CkbBlake2b,
+    ckb_secp256k1::CkbSecp256k1, ec_add::EcAdd, ec_mul::EcMul, ec_pairing::EcPairing,
+    ecrecover::EcRecover, identity::Identity, modexp::ModExp, ripemd160::Ripemd160, sha256::Sha256,
 };
+use crate::system_contract::is_system_contract_address_format;
+
+lazy_static::lazy_static! {
+    pub static ref PRECOMPILE_REGISTRY: PrecompileRegistry = PrecompileRegistry::default();
+}
 
 #[macro_export]
 macro_rules! err {
@@ -64,7 +88,7 @@ macro_rules! precompiles {
     }};
 }
 
-trait PrecompileContract {
+pub(crate) trait PrecompileContract {
     const ADDRESS: H160;
     const MIN_GAS: u64;
 
@@ -78,6 +102,18 @@ trait PrecompileContract {
     fn gas_cost(input: &[u8]) -> u64;
 }
 
+/// The base of the addresses of the Axon specific precompiles, such as the
+/// CKB interoperation ones. The Axon precompiles live in `base..=base + 0xff`,
+/// which is above the range `0x01..=0xff` of the Ethereum precompiles, so
+/// they never shadow a standard one.
+pub const AXON_PRECOMPILE_BASE: u16 = 0x0100;
+
+// The base must be above the Ethereum precompile range and aligned to 0x100.
+const _: () = assert!(AXON_PRECOMPILE_BASE > 0xff && AXON_PRECOMPILE_BASE & 0xff == 0);
+
+/// The address of an Ethereum precompile. Axon provides the standard ones
+/// from `0x01` to `0x09`, which are ecrecover, sha256, ripemd160, identity,
+/// modexp, bn128 add, bn128 mul, bn128 pairing and blake2f.
 const fn eip_precompile_address(addr: u8) -> H160 {
     H160([
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
@@ -85,20 +121,106 @@ const fn eip_precompile_address(addr: u8) -> H160 {
     ])
 }
 
+/// The address of an Axon precompile, which is `addr` above
+/// [`AXON_PRECOMPILE_BASE`].
 const fn axon_precompile_address(addr: u8) -> H160 {
+    let [hi, lo] = (AXON_PRECOMPILE_BASE | addr as u16).to_be_bytes();
     H160([
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00, 0x00, 0x00, 0x01, addr,
+        0x00, 0x00, 0x00, hi, lo,
     ])
 }
 
-pub fn build_precompile_set() -> BTreeMap<H160, PrecompileFn> {
+/// The registry of the precompiles registered at runtime by the integrators.
+/// The precompile set merged with the default precompiles is built only once
+/// and rebuilt on registration, so building the precompile set for each call
+/// is allocation-free.
+pub struct PrecompileRegistry {
+    set:        ArcSwap<BTreeMap<H160, PrecompileFn>>,
+    /// The set with the precompiles activated by the `Antlia` hardfork.
+    antlia_set: ArcSwap<BTreeMap<H160, PrecompileFn>>,
+}
+
+impl Default for PrecompileRegistry {
+    fn default() -> Self {
+        let set = default_precompile_set();
+        let mut antlia_set = set.clone();
+        antlia_set.extend(antlia_precompile_set());
+
+        PrecompileRegistry {
+            set:        ArcSwap::from_pointee(set),
+            antlia_set: ArcSwap::from_pointee(antlia_set),
+        }
+    }
+}
+
+impl PrecompileRegistry {
+    /// Register a precompile at the given address. The addresses reserved for
+    /// the Ethereum precompiles, the CKB interoperation precompiles and the
+    /// system contracts can not be registered.
+    pub fn register(&self, addr: H160, f: PrecompileFn) -> ProtocolResult<()> {
+        if is_reserved_precompile_address(&addr) {
+            return Err(ExecutorError::ReservedPrecompileAddress(addr).into());
+        }
+
+        for set in [&self.set, &self.antlia_set] {
+            set.rcu(|set| {
+                let mut set = BTreeMap::clone(set);
+                set.insert(addr, f);
+                set
+            });
+        }
+        Ok(())
+    }
+
+    /// The precompile set without the precompiles activated by hardforks.
+    pub fn build(&self) -> Arc<BTreeMap<H160, PrecompileFn>> {
+        self.set.load_full()
+    }
+
+    /// The precompile set to execute the block of the given number, which
+    /// includes the precompiles activated by the hardforks enabled in the
+    /// block, see [`hardfork_of_block`](crate::hardfork_of_block).
+    pub fn build_at(&self, block_number: u64) -> Arc<BTreeMap<H160, PrecompileFn>> {
+        if is_enabled_in_block(HardforkName::Antlia, block_number) {
+            self.antlia_set.load_full()
+        } else {
+            self.set.load_full()
+        }
+    }
+}
+
+fn is_reserved_precompile_address(addr: &H160) -> bool {
+    let is_precompile_format = addr.0[0..18].iter().all(|b| *b == 0)
+        && u16::from_be_bytes([addr.0[18], addr.0[19]]) <= AXON_PRECOMPILE_BASE | 0xff;
+    is_precompile_format || is_system_contract_address_format(addr)
+}
+
+fn default_precompile_set() -> BTreeMap<H160, PrecompileFn> {
     precompiles!(
         EcRecover, Sha256, Ripemd160, Identity, ModExp, EcAdd, EcMul, EcPairing, Blake2F,
         CallCkbVM, CkbBlake2b
     )
 }
 
+/// The precompiles available since the `Antlia` hardfork.
+fn antlia_precompile_set() -> BTreeMap<H160, PrecompileFn> {
+    precompiles!(GetCell, CkbSecp256k1, GetMetadata, ChainId, VerifyCkbHeader)
+}
+
+/// Return the shared precompile set, it only increases the reference count
+/// instead of rebuilding the map.
+#[cfg(test)]
+pub fn build_precompile_set() -> Arc<BTreeMap<H160, PrecompileFn>> {
+    PRECOMPILE_REGISTRY.build()
+}
+
+/// Return the shared precompile set to execute the block of the given number,
+/// see [`PrecompileRegistry::build_at`].
+pub fn build_precompile_set_at(block_number: u64) -> Arc<BTreeMap<H160, PrecompileFn>> {
+    PRECOMPILE_REGISTRY.build_at(block_number)
+}
+
 pub(crate) fn read_point(input: &[u8], start: usize) -> Result<G1, PrecompileFailure> {
     if input.len() < start + 64 {
         return err!("Invalid input length");
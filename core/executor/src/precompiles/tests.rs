@@ -1,12 +1,24 @@
-use evm::Context;
+use std::collections::BTreeMap;
+
+use evm::backend::{MemoryBackend, MemoryVicinity};
+use evm::{executor::stack::PrecompileFailure, Context, ExitError};
 use sha2::Digest;
 
-use protocol::{ckb_blake2b_256, codec::hex_decode, rand::random, types::U256};
+use protocol::traits::Executor;
+use protocol::{
+    ckb_blake2b_256,
+    codec::hex_decode,
+    rand::random,
+    types::{H160, U256},
+};
 
 use crate::precompiles::{
-    Blake2F, CkbBlake2b, EcAdd, EcMul, EcPairing, EcRecover, Identity, ModExp, PrecompileContract,
-    Ripemd160, Sha256,
+    axon_precompile_address, default_precompile_set, eip_precompile_address, get_header::GetHeader,
+    is_reserved_precompile_address, Blake2F, CallCkbVM, ChainId, CkbBlake2b, CkbSecp256k1, EcAdd,
+    EcMul, EcPairing, EcRecover, GetCell, GetMetadata, Identity, ModExp, PrecompileContract,
+    PrecompileError, Ripemd160, Sha256, VerifyCkbHeader, AXON_PRECOMPILE_BASE,
 };
+use crate::{AxonExecutor, CURRENT_CHAIN_ID};
 
 macro_rules! test_precompile {
     ($ty: ident, $input: expr, $output: expr, $expect_gas_cost: expr) => {
@@ -39,6 +51,55 @@ fn rand_bytes(len: usize) -> Vec<u8> {
     (0..len).map(|_| random::<u8>()).collect()
 }
 
+#[test]
+fn test_precompile_address_ranges() {
+    let eip = [
+        EcRecover::ADDRESS,
+        Sha256::ADDRESS,
+        Ripemd160::ADDRESS,
+        Identity::ADDRESS,
+        ModExp::ADDRESS,
+        EcAdd::ADDRESS,
+        EcMul::ADDRESS,
+        EcPairing::ADDRESS,
+        Blake2F::ADDRESS,
+    ];
+    let axon = [
+        GetHeader::ADDRESS,
+        GetCell::ADDRESS,
+        CallCkbVM::ADDRESS,
+        CkbBlake2b::ADDRESS,
+        CkbSecp256k1::ADDRESS,
+        GetMetadata::ADDRESS,
+        ChainId::ADDRESS,
+        VerifyCkbHeader::ADDRESS,
+    ];
+
+    for (i, addr) in eip.iter().enumerate() {
+        assert_eq!(*addr, eip_precompile_address(i as u8 + 1));
+    }
+
+    let axon_range = axon_precompile_address(0x00)..=axon_precompile_address(0xff);
+    assert_eq!(
+        *axon_range.start(),
+        H160::from_low_u64_be(AXON_PRECOMPILE_BASE.into())
+    );
+    for addr in axon.iter() {
+        assert!(axon_range.contains(addr));
+        assert!(!eip.contains(addr));
+        assert!(is_reserved_precompile_address(addr));
+    }
+
+    // Every default precompile is either an Ethereum one or an Axon one.
+    let set = default_precompile_set();
+    for addr in set.keys() {
+        assert!(eip.contains(addr) || axon.contains(addr));
+    }
+    for addr in eip.iter() {
+        assert!(set.contains_key(addr));
+    }
+}
+
 #[test]
 fn test_sha256() {
     let input = rand_bytes(100);
@@ -66,6 +127,17 @@ fn test_ecrecover() {
     test_precompile!(EcRecover, &input, output, 3000);
 }
 
+#[test]
+fn test_ckb_secp256k1() {
+    // The message is the ckb_blake2b_256 of "axon", signed by the CKB dev chain
+    // genesis key whose lock arg is 0xc8328aabcd9b9e8e64fbc566c4385c3bdeb219d7.
+    let input = hex_decode("da84009f44afac93ab3808219a1f67c23230e41df4fddb58dfa43871405d0186bb50e2d89a4ed70663d080659fe0ad4b9bc3e06c17a227433966cb59ceee020d74a43ec2990c136f03a64db6087cbc6c1dd8d0bedd5c3a25d4c584580ee32e2201").unwrap();
+    let output =
+        hex_decode("000000000000000000000000c8328aabcd9b9e8e64fbc566c4385c3bdeb219d7").unwrap();
+
+    test_precompile!(CkbSecp256k1, &input, output, 3000);
+}
+
 #[test]
 fn test_ripemd160() {
     let input = &[0xff];
@@ -203,3 +275,85 @@ fn test_blake2f() {
     let output = hex_decode("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923").unwrap();
     test_precompile!(Blake2F, input, output, 12);
 }
+
+#[test]
+fn test_get_cell_gas_cost() {
+    let input = rand_bytes(64);
+    assert_eq!(GetCell::gas_cost(&input), GetCell::MIN_GAS);
+    assert_eq!(
+        GetCell::gas_cost_with_output(&input, None),
+        GetCell::MIN_GAS
+    );
+
+    // The input is longer than a small output.
+    assert_eq!(
+        GetCell::gas_cost_with_output(&input, Some(1)),
+        GetCell::MIN_GAS + 2 * 3
+    );
+    assert_eq!(
+        GetCell::gas_cost_with_output(&input, Some(65)),
+        GetCell::MIN_GAS + 3 * 3
+    );
+    assert_eq!(
+        GetCell::gas_cost_with_output(&input, Some(32 * 1024)),
+        GetCell::MIN_GAS + 1024 * 3
+    );
+    assert_eq!(
+        GetCell::gas_cost_with_output(&[], Some(0)),
+        GetCell::MIN_GAS
+    );
+}
+
+#[test]
+fn test_precompile_error() {
+    let failure: PrecompileFailure = PrecompileError::OutOfGas {
+        cost:  42000,
+        limit: 21000,
+    }
+    .into();
+    assert_eq!(failure, PrecompileFailure::Error {
+        exit_status: ExitError::Other("Precompile out of gas, cost 42000, limit 21000".into()),
+    });
+
+    assert_eq!(
+        PrecompileError::InvalidInput("too short".to_string()).to_string(),
+        "Invalid precompile input: too short"
+    );
+    assert_eq!(
+        PrecompileError::NotFound("cell".to_string()).to_string(),
+        "cell not found"
+    );
+}
+
+#[test]
+fn test_chain_id() {
+    CURRENT_CHAIN_ID.with(|id| *id.borrow_mut() = U256::from(0x41786f6eu64));
+    let mut output = vec![0u8; 28];
+    output.extend_from_slice(&[0x41, 0x78, 0x6f, 0x6e]);
+    test_precompile!(ChainId, &[], output, 2);
+
+    // The chain id is read from the backend of the call.
+    let vicinity = MemoryVicinity {
+        gas_price:              U256::zero(),
+        origin:                 Default::default(),
+        chain_id:               5u64.into(),
+        block_hashes:           Vec::new(),
+        block_number:           U256::zero(),
+        block_coinbase:         Default::default(),
+        block_timestamp:        U256::zero(),
+        block_difficulty:       U256::zero(),
+        block_gas_limit:        U256::max_value(),
+        block_base_fee_per_gas: U256::zero(),
+    };
+    let backend = MemoryBackend::new(&vicinity, BTreeMap::new());
+    let resp = AxonExecutor.call(
+        &backend,
+        100_000,
+        None,
+        Some(ChainId::ADDRESS),
+        U256::zero(),
+        vec![],
+    );
+    assert!(resp.exit_reason.is_succeed());
+    assert_eq!(U256::from_big_endian(&resp.ret), 5u64.into());
+}
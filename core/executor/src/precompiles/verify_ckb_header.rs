@@ -0,0 +1,64 @@
+use evm::executor::stack::{PrecompileFailure, PrecompileOutput};
+use evm::{Context, ExitSucceed};
+
+use protocol::types::{H160, H256, U256};
+
+use crate::precompiles::{axon_precompile_address, PrecompileContract, PrecompileError};
+use crate::{system_contract::ckb_light_client::CkbHeaderReader, CURRENT_HEADER_CELL_ROOT};
+
+/// Verify a CKB header is known to Axon. The input is the 32-byte hash of the
+/// header, and the output is a 32-byte word of 1 if the header is stored in
+/// the CKB light client contract at the header cell root of the block being
+/// executed, otherwise 0. Unlike `GetHeader`, a missing header is not an
+/// error, so the contracts gated by CKB headers can branch on it.
+#[derive(Default, Clone)]
+pub struct VerifyCkbHeader;
+
+impl PrecompileContract for VerifyCkbHeader {
+    const ADDRESS: H160 = axon_precompile_address(0x0a);
+    // The same trie read as `GetHeader`.
+    const MIN_GAS: u64 = 42000;
+
+    fn exec_fn(
+        input: &[u8],
+        gas_limit: Option<u64>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> Result<(PrecompileOutput, u64), PrecompileFailure> {
+        let gas = Self::gas_cost(input);
+        if let Some(limit) = gas_limit {
+            if gas > limit {
+                return Err(PrecompileError::OutOfGas { cost: gas, limit }.into());
+            }
+        }
+
+        if input.len() != 32 {
+            return Err(PrecompileError::InvalidInput(format!(
+                "expect a 32-byte header hash, got {} bytes",
+                input.len()
+            ))
+            .into());
+        }
+
+        let root = CURRENT_HEADER_CELL_ROOT.with(|r| *r.borrow());
+        let found = CkbHeaderReader
+            .get_raw(root, input)
+            .map_err(|e| PrecompileError::Internal(e.to_string()))?
+            .is_some();
+
+        let mut output = H256::default();
+        U256::from(found as u8).to_big_endian(output.as_bytes_mut());
+
+        Ok((
+            PrecompileOutput {
+                exit_status: ExitSucceed::Returned,
+                output:      output.as_bytes().to_vec(),
+            },
+            gas,
+        ))
+    }
+
+    fn gas_cost(_input: &[u8]) -> u64 {
+        Self::MIN_GAS
+    }
+}
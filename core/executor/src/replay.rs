@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use evm::backend::{Apply, Basic};
+use serde::{Deserialize, Serialize};
+
+use protocol::traits::{ApplyBackend, Executor, ExecutorAdapter};
+use protocol::types::{
+    Bytes, ExecResp, ExecutorContext, Log, SignedTransaction, ValidatorExtend, H160, H256, U256,
+};
+use protocol::{trie::MemoryDB, ProtocolResult};
+
+use core_db::MemoryAdapter;
+use core_storage::ImplStorage;
+
+use crate::{AxonExecutor, AxonExecutorApplyAdapter, ExecutorError};
+
+/// An account of the state before replaying a block.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct ReplayAccount {
+    #[serde(default)]
+    pub nonce:   U256,
+    #[serde(default)]
+    pub balance: U256,
+    #[serde(default)]
+    pub code:    Bytes,
+    #[serde(default)]
+    pub storage: BTreeMap<H256, H256>,
+}
+
+/// A block captured as a JSON fixture for regression testing, which holds the
+/// state before the block, the transactions and the validators, and the
+/// response of executing the block if it has been recorded.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReplayFixture {
+    pub context:    ExecutorContext,
+    pub alloc:      BTreeMap<H160, ReplayAccount>,
+    pub txs:        Vec<SignedTransaction>,
+    #[serde(default)]
+    pub validators: Vec<ValidatorExtend>,
+    #[serde(default)]
+    pub resp:       Option<ExecResp>,
+}
+
+impl ReplayFixture {
+    pub fn load<P: AsRef<Path>>(path: P) -> ProtocolResult<Self> {
+        let json = std::fs::read(path.as_ref())
+            .map_err(|e| ExecutorError::InvalidReplayFixture(e.to_string()))?;
+        let fixture = serde_json::from_slice(&json)
+            .map_err(|e| ExecutorError::InvalidReplayFixture(e.to_string()))?;
+        Ok(fixture)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> ProtocolResult<()> {
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| ExecutorError::InvalidReplayFixture(e.to_string()))?;
+        std::fs::write(path.as_ref(), json)
+            .map_err(|e| ExecutorError::InvalidReplayFixture(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Execute the block on a fresh in-memory state built from the `alloc`.
+    /// Returns an error if the state root differs from the recorded one.
+    pub fn replay(&self) -> ProtocolResult<ExecResp> {
+        let mut adapter = self.build_state()?;
+        let resp = AxonExecutor.exec(&mut adapter, &self.txs, &self.validators);
+
+        if let Some(expect) = self.resp.as_ref() {
+            if expect.state_root != resp.state_root {
+                return Err(ExecutorError::ReplayStateRootMismatch {
+                    expect: expect.state_root,
+                    actual: resp.state_root,
+                }
+                .into());
+            }
+        }
+
+        Ok(resp)
+    }
+
+    /// Replay the block and record the response into the fixture, replacing
+    /// the previously recorded one.
+    pub fn record(&mut self) -> ProtocolResult<&ExecResp> {
+        self.resp = None;
+        let resp = self.replay()?;
+        Ok(self.resp.insert(resp))
+    }
+
+    fn build_state(
+        &self,
+    ) -> ProtocolResult<AxonExecutorApplyAdapter<ImplStorage<MemoryAdapter>, MemoryDB>> {
+        let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()), 20);
+        let mut adapter = AxonExecutorApplyAdapter::new(
+            Arc::new(MemoryDB::new(false)),
+            Arc::new(storage),
+            self.context.clone(),
+        )?;
+
+        let changes = self
+            .alloc
+            .iter()
+            .map(|(address, account)| Apply::Modify {
+                address:       *address,
+                basic:         Basic {
+                    balance: account.balance,
+                    nonce:   account.nonce,
+                },
+                code:          (!account.code.is_empty()).then(|| account.code.to_vec()),
+                storage:       account
+                    .storage
+                    .iter()
+                    .map(|(k, v)| (*k, *v))
+                    .collect::<Vec<_>>(),
+                reset_storage: false,
+            })
+            .collect::<Vec<_>>();
+        ApplyBackend::apply(&mut adapter, changes, Vec::<Log>::new(), false);
+        adapter.commit();
+        adapter.take_touched_accounts();
+
+        Ok(adapter)
+    }
+}
+
+/// Load a fixture and replay the block, see [`ReplayFixture::replay`].
+pub fn replay_fixture<P: AsRef<Path>>(path: P) -> ProtocolResult<ExecResp> {
+    ReplayFixture::load(path)?.replay()
+}
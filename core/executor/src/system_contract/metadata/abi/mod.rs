@@ -1,8 +1,8 @@
 pub mod metadata_abi;
 
 use protocol::types::{
-    CkbRelatedInfo, ConsensusConfig, Hex, Metadata, MetadataVersion, ProposeCount, ValidatorExtend,
-    H256,
+    default_max_call_depth, CkbRelatedInfo, ConsensusConfig, Hex, Metadata, MetadataVersion,
+    ProposeCount, ValidatorExtend, H256,
 };
 
 impl From<metadata_abi::Metadata> for Metadata {
@@ -48,6 +48,8 @@ impl From<ConsensusConfig> for metadata_abi::ConsensusConfig {
     }
 }
 
+/// The call depth is not in the ABI before `updateConsensusConfigV1`, so it
+/// is the default.
 impl From<metadata_abi::ConsensusConfig> for ConsensusConfig {
     fn from(value: metadata_abi::ConsensusConfig) -> Self {
         ConsensusConfig {
@@ -60,6 +62,41 @@ impl From<metadata_abi::ConsensusConfig> for ConsensusConfig {
             gas_limit:          value.gas_limit,
             interval:           value.interval,
             max_contract_limit: value.max_contract_limit,
+            max_call_depth:     default_max_call_depth(),
+        }
+    }
+}
+
+impl From<ConsensusConfig> for metadata_abi::ConsensusConfigV1 {
+    fn from(value: ConsensusConfig) -> Self {
+        metadata_abi::ConsensusConfigV1 {
+            propose_ratio:      value.propose_ratio,
+            prevote_ratio:      value.prevote_ratio,
+            precommit_ratio:    value.precommit_ratio,
+            brake_ratio:        value.brake_ratio,
+            tx_num_limit:       value.tx_num_limit,
+            max_tx_size:        value.max_tx_size,
+            gas_limit:          value.gas_limit,
+            interval:           value.interval,
+            max_contract_limit: value.max_contract_limit,
+            max_call_depth:     value.max_call_depth,
+        }
+    }
+}
+
+impl From<metadata_abi::ConsensusConfigV1> for ConsensusConfig {
+    fn from(value: metadata_abi::ConsensusConfigV1) -> Self {
+        ConsensusConfig {
+            propose_ratio:      value.propose_ratio,
+            prevote_ratio:      value.prevote_ratio,
+            precommit_ratio:    value.precommit_ratio,
+            brake_ratio:        value.brake_ratio,
+            tx_num_limit:       value.tx_num_limit,
+            max_tx_size:        value.max_tx_size,
+            gas_limit:          value.gas_limit,
+            interval:           value.interval,
+            max_contract_limit: value.max_contract_limit,
+            max_call_depth:     value.max_call_depth,
         }
     }
 }
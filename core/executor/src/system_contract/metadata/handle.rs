@@ -3,7 +3,7 @@ use protocol::ProtocolResult;
 
 use std::sync::Arc;
 
-use crate::system_contract::metadata::{MetadataStore, HARDFORK_INFO};
+use crate::system_contract::metadata::{MetadataStore, HARDFORK_INFO, HARDFORK_REGISTRY};
 
 /// The MetadataHandle is used to expose apis that can be accessed from outside
 /// of the system contract.
@@ -55,11 +55,11 @@ impl MetadataHandle {
     }
 
     pub fn init_hardfork(&self, block_number: u64) -> ProtocolResult<()> {
-        let hardfork = MetadataStore::new(self.root)?
-            .hardfork_info(block_number)
-            .unwrap();
+        let store = MetadataStore::new(self.root)?;
+        let hardfork = store.hardfork_info(block_number).unwrap();
 
         HARDFORK_INFO.swap(Arc::new(hardfork));
+        HARDFORK_REGISTRY.swap(Arc::new(store.hardfork_infos()?));
         Ok(())
     }
 
@@ -10,19 +10,24 @@ pub use store::{encode_consensus_config, MetadataStore};
 use std::{num::NonZeroUsize, sync::Arc};
 
 use arc_swap::ArcSwap;
+use common_config_parser::types::spec::HardforkName;
 use ethers::abi::AbiDecode;
 use lru::LruCache;
 use parking_lot::RwLock;
 
 use protocol::codec::ProtocolCodec;
 use protocol::traits::{ApplyBackend, ExecutorAdapter};
-use protocol::types::{HardforkInfoInner, Hasher, Metadata, SignedTransaction, TxResp, H160, H256};
+use protocol::types::{
+    default_max_call_depth, ConsensusConfig, HardforkInfo, HardforkInfoInner, Hasher, Metadata,
+    SignedTransaction, TxResp, H160, H256,
+};
+use protocol::ProtocolResult;
 
 use crate::system_contract::utils::{
     generate_mpt_root_changes, revert_resp, succeed_resp, update_states,
 };
 use crate::system_contract::{system_contract_address, SystemContract};
-use crate::{exec_try, system_contract_struct, CURRENT_METADATA_ROOT};
+use crate::{exec_try, is_enabled_in_block, system_contract_struct, CURRENT_METADATA_ROOT};
 
 type Epoch = u64;
 
@@ -35,6 +40,7 @@ lazy_static::lazy_static! {
     pub static ref CONSENSUS_CONFIG: H256 = Hasher::digest("consensus_config");
     pub static ref HARDFORK_KEY: H256 = Hasher::digest("hardfork");
     pub static ref HARDFORK_INFO: ArcSwap<H256> = ArcSwap::new(Arc::new(H256::zero()));
+    pub static ref HARDFORK_REGISTRY: ArcSwap<HardforkInfo> = ArcSwap::new(Arc::new(HardforkInfo::default()));
     static ref METADATA_CACHE: RwLock<LruCache<Epoch, Metadata>> =  RwLock::new(LruCache::new(METADATA_CACHE_SIZE));
 }
 
@@ -79,8 +85,14 @@ impl<Adapter: ExecutorAdapter + ApplyBackend> SystemContract<Adapter>
 
         match call_abi {
             metadata_abi::MetadataContractCalls::AppendMetadata(c) => {
+                let mut metadata: Metadata = c.metadata.into();
+                metadata.consensus_config.max_call_depth = exec_try!(
+                    current_max_call_depth(&store, block_number),
+                    gas_limit,
+                    "[metadata] get max call depth"
+                );
                 exec_try!(
-                    store.append_metadata(&c.metadata.into()),
+                    store.append_metadata(&metadata),
                     gas_limit,
                     "[metadata] append metadata"
                 );
@@ -93,12 +105,31 @@ impl<Adapter: ExecutorAdapter + ApplyBackend> SystemContract<Adapter>
                 );
             }
             metadata_abi::MetadataContractCalls::UpdateConsensusConfig(c) => {
+                let mut config: ConsensusConfig = c.config.into();
+                config.max_call_depth = exec_try!(
+                    current_max_call_depth(&store, block_number),
+                    gas_limit,
+                    "[metadata] get max call depth"
+                );
                 exec_try!(
-                    store.update_consensus_config(c.config.into()),
+                    store.update_consensus_config(config),
                     gas_limit,
                     "[metadata] update consensus config"
                 );
             }
+            metadata_abi::MetadataContractCalls::UpdateConsensusConfigV1(c) => {
+                // The call depth is modifiable since the Antlia hardfork, before
+                // which the call is rejected the same as an unknown function.
+                if !is_enabled_in_block(HardforkName::Antlia, block_number) {
+                    return revert_resp(gas_limit);
+                }
+
+                exec_try!(
+                    store.update_consensus_config(c.config.into()),
+                    gas_limit,
+                    "[metadata] update consensus config v1"
+                );
+            }
         }
 
         update_states(adapter, sender, Self::ADDRESS);
@@ -127,6 +158,7 @@ impl<Adapter: ExecutorAdapter + ApplyBackend> SystemContract<Adapter>
         let hardfork = store.hardfork_info(block_number.as_u64()).unwrap();
 
         HARDFORK_INFO.swap(Arc::new(hardfork));
+        HARDFORK_REGISTRY.swap(Arc::new(store.hardfork_infos().unwrap()));
 
         if let Err(e) = store.update_propose_count(block_number.as_u64(), &adapter.origin()) {
             panic!("Update propose count at {:?} failed: {:?}", block_number, e)
@@ -137,6 +169,16 @@ impl<Adapter: ExecutorAdapter + ApplyBackend> SystemContract<Adapter>
     }
 }
 
+/// The call depth is only in the ABI of `updateConsensusConfigV1`, so the
+/// calls with the previous ABI keep the current one.
+fn current_max_call_depth(store: &MetadataStore, block_number: u64) -> ProtocolResult<u64> {
+    if block_number == 0 {
+        return Ok(default_max_call_depth());
+    }
+
+    Ok(store.get_consensus_config()?.max_call_depth)
+}
+
 pub fn check_ckb_related_info_exist(root: H256) -> bool {
     MetadataHandle::new(root).get_ckb_related_info().is_ok()
 }
@@ -4,8 +4,8 @@ use std::sync::Arc;
 use common_config_parser::types::spec::HardforkName;
 use protocol::trie::Trie as _;
 use protocol::types::{
-    CkbRelatedInfo, ConsensusConfig, ConsensusConfigV0, HardforkInfo, HardforkInfoInner, Metadata,
-    MetadataInner, H160, H256,
+    CkbRelatedInfo, ConsensusConfig, ConsensusConfigV0, ConsensusConfigV1, HardforkInfo,
+    HardforkInfoInner, Metadata, MetadataInner, H160, H256,
 };
 use protocol::{codec::ProtocolCodec, ProtocolResult};
 
@@ -245,17 +245,7 @@ impl MetadataStore {
 
     pub fn hardfork_info(&self, target_number: u64) -> ProtocolResult<H256> {
         match self.trie.get(HARDFORK_KEY.as_bytes())? {
-            Some(data) => {
-                let hardfork_info = HardforkInfo::decode(data)?;
-
-                for k in hardfork_info.inner.iter().rev() {
-                    if k.block_number > target_number {
-                        continue;
-                    }
-                    return Ok(k.flags);
-                }
-                Ok(H256::zero())
-            }
+            Some(data) => Ok(HardforkInfo::decode(data)?.flags_at(target_number)),
             None => Ok(H256::zero()),
         }
     }
@@ -272,6 +262,7 @@ impl MetadataStore {
 enum ConsensusConfigFlag {
     V0 = 0b0,
     V1 = 0b1,
+    V2 = 0b10,
 }
 
 impl From<u16> for ConsensusConfigFlag {
@@ -279,6 +270,7 @@ impl From<u16> for ConsensusConfigFlag {
         match value {
             0b0 => ConsensusConfigFlag::V0,
             0b1 => ConsensusConfigFlag::V1,
+            0b10 => ConsensusConfigFlag::V2,
             _ => unreachable!(),
         }
     }
@@ -287,9 +279,11 @@ impl From<u16> for ConsensusConfigFlag {
 impl ConsensusConfigFlag {
     fn new(flags: H256) -> Self {
         let v1_name_flag = H256::from_low_u64_be((HardforkName::Andromeda as u64).to_be());
-        let res = flags & v1_name_flag;
+        let v2_name_flag = H256::from_low_u64_be((HardforkName::Antlia as u64).to_be());
 
-        if res & v1_name_flag == v1_name_flag {
+        if flags & v2_name_flag == v2_name_flag {
+            ConsensusConfigFlag::V2
+        } else if flags & v1_name_flag == v1_name_flag {
             ConsensusConfigFlag::V1
         } else {
             ConsensusConfigFlag::V0
@@ -308,7 +302,8 @@ fn decode_consensus_config(raw: Vec<u8>) -> ProtocolResult<ConsensusConfig> {
 
     match flag {
         ConsensusConfigFlag::V0 => ConsensusConfigV0::decode(&raw[2..]).map(Into::into),
-        ConsensusConfigFlag::V1 => ConsensusConfig::decode(&raw[2..]),
+        ConsensusConfigFlag::V1 => ConsensusConfigV1::decode(&raw[2..]).map(Into::into),
+        ConsensusConfigFlag::V2 => ConsensusConfig::decode(&raw[2..]),
     }
 }
 
@@ -320,7 +315,8 @@ pub fn encode_consensus_config(
 
     let config_bytes = match flag {
         ConsensusConfigFlag::V0 => Into::<ConsensusConfigV0>::into(config).encode()?,
-        ConsensusConfigFlag::V1 => config.encode()?,
+        ConsensusConfigFlag::V1 => Into::<ConsensusConfigV1>::into(config).encode()?,
+        ConsensusConfigFlag::V2 => config.encode()?,
     };
     let mut res = (flag as u16).to_be_bytes().to_vec();
     res.extend(config_bytes.to_vec());
@@ -3,7 +3,7 @@ mod native_token;
 mod utils;
 
 pub(crate) mod ckb_light_client;
-pub(crate) mod image_cell;
+pub mod image_cell;
 pub mod metadata;
 
 pub use crate::system_contract::ckb_light_client::{
@@ -24,22 +24,25 @@ use ckb_traits::{CellDataProvider, ExtensionProvider, HeaderProvider};
 use ckb_types::core::cell::{CellProvider, CellStatus};
 use ckb_types::core::{HeaderBuilder, HeaderView};
 use ckb_types::{packed, prelude::*};
+use common_config_parser::types::spec::HardforkName;
 use evm::backend::ApplyBackend;
 use parking_lot::RwLock;
 use rocksdb::DB;
 
 use protocol::traits::{CkbDataProvider, ExecutorAdapter};
 use protocol::types::{
-    Bytes, HardforkInfoInner, Hasher, Metadata, SignedTransaction, TransactionAction, TxResp, H160,
-    H256,
+    Bytes, ExitError, ExitReason, HardforkInfoInner, Hasher, Metadata, SignedTransaction,
+    TransactionAction, TxResp, H160, H256, U256,
 };
 use protocol::{ckb_blake2b_256, ProtocolResult};
 
 use crate::adapter::RocksTrieDB;
+use crate::is_enabled_in_block;
 use crate::system_contract::{
     ckb_light_client::CkbHeaderReader, image_cell::ImageCellReader, metadata::MetadataStore,
     utils::generate_mpt_root_changes,
 };
+use crate::utils::{checked_prepay_gas, deduct_prepay_gas, refund_remaining_gas};
 
 pub const fn system_contract_address(addr: u8) -> H160 {
     H160([
@@ -214,25 +217,71 @@ pub fn after_block_hook<Adapter: ExecutorAdapter + ApplyBackend>(adapter: &mut A
     ImageCellContract::default().after_block_hook(adapter);
 }
 
+/// Dispatch a transaction calling a system contract to its handler. `None` is
+/// returned if the transaction does not call a system contract address, then
+/// it is executed by the EVM.
+///
+/// A call to a system contract address always takes the system contract path
+/// whatever its data is, and the EVM is never invoked for it even if there is
+/// code at the address.
 pub fn system_contract_dispatch<Adapter: ExecutorAdapter + ApplyBackend>(
     adapter: &mut Adapter,
     tx: &SignedTransaction,
 ) -> Option<TxResp> {
-    if let Some(addr) = tx.get_to() {
-        log::debug!("execute addr {:#x}", addr);
-
-        if addr == NATIVE_TOKEN_CONTRACT_ADDRESS {
-            return Some(NativeTokenContract::default().exec_(adapter, tx));
-        } else if addr == METADATA_CONTRACT_ADDRESS {
-            return Some(MetadataContract::default().exec_(adapter, tx));
-        } else if addr == CKB_LIGHT_CLIENT_CONTRACT_ADDRESS {
-            return Some(CkbLightClientContract::default().exec_(adapter, tx));
-        } else if addr == IMAGE_CELL_CONTRACT_ADDRESS {
-            return Some(ImageCellContract::default().exec_(adapter, tx));
-        }
+    let addr = tx.get_to()?;
+    if !SYSTEM_CONTRACT_ADDRESSES_SET.contains(&addr) {
+        return None;
+    }
+
+    log::debug!("execute addr {:#x}", addr);
+
+    // Since the `Antlia` hardfork the gas is prepaid and the remaining is refunded
+    // the same as the EVM transactions, and the fee of the used gas is collected.
+    // Before it the system contract calls are free.
+    let block_number = adapter.block_number().low_u64();
+    if !is_enabled_in_block(HardforkName::Antlia, block_number) {
+        return Some(exec_system_contract(adapter, addr, tx));
     }
 
-    None
+    let gas_price = tx.transaction.unsigned.gas_price();
+    let prepay_gas = match checked_prepay_gas(*tx.transaction.unsigned.gas_limit(), gas_price) {
+        Ok((_, prepay_gas)) if adapter.get_account(&tx.sender).balance >= prepay_gas => prepay_gas,
+        Ok(_) => {
+            return Some(TxResp {
+                exit_reason: ExitReason::Error(ExitError::OutOfFund),
+                ..Default::default()
+            })
+        }
+        Err(exit_reason) => {
+            return Some(TxResp {
+                exit_reason,
+                ..Default::default()
+            })
+        }
+    };
+    deduct_prepay_gas(adapter, tx.sender, prepay_gas);
+
+    let mut resp = exec_system_contract(adapter, addr, tx);
+    refund_remaining_gas(adapter, tx.sender, resp.remain_gas, gas_price);
+    // The used gas is no more than the gas limit, so the fee never overflows.
+    resp.fee_cost = gas_price * U256::from(resp.gas_used);
+    Some(resp)
+}
+
+fn exec_system_contract<Adapter: ExecutorAdapter + ApplyBackend>(
+    adapter: &mut Adapter,
+    addr: H160,
+    tx: &SignedTransaction,
+) -> TxResp {
+    if addr == NATIVE_TOKEN_CONTRACT_ADDRESS {
+        NativeTokenContract::default().exec_(adapter, tx)
+    } else if addr == METADATA_CONTRACT_ADDRESS {
+        MetadataContract::default().exec_(adapter, tx)
+    } else if addr == CKB_LIGHT_CLIENT_CONTRACT_ADDRESS {
+        CkbLightClientContract::default().exec_(adapter, tx)
+    } else {
+        ImageCellContract::default().exec_(adapter, tx)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -322,6 +371,31 @@ pub fn is_system_contract_address_format(addr: &H160) -> bool {
     addr.0[0..19] == SYSTEM_CONTRACT_ADDRESSES_PREFIX
 }
 
+/// The kinds of the system contracts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SystemContractKind {
+    NativeToken,
+    Metadata,
+    CkbLightClient,
+    ImageCell,
+}
+
+/// Return which system contract the address maps to, or `None` if the address
+/// is not a system contract.
+pub fn system_contract_kind(addr: &H160) -> Option<SystemContractKind> {
+    if *addr == NATIVE_TOKEN_CONTRACT_ADDRESS {
+        Some(SystemContractKind::NativeToken)
+    } else if *addr == METADATA_CONTRACT_ADDRESS {
+        Some(SystemContractKind::Metadata)
+    } else if *addr == CKB_LIGHT_CLIENT_CONTRACT_ADDRESS {
+        Some(SystemContractKind::CkbLightClient)
+    } else if *addr == IMAGE_CELL_CONTRACT_ADDRESS {
+        Some(SystemContractKind::ImageCell)
+    } else {
+        None
+    }
+}
+
 pub fn is_call_system_script(action: &TransactionAction) -> ProtocolResult<bool> {
     let call_addr = match action {
         TransactionAction::Call(addr) => addr,
@@ -376,4 +450,30 @@ mod tests {
         let action = TransactionAction::Call(addr);
         assert!(is_call_system_script(&action).is_err());
     }
+
+    #[test]
+    fn test_system_contract_kind() {
+        assert_eq!(
+            system_contract_kind(&NATIVE_TOKEN_CONTRACT_ADDRESS),
+            Some(SystemContractKind::NativeToken)
+        );
+        assert_eq!(
+            system_contract_kind(&METADATA_CONTRACT_ADDRESS),
+            Some(SystemContractKind::Metadata)
+        );
+        assert_eq!(
+            system_contract_kind(&CKB_LIGHT_CLIENT_CONTRACT_ADDRESS),
+            Some(SystemContractKind::CkbLightClient)
+        );
+        assert_eq!(
+            system_contract_kind(&IMAGE_CELL_CONTRACT_ADDRESS),
+            Some(SystemContractKind::ImageCell)
+        );
+
+        assert!(SYSTEM_CONTRACT_ADDRESSES_SET
+            .iter()
+            .all(|addr| system_contract_kind(addr).is_some()));
+        assert_eq!(system_contract_kind(&system_contract_address(0x4)), None);
+        assert_eq!(system_contract_kind(&H160::from_low_u64_be(0x1)), None);
+    }
 }
@@ -1,30 +1,60 @@
+mod replay;
 mod system_script;
 
 use std::collections::BTreeMap;
+use std::io;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
 
-use evm::backend::{MemoryAccount, MemoryVicinity};
-use evm::Config;
+use evm::backend::{Apply, Basic, MemoryAccount, MemoryBackend, MemoryVicinity};
+use evm::executor::stack::{PrecompileFailure, PrecompileFn, PrecompileOutput};
+use evm::{Config, Context, Opcode};
 
+use protocol::traits::{ApplyBackend, Backend, Executor, ExecutorAdapter, ExecutorReadOnlyAdapter};
+use protocol::trie::{MemoryDB, Trie as _, DB as TrieDB};
 use protocol::types::{
-    Bytes, Eip1559Transaction, ExecutorContext, ExitReason, ExitSucceed, Public,
-    SignatureComponents, SignedTransaction, TransactionAction, UnsignedTransaction,
-    UnverifiedTransaction, H160, H256, U256,
+    decode_receipt, AccessListItem, Account, Bytes, Eip1559Transaction, Eip4844Transaction,
+    ExecutorContext, ExitError, ExitReason, ExitSucceed, HardforkInfo, HardforkInfoInner, Hasher,
+    Hex, LegacyTransaction, Public, SignatureComponents, SignedTransaction, TransactionAction,
+    TxResp, UnsignedTransaction, UnverifiedTransaction, ValidatorExtend, H160, H256, NIL_DATA,
+    RLP_NULL, U256,
+};
+use protocol::{
+    codec::{hex_decode, ProtocolCodec},
+    tokio, ProtocolResult,
 };
-use protocol::{codec::hex_decode, tokio, traits::Executor, trie::MemoryDB};
 
+use common_config_parser::types::spec::HardforkName;
+use common_merkle::TrieMerkle;
 use core_db::MemoryAdapter;
 use core_storage::ImplStorage;
 
-use crate::AxonExecutorApplyAdapter;
+use crate::system_contract::{
+    metadata::HARDFORK_REGISTRY, CKB_LIGHT_CLIENT_CONTRACT_ADDRESS, HEADER_CELL_ROOT_KEY,
+    METADATA_CONTRACT_ADDRESS,
+};
+use crate::{
+    code_address, hardfork_of_block, is_enabled_in_block, predicted_create_address, state_diff,
+    AxonExecutorApplyAdapter, AxonExecutorReadOnlyAdapter, BlockEnv, BlockHookObserver,
+    DefaultFeeAllocator, DiffKind, ExecControl, ExecutorError, FeeAllocate, FeeInlet, MPTTrie,
+    NonceCheck, NoopTracer, SystemContractRoots, Tracer, AXON_PRECOMPILE_BASE, BLOCK_HOOK_OBSERVER,
+    PRECOMPILE_REGISTRY,
+};
 use crate::{precompiles::build_precompile_set, AxonExecutor as EvmExecutor, AxonExecutor};
 
 fn exec_adapter() -> AxonExecutorApplyAdapter<ImplStorage<MemoryAdapter>, MemoryDB> {
+    exec_adapter_at(0)
+}
+
+fn exec_adapter_at(
+    block_number: u64,
+) -> AxonExecutorApplyAdapter<ImplStorage<MemoryAdapter>, MemoryDB> {
     let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()), 20);
     let ctx = ExecutorContext {
+        block_number: block_number.into(),
         block_gas_limit: u32::MAX.into(),
-        block_base_fee_per_gas: U256::one(),
+        block_base_fee_per_gas: U256::zero(),
         ..Default::default()
     };
 
@@ -48,8 +78,8 @@ fn gen_vicinity() -> MemoryVicinity {
 
 fn gen_tx(sender: H160, addr: H160, value: u64, data: Vec<u8>) -> SignedTransaction {
     SignedTransaction {
-        transaction: UnverifiedTransaction {
-            unsigned:  UnsignedTransaction::Eip1559(Eip1559Transaction {
+        transaction: UnverifiedTransaction::new(
+            UnsignedTransaction::Eip1559(Eip1559Transaction {
                 nonce:                    U256::default(),
                 max_priority_fee_per_gas: U256::default(),
                 gas_price:                U256::default(),
@@ -59,19 +89,45 @@ fn gen_tx(sender: H160, addr: H160, value: u64, data: Vec<u8>) -> SignedTransact
                 data:                     data.into(),
                 access_list:              Vec::new(),
             }),
-            signature: Some(SignatureComponents {
+            Some(SignatureComponents {
                 standard_v: 0,
                 r:          Bytes::default(),
                 s:          Bytes::default(),
             }),
-            chain_id:  Some(0u64),
-            hash:      H256::default(),
-        },
+            Some(0u64),
+            H256::default(),
+        ),
         sender,
         public: Some(Public::default()),
     }
 }
 
+/// The hardforks are read from the global registry, which is also updated by
+/// the after block hook. The tests scheduling the hardforks or running the
+/// hooks at a non-zero block hold this lock to not interfere with each other.
+static HARDFORK_LOCK: Mutex<()> = Mutex::new(());
+
+fn hardfork_lock() -> MutexGuard<'static, ()> {
+    HARDFORK_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Schedule the hardforks since the given block number while `f` runs, the
+/// registry is reset after.
+fn with_hardforks<T>(block_number: u64, hardforks: &[HardforkName], f: impl FnOnce() -> T) -> T {
+    let _lock = hardfork_lock();
+    let flags = hardforks.iter().fold(0u64, |acc, name| acc | *name as u64);
+    let mut registry = HardforkInfo::default();
+    registry.push(HardforkInfoInner {
+        block_number,
+        flags: H256::from_low_u64_be(flags.to_be()),
+    });
+
+    HARDFORK_REGISTRY.store(Arc::new(registry));
+    let ret = f();
+    HARDFORK_REGISTRY.store(Arc::new(HardforkInfo::default()));
+    ret
+}
+
 #[test]
 fn test_ackermann31() {
     let mut state = BTreeMap::new();
@@ -195,3 +251,2229 @@ async fn test_simplestorage() {
     );
     assert_eq!(r.exit_reason, ExitReason::Succeed(ExitSucceed::Stopped));
 }
+
+fn echo_precompile(
+    input: &[u8],
+    _gas_limit: Option<u64>,
+    _context: &Context,
+    _is_static: bool,
+) -> Result<(PrecompileOutput, u64), PrecompileFailure> {
+    Ok((
+        PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output:      input.to_vec(),
+        },
+        0,
+    ))
+}
+
+#[test]
+fn test_precompile_registry() {
+    let addr = H160::from_low_u64_be(0x0200);
+    PRECOMPILE_REGISTRY
+        .register(addr, echo_precompile as PrecompileFn)
+        .unwrap();
+    assert!(build_precompile_set().contains_key(&addr));
+    // The precompile set is shared rather than rebuilt.
+    assert!(Arc::ptr_eq(
+        &build_precompile_set(),
+        &build_precompile_set()
+    ));
+
+    // The reserved addresses can not be registered.
+    for reserved in [
+        H160::from_low_u64_be(0x04),
+        H160::from_low_u64_be(0x0105),
+        METADATA_CONTRACT_ADDRESS,
+    ] {
+        assert!(PRECOMPILE_REGISTRY
+            .register(reserved, echo_precompile as PrecompileFn)
+            .is_err());
+    }
+
+    let vicinity = gen_vicinity();
+    let backend = MemoryBackend::new(&vicinity, BTreeMap::new());
+    let r = AxonExecutor.call(&backend, u64::MAX, None, Some(addr), U256::zero(), vec![
+        1, 2, 3,
+    ]);
+    assert_eq!(r.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+    assert_eq!(r.ret, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_precompile_activation() {
+    // The precompiles available since the `Antlia` hardfork.
+    let activated = [0x03, 0x07, 0x08, 0x09, 0x0a]
+        .map(|addr| H160::from_low_u64_be((AXON_PRECOMPILE_BASE | addr).into()));
+
+    with_hardforks(100, &[HardforkName::Antlia], || {
+        let (before, after) = (
+            PRECOMPILE_REGISTRY.build_at(100),
+            PRECOMPILE_REGISTRY.build_at(101),
+        );
+        for addr in activated.iter() {
+            assert!(!before.contains_key(addr));
+            assert!(after.contains_key(addr));
+        }
+        assert!(before.keys().all(|addr| after.contains_key(addr)));
+    });
+}
+
+fn block_timestamp_precompile(
+    _input: &[u8],
+    _gas_limit: Option<u64>,
+    _context: &Context,
+    _is_static: bool,
+) -> Result<(PrecompileOutput, u64), PrecompileFailure> {
+    let mut output = [0u8; 32];
+    BlockEnv::current().timestamp.to_big_endian(&mut output);
+    Ok((
+        PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output:      output.to_vec(),
+        },
+        0,
+    ))
+}
+
+#[test]
+fn test_block_env_precompile() {
+    let addr = H160::from_low_u64_be(0x0201);
+    PRECOMPILE_REGISTRY
+        .register(addr, block_timestamp_precompile as PrecompileFn)
+        .unwrap();
+
+    let mut vicinity = gen_vicinity();
+    vicinity.block_number = 7u64.into();
+    vicinity.block_timestamp = 1_700_000_000u64.into();
+    vicinity.block_coinbase = H160::random();
+    let backend = MemoryBackend::new(&vicinity, BTreeMap::new());
+
+    let r = AxonExecutor.call(&backend, u64::MAX, None, Some(addr), U256::zero(), vec![]);
+    assert_eq!(r.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+    assert_eq!(U256::from_big_endian(&r.ret), 1_700_000_000u64.into());
+    assert_eq!(BlockEnv::current(), BlockEnv {
+        number:    7,
+        timestamp: 1_700_000_000u64.into(),
+        coinbase:  vicinity.block_coinbase,
+        base_fee:  U256::zero(),
+    });
+}
+
+#[test]
+fn test_call_with_logs() {
+    let contract = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let mut state = BTreeMap::new();
+    // PUSH1 0x00 PUSH1 0x00 LOG0 STOP
+    state.insert(contract, MemoryAccount {
+        nonce:   U256::one(),
+        balance: U256::zero(),
+        storage: BTreeMap::new(),
+        code:    hex_decode("60006000a000").unwrap(),
+    });
+    let vicinity = gen_vicinity();
+    let backend = MemoryBackend::new(&vicinity, state);
+
+    let r = AxonExecutor.call(
+        &backend,
+        u64::MAX,
+        None,
+        Some(contract),
+        U256::zero(),
+        vec![],
+    );
+    assert_eq!(r.exit_reason, ExitReason::Succeed(ExitSucceed::Stopped));
+    assert!(r.logs.is_empty());
+
+    let r = AxonExecutor.call_with_logs(
+        &backend,
+        u64::MAX,
+        None,
+        Some(contract),
+        U256::zero(),
+        vec![],
+    );
+    assert_eq!(r.exit_reason, ExitReason::Succeed(ExitSucceed::Stopped));
+    assert_eq!(r.logs.len(), 1);
+    assert_eq!(r.logs[0].address, contract);
+    assert!(r.logs[0].topics.is_empty());
+}
+
+struct ExcessiveFeeAllocator;
+
+impl FeeAllocate for ExcessiveFeeAllocator {
+    fn allocate(
+        &self,
+        _block_number: U256,
+        fee_collect: U256,
+        proposer: H160,
+        _validators: &[ValidatorExtend],
+    ) -> ProtocolResult<Vec<FeeInlet>> {
+        Ok(vec![FeeInlet {
+            address: proposer,
+            amount:  fee_collect + U256::one(),
+        }])
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_allocate_fee() {
+    let mut adapter = exec_adapter();
+    let validator = ValidatorExtend {
+        bls_pub_key:    Hex::empty(),
+        pub_key:        Hex::empty(),
+        address:        H160::random(),
+        propose_weight: 1,
+        vote_weight:    1,
+    };
+    let fee = U256::from(100u64);
+
+    let res =
+        AxonExecutor::allocate_fee(&mut adapter, &ExcessiveFeeAllocator, U256::one(), fee, &[
+            validator.clone(),
+        ]);
+    assert!(res.is_err());
+    assert!(adapter.get_account(&adapter.origin()).balance.is_zero());
+
+    AxonExecutor::allocate_fee(&mut adapter, &DefaultFeeAllocator, U256::one(), fee, &[
+        validator.clone(),
+    ])
+    .unwrap();
+    assert_eq!(adapter.get_account(&validator.address).balance, fee);
+}
+
+struct DuplicateFeeAllocator;
+
+impl FeeAllocate for DuplicateFeeAllocator {
+    fn allocate(
+        &self,
+        _block_number: U256,
+        fee_collect: U256,
+        proposer: H160,
+        _validators: &[ValidatorExtend],
+    ) -> ProtocolResult<Vec<FeeInlet>> {
+        let half = fee_collect / 2;
+        Ok(vec![
+            FeeInlet {
+                address: proposer,
+                amount:  half,
+            },
+            FeeInlet {
+                address: proposer,
+                amount:  fee_collect - half,
+            },
+        ])
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_allocate_fee_aggregate() {
+    let mut adapter = exec_adapter();
+    let proposer = adapter.origin();
+    let fee = U256::from(101u64);
+
+    let inlets = DuplicateFeeAllocator
+        .allocate(U256::one(), fee, proposer, &[])
+        .unwrap();
+    assert_eq!(FeeInlet::aggregate(inlets), vec![FeeInlet {
+        address: proposer,
+        amount:  fee,
+    }]);
+
+    AxonExecutor::allocate_fee(&mut adapter, &DuplicateFeeAllocator, U256::one(), fee, &[])
+        .unwrap();
+    assert_eq!(adapter.get_account(&proposer).balance, fee);
+}
+
+#[derive(Default)]
+struct CountingFeeAllocator {
+    calls:         std::sync::atomic::AtomicUsize,
+    allocate_zero: bool,
+}
+
+impl FeeAllocate for CountingFeeAllocator {
+    fn allocate(
+        &self,
+        block_number: U256,
+        fee_collect: U256,
+        proposer: H160,
+        validators: &[ValidatorExtend],
+    ) -> ProtocolResult<Vec<FeeInlet>> {
+        self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        DefaultFeeAllocator.allocate(block_number, fee_collect, proposer, validators)
+    }
+
+    fn allocate_zero_fee(&self) -> bool {
+        self.allocate_zero
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_allocate_zero_fee() {
+    let mut adapter = exec_adapter();
+    let validator = ValidatorExtend {
+        bls_pub_key:    Hex::empty(),
+        pub_key:        Hex::empty(),
+        address:        H160::random(),
+        propose_weight: 1,
+        vote_weight:    1,
+    };
+    let root = adapter.commit();
+
+    AxonExecutor::allocate_fee(
+        &mut adapter,
+        &DefaultFeeAllocator,
+        U256::one(),
+        U256::zero(),
+        &[validator.clone()],
+    )
+    .unwrap();
+    assert_eq!(adapter.commit(), root);
+    assert!(!adapter.exists(validator.address));
+
+    let allocator = CountingFeeAllocator::default();
+    AxonExecutor::allocate_fee(&mut adapter, &allocator, U256::one(), U256::zero(), &[
+        validator.clone(),
+    ])
+    .unwrap();
+    assert_eq!(allocator.calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+    let allocator = CountingFeeAllocator {
+        allocate_zero: true,
+        ..Default::default()
+    };
+    AxonExecutor::allocate_fee(&mut adapter, &allocator, U256::one(), U256::zero(), &[
+        validator,
+    ])
+    .unwrap();
+    assert_eq!(allocator.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert_eq!(adapter.commit(), root);
+}
+
+/// Pay a fixed reward to the proposer besides the collected fee.
+struct BlockRewardAllocator(U256);
+
+impl FeeAllocate for BlockRewardAllocator {
+    fn allocate(
+        &self,
+        _block_number: U256,
+        fee_collect: U256,
+        proposer: H160,
+        _validators: &[ValidatorExtend],
+    ) -> ProtocolResult<Vec<FeeInlet>> {
+        Ok(vec![FeeInlet {
+            address: proposer,
+            amount:  fee_collect + self.0,
+        }])
+    }
+
+    fn allocate_zero_fee(&self) -> bool {
+        true
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_allocate_block_reward() {
+    let mut adapter = exec_adapter();
+    let proposer = adapter.origin();
+    let reward = U256::from(10u64);
+
+    // The reward of an empty block is minted to the proposer.
+    AxonExecutor::allocate_fee(
+        &mut adapter,
+        &BlockRewardAllocator(reward),
+        U256::one(),
+        U256::zero(),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(adapter.get_account(&proposer).balance, reward);
+
+    AxonExecutor::allocate_fee(
+        &mut adapter,
+        &BlockRewardAllocator(reward),
+        U256::from(2u64),
+        U256::from(5u64),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        adapter.get_account(&proposer).balance,
+        reward * 2 + U256::from(5u64)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_zero_gas_price_tx() {
+    // The after block hook updates the hardfork registry.
+    let _lock = hardfork_lock();
+    let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()), 20);
+    let ctx = ExecutorContext {
+        block_number: U256::one(),
+        block_gas_limit: u32::MAX.into(),
+        block_base_fee_per_gas: U256::zero(),
+        ..Default::default()
+    };
+    let mut adapter =
+        AxonExecutorApplyAdapter::new(Arc::new(MemoryDB::new(false)), Arc::new(storage), ctx)
+            .unwrap();
+
+    let (sender, receiver) = (H160::random(), H160::random());
+    adapter.save_account(&sender, &Account {
+        nonce:        U256::zero(),
+        balance:      100u64.into(),
+        storage_root: RLP_NULL,
+        code_hash:    NIL_DATA,
+    });
+    let validator = ValidatorExtend {
+        bls_pub_key:    Hex::empty(),
+        pub_key:        Hex::empty(),
+        address:        H160::random(),
+        propose_weight: 1,
+        vote_weight:    1,
+    };
+
+    // The gas price of the generated transaction is zero.
+    let tx = gen_tx(sender, receiver, 10, vec![]);
+    assert!(tx.gas_price().is_zero());
+    let resp = AxonExecutor.exec(&mut adapter, &[tx], &[validator.clone()]);
+
+    assert!(resp.tx_resp[0].exit_reason.is_succeed());
+    assert!(resp.tx_resp[0].fee_cost.is_zero());
+    assert!(!resp.fee_overflow);
+    assert_eq!(adapter.basic(sender).balance, 90u64.into());
+    assert_eq!(adapter.basic(receiver).balance, 10u64.into());
+
+    // No fee is collected, so nothing is allocated to the validator.
+    assert!(!resp.touched_accounts.contains(&validator.address));
+    assert!(!adapter.exists(validator.address));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_touched_accounts() {
+    // The after block hook updates the hardfork registry.
+    let _lock = hardfork_lock();
+    let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()), 20);
+    let ctx = ExecutorContext {
+        block_number: U256::one(),
+        block_gas_limit: u32::MAX.into(),
+        block_base_fee_per_gas: U256::zero(),
+        ..Default::default()
+    };
+    let mut adapter =
+        AxonExecutorApplyAdapter::new(Arc::new(MemoryDB::new(false)), Arc::new(storage), ctx)
+            .unwrap();
+
+    let (sender, recipient) = (H160::random(), H160::random());
+    adapter.save_account(&sender, &Account {
+        nonce:        U256::zero(),
+        balance:      u64::MAX.into(),
+        storage_root: RLP_NULL,
+        code_hash:    NIL_DATA,
+    });
+    assert_eq!(adapter.take_touched_accounts(), vec![sender]);
+    assert!(adapter.take_touched_accounts().is_empty());
+
+    let mut tx = gen_tx(sender, recipient, 10, vec![]);
+    if let UnsignedTransaction::Eip1559(ref mut utx) = tx.transaction.unsigned {
+        utx.gas_price = U256::one();
+        utx.max_priority_fee_per_gas = U256::one();
+    }
+    let proposer = ValidatorExtend {
+        bls_pub_key:    Hex::empty(),
+        pub_key:        Hex::empty(),
+        address:        H160::random(),
+        propose_weight: 1,
+        vote_weight:    1,
+    };
+
+    let resp = AxonExecutor.exec(&mut adapter, &[tx], &[proposer.clone()]);
+    assert!(resp.tx_resp[0].exit_reason.is_succeed());
+    for address in [sender, recipient, proposer.address] {
+        assert!(resp.touched_accounts.contains(&address));
+    }
+    assert!(adapter.take_touched_accounts().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fee_overflow() {
+    let mut adapter = exec_adapter();
+    let txs = vec![gen_tx(H160::random(), H160::random(), 0, vec![]); 2];
+    let gen_res = |fee_costs: &[U256]| {
+        fee_costs
+            .iter()
+            .map(|fee_cost| TxResp {
+                fee_cost: *fee_cost,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let res = gen_res(&[U256::max_value() - 1, U256::one()]);
+    let resp = AxonExecutor::end_block(&mut adapter, &txs, res, &[], false, false).unwrap();
+    assert!(!resp.fee_overflow);
+
+    let res = gen_res(&[U256::max_value(), U256::one()]);
+    let resp = AxonExecutor::end_block(&mut adapter, &txs, res, &[], false, false).unwrap();
+    assert!(resp.fee_overflow);
+}
+
+#[test]
+fn test_call_create_address() {
+    let sender = H160::random();
+    let nonce = U256::from(5u64);
+    let mut state = BTreeMap::new();
+    state.insert(sender, MemoryAccount {
+        nonce,
+        balance: U256::zero(),
+        storage: BTreeMap::new(),
+        code: Vec::new(),
+    });
+    let vicinity = gen_vicinity();
+    let backend = MemoryBackend::new(&vicinity, state);
+
+    // PUSH1 0x2a PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+    let r = AxonExecutor.call(
+        &backend,
+        u64::MAX,
+        Some(sender),
+        None,
+        U256::zero(),
+        hex_decode("602a60005260206000f3").unwrap(),
+    );
+    assert!(r.exit_reason.is_succeed());
+    assert_eq!(
+        r.code_address.map(H160::from),
+        Some(predicted_create_address(sender, nonce))
+    );
+    assert_eq!(
+        predicted_create_address(sender, nonce),
+        H160::from(code_address(&sender, &nonce))
+    );
+}
+
+#[test]
+fn test_call_with_step_limit() {
+    let (looping, returning) = (H160::random(), H160::random());
+    let mut state = BTreeMap::new();
+    // JUMPDEST PUSH1 0x00 JUMP
+    state.insert(looping, MemoryAccount {
+        nonce:   U256::one(),
+        balance: U256::zero(),
+        storage: BTreeMap::new(),
+        code:    hex_decode("5b600056").unwrap(),
+    });
+    // PUSH1 0x2a PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+    state.insert(returning, MemoryAccount {
+        nonce:   U256::one(),
+        balance: U256::zero(),
+        storage: BTreeMap::new(),
+        code:    hex_decode("602a60005260206000f3").unwrap(),
+    });
+    let vicinity = gen_vicinity();
+    let backend = MemoryBackend::new(&vicinity, state);
+    let call = |to, step_limit| {
+        AxonExecutor.call_with_step_limit(
+            &backend,
+            u64::MAX,
+            None,
+            Some(to),
+            U256::zero(),
+            vec![],
+            step_limit,
+        )
+    };
+
+    let r = call(looping, Some(10_000));
+    assert_eq!(r.exit_reason, ExitReason::Error(ExitError::OutOfGas));
+    assert_eq!(r.gas_used, u64::MAX);
+    assert_eq!(r.remain_gas, 0);
+
+    let r = call(returning, Some(6));
+    assert_eq!(r.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+    assert_eq!(U256::from_big_endian(&r.ret), 42u64.into());
+    assert_eq!(call(returning, None), r);
+
+    let r = call(returning, Some(5));
+    assert_eq!(r.exit_reason, ExitReason::Error(ExitError::OutOfGas));
+}
+
+#[test]
+fn test_estimate_gas() {
+    let contract = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let invalid = H160::from_str("0x2000000000000000000000000000000000000000").unwrap();
+    let mut state = BTreeMap::new();
+    // PUSH1 0x00 PUSH1 0x00 LOG0 STOP
+    state.insert(contract, MemoryAccount {
+        nonce:   U256::one(),
+        balance: U256::zero(),
+        storage: BTreeMap::new(),
+        code:    hex_decode("60006000a000").unwrap(),
+    });
+    // INVALID
+    state.insert(invalid, MemoryAccount {
+        nonce:   U256::one(),
+        balance: U256::zero(),
+        storage: BTreeMap::new(),
+        code:    hex_decode("fe").unwrap(),
+    });
+    let vicinity = gen_vicinity();
+    let backend = MemoryBackend::new(&vicinity, state);
+
+    let used_gas = AxonExecutor
+        .call(
+            &backend,
+            u32::MAX as u64,
+            None,
+            Some(contract),
+            U256::zero(),
+            vec![],
+        )
+        .gas_used;
+    let estimate = AxonExecutor
+        .estimate_gas(
+            &backend,
+            None,
+            Some(contract),
+            U256::zero(),
+            vec![],
+            u32::MAX as u64,
+        )
+        .unwrap();
+    assert_eq!(estimate, used_gas);
+
+    let r = AxonExecutor.call(
+        &backend,
+        estimate - 1,
+        None,
+        Some(contract),
+        U256::zero(),
+        vec![],
+    );
+    assert!(!r.exit_reason.is_succeed());
+
+    let r = AxonExecutor.estimate_gas(
+        &backend,
+        None,
+        Some(invalid),
+        U256::zero(),
+        vec![],
+        u32::MAX as u64,
+    );
+    assert!(r.is_err());
+}
+
+#[test]
+fn test_create_access_list() {
+    let reader = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let store = H160::from_str("0x2000000000000000000000000000000000000000").unwrap();
+    let mut state = BTreeMap::new();
+    // PUSH1 0x01 SLOAD POP, then STATICCALL the store contract
+    state.insert(reader, MemoryAccount {
+        nonce:   U256::one(),
+        balance: U256::zero(),
+        storage: BTreeMap::new(),
+        code:    hex_decode(
+            "6001545060006000600060007320000000000000000000000000000000000000005afa00",
+        )
+        .unwrap(),
+    });
+    // PUSH1 0x00 SLOAD STOP
+    state.insert(store, MemoryAccount {
+        nonce:   U256::one(),
+        balance: U256::zero(),
+        storage: BTreeMap::new(),
+        code:    hex_decode("60005400").unwrap(),
+    });
+    let vicinity = gen_vicinity();
+    let backend = MemoryBackend::new(&vicinity, state);
+
+    let (access_list, gas_used) = AxonExecutor.create_access_list(
+        &backend,
+        None,
+        Some(reader),
+        U256::zero(),
+        vec![],
+        u32::MAX as u64,
+    );
+    assert_eq!(access_list, vec![
+        AccessListItem {
+            address:      reader,
+            storage_keys: vec![H256::from_low_u64_be(1)],
+        },
+        AccessListItem {
+            address:      store,
+            storage_keys: vec![H256::zero()],
+        },
+    ]);
+    // The access list cost of two accounts and two storage keys is charged.
+    assert!(gas_used >= 21000 + 2 * 2400 + 2 * 1900);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_try_exec() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let receiver = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let tx_1 = gen_tx(sender, receiver, 0, vec![]);
+    let tx_3 = gen_tx(sender, receiver, 0, vec![]);
+    // A gas limit exceeding u64 can not be charged, so the transaction is
+    // excluded from the block.
+    let mut tx_2 = gen_tx(sender, receiver, 0, vec![]);
+    if let UnsignedTransaction::Eip1559(ref mut tx) = tx_2.transaction.unsigned {
+        tx.gas_limit = U256::max_value();
+    }
+
+    let mut adapter = exec_adapter();
+    let (resp, failures) = AxonExecutor
+        .try_exec(&mut adapter, &[tx_1.clone(), tx_2, tx_3.clone()], &[])
+        .unwrap();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].0, 1);
+    assert!(matches!(
+        failures[0].1,
+        ExecutorError::InvalidTransaction { .. }
+    ));
+    assert_eq!(resp.tx_resp.len(), 2);
+
+    let mut adapter = exec_adapter();
+    let expect = AxonExecutor.exec(&mut adapter, &[tx_1, tx_3], &[]);
+    assert_eq!(resp.state_root, expect.state_root);
+    assert_eq!(resp.receipt_root, expect.receipt_root);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_exec_cumulative_gas_used() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let receiver = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let txs = vec![
+        gen_tx(sender, receiver, 0, vec![]),
+        gen_tx(sender, receiver, 0, vec![1, 2, 3]),
+        gen_tx(sender, receiver, 0, vec![0, 0]),
+    ];
+
+    let mut adapter = exec_adapter();
+    let resp = AxonExecutor.exec(&mut adapter, &txs, &[]);
+    assert_eq!(resp.cumulative_gas_used, vec![21000, 42048, 63056]);
+    assert_eq!(resp.cumulative_gas_used.last(), Some(&resp.gas_used));
+
+    let resp = AxonExecutor.exec(&mut adapter, &[], &[]);
+    assert!(resp.cumulative_gas_used.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reject_eip4844_tx() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let receiver = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let mut tx = gen_tx(sender, receiver, 0, vec![]);
+    tx.transaction.unsigned = UnsignedTransaction::Eip4844(Eip4844Transaction {
+        nonce:                    U256::default(),
+        max_priority_fee_per_gas: U256::default(),
+        gas_price:                U256::default(),
+        gas_limit:                U256::from_str("0x1000000000").unwrap(),
+        action:                   TransactionAction::Call(receiver),
+        value:                    U256::default(),
+        data:                     Bytes::default(),
+        access_list:              Vec::new(),
+        max_fee_per_blob_gas:     U256::one(),
+        blob_versioned_hashes:    vec![H256::random()],
+    });
+
+    let mut adapter = exec_adapter();
+    let resp = AxonExecutor.exec(&mut adapter, &[tx], &[]);
+    assert_eq!(
+        resp.tx_resp[0].exit_reason,
+        ExitReason::Error(ExitError::Other("blob transaction not supported".into()))
+    );
+    assert_eq!(resp.gas_used, 0);
+    assert_eq!(adapter.get_account(&sender).nonce, U256::zero());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_exec_encoded_receipts() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let receiver = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let txs = vec![
+        gen_tx(sender, receiver, 0, vec![]),
+        gen_tx(sender, receiver, 0, vec![1, 2, 3]),
+    ];
+
+    let mut adapter = exec_adapter();
+    let resp = AxonExecutor.exec(&mut adapter, &txs, &[]);
+    assert_eq!(resp.encoded_receipts.len(), txs.len());
+    assert_eq!(
+        TrieMerkle::from_receipts(&resp.encoded_receipts)
+            .root_hash()
+            .unwrap(),
+        resp.receipt_root
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_failed_create_receipt() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    // PUSH1 0x00 PUSH1 0x00 REVERT
+    let mut tx = gen_tx(
+        sender,
+        H160::default(),
+        0,
+        hex_decode("60006000fd").unwrap(),
+    );
+    tx.transaction
+        .unsigned
+        .set_action(TransactionAction::Create);
+
+    let mut adapter = exec_adapter();
+    let resp = AxonExecutor.exec(&mut adapter, &[tx], &[]);
+    assert!(matches!(resp.tx_resp[0].exit_reason, ExitReason::Revert(_)));
+    assert!(resp.tx_resp[0].code_address.is_none());
+
+    let (status, ..) = decode_receipt(&resp.encoded_receipts[0]).unwrap();
+    assert_eq!(status, 0);
+    assert_eq!(adapter.get_account(&sender).nonce, U256::one());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_get_accounts() {
+    let mut adapter = exec_adapter();
+    let addrs = (0..5).map(|_| H160::random()).collect::<Vec<_>>();
+    for (i, addr) in addrs.iter().take(3).enumerate() {
+        adapter.save_account(addr, &Account {
+            nonce:        (i as u64).into(),
+            balance:      (i as u64 * 100).into(),
+            storage_root: RLP_NULL,
+            code_hash:    NIL_DATA,
+        });
+    }
+
+    let accounts = adapter.get_accounts(&addrs);
+    assert_eq!(accounts.len(), addrs.len());
+    for (addr, account) in addrs.iter().zip(accounts.iter()) {
+        assert_eq!(account, &adapter.get_account(addr));
+    }
+    assert!(adapter.get_accounts(&[]).is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_read_storage_at_root() {
+    let db = Arc::new(MemoryDB::new(false));
+    let storage = Arc::new(ImplStorage::new(Arc::new(MemoryAdapter::new()), 20));
+    let mut adapter =
+        AxonExecutorApplyAdapter::new(Arc::clone(&db), Arc::clone(&storage), Default::default())
+            .unwrap();
+    let addr = H160::random();
+    let index = H256::from_low_u64_be(1);
+
+    let mut roots = Vec::new();
+    for value in [1u64, 2] {
+        let basic = Basic {
+            balance: value.into(),
+            nonce:   U256::zero(),
+        };
+        ApplyBackend::apply(
+            &mut adapter,
+            vec![Apply::Modify {
+                address: addr,
+                basic,
+                code: None,
+                storage: vec![(index, H256::from_low_u64_be(value))],
+                reset_storage: false,
+            }],
+            vec![],
+            false,
+        );
+        roots.push(adapter.commit());
+    }
+
+    for (root, value) in roots.into_iter().zip([1u64, 2]) {
+        let reader =
+            AxonExecutorReadOnlyAdapter::at_root(Arc::clone(&db), Arc::clone(&storage), root)
+                .unwrap();
+        assert_eq!(reader.storage(addr, index), H256::from_low_u64_be(value));
+        assert_eq!(reader.get_account(&addr).balance, value.into());
+    }
+
+    assert!(
+        AxonExecutorReadOnlyAdapter::at_root(Arc::clone(&db), storage, H256::random()).is_err()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_check_nonce() {
+    let db = Arc::new(MemoryDB::new(false));
+    let storage = Arc::new(ImplStorage::new(Arc::new(MemoryAdapter::new()), 20));
+    let mut adapter =
+        AxonExecutorApplyAdapter::new(Arc::clone(&db), Arc::clone(&storage), Default::default())
+            .unwrap();
+    let sender = H160::random();
+    adapter.save_account(&sender, &Account {
+        nonce:        5u64.into(),
+        balance:      U256::zero(),
+        storage_root: RLP_NULL,
+        code_hash:    NIL_DATA,
+    });
+    let root = adapter.commit();
+
+    let reader = AxonExecutorReadOnlyAdapter::at_root(db, storage, root).unwrap();
+    assert_eq!(reader.check_nonce(&sender, 5u64.into()), NonceCheck::Ready);
+    assert_eq!(reader.check_nonce(&sender, 4u64.into()), NonceCheck::Stale);
+    assert_eq!(
+        reader.check_nonce(&sender, 8u64.into()),
+        NonceCheck::Queued(3u64.into())
+    );
+    assert!(reader.is_valid_next_nonce(&sender, 5u64.into()));
+    assert!(!reader.is_valid_next_nonce(&sender, 4u64.into()));
+    assert!(!reader.is_valid_next_nonce(&sender, 6u64.into()));
+
+    // The absent account starts from zero.
+    assert!(reader.is_valid_next_nonce(&H160::random(), U256::zero()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_exec_streaming() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let receiver = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let txs = vec![gen_tx(sender, receiver, 0, vec![]); 3];
+
+    let mut adapter = exec_adapter();
+    let mut executed = vec![];
+    let resp = AxonExecutor.exec_streaming(&mut adapter, &txs, &[], |idx, _| {
+        executed.push(idx);
+        if idx == 1 {
+            ExecControl::Stop
+        } else {
+            ExecControl::Continue
+        }
+    });
+    assert_eq!(executed, vec![0, 1]);
+    assert_eq!(resp.tx_resp.len(), 2);
+
+    let mut adapter = exec_adapter();
+    let expect = AxonExecutor.exec(&mut adapter, &txs[..2], &[]);
+    assert_eq!(resp.state_root, expect.state_root);
+    assert_eq!(resp.receipt_root, expect.receipt_root);
+    assert_eq!(resp.gas_used, expect.gas_used);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_exec_within_block_gas_limit() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let receiver = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let mut tx = gen_tx(sender, receiver, 0, vec![]);
+    if let UnsignedTransaction::Eip1559(ref mut utx) = tx.transaction.unsigned {
+        utx.gas_limit = 21000u64.into();
+    }
+    let txs = vec![tx; 3];
+
+    let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()), 20);
+    let ctx = ExecutorContext {
+        block_gas_limit: 50000u64.into(),
+        block_base_fee_per_gas: U256::zero(),
+        ..Default::default()
+    };
+    let mut adapter =
+        AxonExecutorApplyAdapter::new(Arc::new(MemoryDB::new(false)), Arc::new(storage), ctx)
+            .unwrap();
+
+    // The third transaction can not be covered by the rest 8000 gas.
+    let (resp, excluded) = AxonExecutor.exec_within_block_gas_limit(&mut adapter, &txs, &[]);
+    assert_eq!(resp.tx_resp.len(), 2);
+    assert_eq!(resp.gas_used, 42000);
+    assert_eq!(excluded, &txs[2..]);
+
+    let mut adapter = exec_adapter();
+    let expect = AxonExecutor.exec(&mut adapter, &txs[..2], &[]);
+    assert_eq!(resp.state_root, expect.state_root);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dry_run_block() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let receiver = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let txs = vec![gen_tx(sender, receiver, 0, vec![])];
+
+    let db = Arc::new(MemoryDB::new(false));
+    let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()), 20);
+    let ctx = ExecutorContext {
+        block_gas_limit: u32::MAX.into(),
+        block_base_fee_per_gas: U256::zero(),
+        ..Default::default()
+    };
+    let mut adapter =
+        AxonExecutorApplyAdapter::new(Arc::clone(&db), Arc::new(storage), ctx).unwrap();
+    let origin_root = adapter.commit();
+    let observer = Arc::new(RecordObserver::default());
+    BLOCK_HOOK_OBSERVER.store(Some(Arc::new(Box::new(Arc::clone(&observer)))));
+    let dry_run = AxonExecutor.dry_run_block(&mut adapter, &txs, &[]).unwrap();
+    BLOCK_HOOK_OBSERVER.store(None);
+    assert_eq!(dry_run.tx_resp.len(), 1);
+
+    // Nothing is changed by the dry run, and its trie nodes are not persisted.
+    // The block hooks are not observed either.
+    assert!(!db.contains(dry_run.state_root.as_bytes()).unwrap());
+    assert!(!observer
+        .after
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(_, root)| *root == dry_run.state_root));
+    assert_eq!(adapter.commit(), origin_root);
+    assert_eq!(adapter.get_account(&sender).nonce, U256::zero());
+
+    // The block hooks are skipped in the dry run.
+    let resp = AxonExecutor.exec_with_hooks(&mut adapter, &txs, &[], false);
+    assert_eq!(resp.state_root, dry_run.state_root);
+    assert_eq!(resp.receipt_root, dry_run.receipt_root);
+    assert_eq!(resp.gas_used, dry_run.gas_used);
+    assert_eq!(adapter.get_account(&sender).nonce, U256::one());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_max_account_nonce() {
+    let config = Config::london();
+    let precompiles = build_precompile_set();
+    let (sender, receiver) = (H160::random(), H160::random());
+
+    for (nonce, succeed) in [(u64::MAX - 1, true), (u64::MAX, false)] {
+        let mut adapter = exec_adapter();
+        adapter.save_account(&sender, &Account {
+            nonce:        nonce.into(),
+            balance:      100u64.into(),
+            storage_root: RLP_NULL,
+            code_hash:    NIL_DATA,
+        });
+
+        let tx = gen_tx(sender, receiver, 10, vec![]);
+        let r = EvmExecutor::evm_exec(&mut adapter, &config, &precompiles, &tx);
+
+        if succeed {
+            assert!(r.exit_reason.is_succeed());
+            assert_eq!(adapter.basic(sender).nonce, u64::MAX.into());
+        } else {
+            assert_eq!(
+                r.exit_reason,
+                ExitReason::Error(ExitError::Other("nonce too high".into()))
+            );
+            assert_eq!(adapter.basic(sender).nonce, u64::MAX.into());
+            assert_eq!(adapter.basic(sender).balance, 100u64.into());
+            assert_eq!(adapter.basic(receiver).balance, U256::zero());
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_gas_overflow() {
+    let config = Config::london();
+    let precompiles = build_precompile_set();
+    let sender = H160::random();
+
+    for (gas_price, gas_limit, reason) in [
+        (
+            U256::max_value(),
+            U256::from(21000u64),
+            "pre-pay gas overflow",
+        ),
+        (
+            U256::one(),
+            U256::from(u64::MAX) + 1,
+            "gas limit exceeds u64",
+        ),
+    ] {
+        let mut adapter = exec_adapter();
+        adapter.save_account(&sender, &Account {
+            nonce:        U256::zero(),
+            balance:      U256::max_value(),
+            storage_root: RLP_NULL,
+            code_hash:    NIL_DATA,
+        });
+
+        let mut tx = gen_tx(sender, H160::random(), 0, vec![]);
+        if let UnsignedTransaction::Eip1559(ref mut utx) = tx.transaction.unsigned {
+            utx.gas_price = gas_price;
+            utx.max_priority_fee_per_gas = gas_price;
+            utx.gas_limit = gas_limit;
+        }
+
+        let r = EvmExecutor::evm_exec(&mut adapter, &config, &precompiles, &tx);
+        assert_eq!(
+            r.exit_reason,
+            ExitReason::Error(ExitError::Other(reason.into()))
+        );
+        assert_eq!(adapter.basic(sender).nonce, U256::zero());
+        assert_eq!(adapter.basic(sender).balance, U256::max_value());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_snapshot_revert() {
+    let (sender, receiver) = (H160::random(), H160::random());
+    let mut adapter = exec_adapter();
+    adapter.save_account(&sender, &Account {
+        nonce:        U256::zero(),
+        balance:      100u64.into(),
+        storage_root: RLP_NULL,
+        code_hash:    NIL_DATA,
+    });
+
+    let root = adapter.commit();
+
+    let config = Config::london();
+    let precompiles = build_precompile_set();
+    let snapshot = adapter.snapshot();
+
+    let tx = gen_tx(sender, receiver, 10, vec![]);
+    let r = EvmExecutor::evm_exec(&mut adapter, &config, &precompiles, &tx);
+    assert!(r.exit_reason.is_succeed());
+    assert_eq!(adapter.basic(sender).balance, 90u64.into());
+    assert_eq!(adapter.basic(receiver).balance, 10u64.into());
+
+    // Revert a later snapshot then the earlier one.
+    let later = adapter.snapshot();
+    let tx = gen_tx(sender, receiver, 20, vec![]);
+    EvmExecutor::evm_exec(&mut adapter, &config, &precompiles, &tx);
+    assert_eq!(adapter.basic(receiver).balance, 30u64.into());
+    adapter.revert_to(later);
+    assert_eq!(adapter.basic(receiver).balance, 10u64.into());
+
+    adapter.revert_to(snapshot);
+    assert_eq!(adapter.basic(sender).balance, 100u64.into());
+    assert_eq!(adapter.basic(sender).nonce, U256::zero());
+    assert_eq!(adapter.basic(receiver).balance, U256::zero());
+    assert!(!adapter.exists(receiver));
+    assert_eq!(adapter.commit(), root);
+}
+
+/// A trie database counting the writes to it.
+struct CountingDB {
+    inner:  MemoryDB,
+    writes: AtomicUsize,
+}
+
+impl TrieDB for CountingDB {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, io::Error> {
+        self.inner.get(key)
+    }
+
+    fn contains(&self, key: &[u8]) -> Result<bool, io::Error> {
+        self.inner.contains(key)
+    }
+
+    fn insert(&self, key: Vec<u8>, value: Vec<u8>) -> Result<(), io::Error> {
+        self.writes.fetch_add(1, Ordering::SeqCst);
+        self.inner.insert(key, value)
+    }
+
+    fn insert_batch(&self, keys: Vec<Vec<u8>>, values: Vec<Vec<u8>>) -> Result<(), io::Error> {
+        self.writes.fetch_add(keys.len(), Ordering::SeqCst);
+        self.inner.insert_batch(keys, values)
+    }
+
+    fn remove(&self, key: &[u8]) -> Result<(), io::Error> {
+        self.writes.fetch_add(1, Ordering::SeqCst);
+        self.inner.remove(key)
+    }
+
+    fn remove_batch(&self, keys: &[Vec<u8>]) -> Result<(), io::Error> {
+        self.writes.fetch_add(keys.len(), Ordering::SeqCst);
+        self.inner.remove_batch(keys)
+    }
+
+    fn flush(&self) -> Result<(), io::Error> {
+        self.inner.flush()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_snapshot_revert_without_db_writes() {
+    let db = Arc::new(CountingDB {
+        inner:  MemoryDB::new(false),
+        writes: AtomicUsize::new(0),
+    });
+    let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()), 20);
+    let ctx = ExecutorContext {
+        block_gas_limit: u32::MAX.into(),
+        ..Default::default()
+    };
+    let mut adapter =
+        AxonExecutorApplyAdapter::new(Arc::clone(&db), Arc::new(storage), ctx).unwrap();
+    let (sender, receiver) = (H160::random(), H160::random());
+    adapter.save_account(&sender, &Account {
+        nonce:        U256::zero(),
+        balance:      100u64.into(),
+        storage_root: RLP_NULL,
+        code_hash:    NIL_DATA,
+    });
+    let root = adapter.commit();
+    let writes = db.writes.load(Ordering::SeqCst);
+
+    let snapshot = adapter.snapshot();
+    let tx = gen_tx(sender, receiver, 10, vec![]);
+    let r = EvmExecutor::evm_exec(
+        &mut adapter,
+        &Config::london(),
+        &build_precompile_set(),
+        &tx,
+    );
+    assert!(r.exit_reason.is_succeed());
+    adapter.revert_to(snapshot);
+
+    // Neither the transfer nor the snapshot and the revert write to the
+    // database.
+    assert_eq!(db.writes.load(Ordering::SeqCst), writes);
+    assert_eq!(adapter.basic(sender).balance, 100u64.into());
+    assert!(!adapter.exists(receiver));
+    assert_eq!(adapter.commit(), root);
+}
+
+#[derive(Default)]
+struct StepRecorder {
+    steps: Vec<(usize, Opcode, u64, usize)>,
+}
+
+impl Tracer for StepRecorder {
+    fn step(&mut self, pc: usize, opcode: Opcode, gas_remaining: u64, stack_depth: usize) {
+        self.steps.push((pc, opcode, gas_remaining, stack_depth));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_exec_with_tracer() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    // PUSH1 0x01 PUSH1 0x02 ADD STOP
+    let mut tx = gen_tx(sender, H160::zero(), 0, hex_decode("600160020100").unwrap());
+    if let UnsignedTransaction::Eip1559(ref mut utx) = tx.transaction.unsigned {
+        utx.action = TransactionAction::Create;
+    }
+    let txs = vec![tx];
+
+    let mut adapter = exec_adapter();
+    let (resp, recorder) =
+        AxonExecutor.exec_with_tracer(&mut adapter, &txs, &[], StepRecorder::default());
+    assert!(resp.tx_resp[0].exit_reason.is_succeed());
+
+    let steps = recorder
+        .steps
+        .iter()
+        .map(|(pc, opcode, _, depth)| (*pc, *opcode, *depth))
+        .collect::<Vec<_>>();
+    assert_eq!(steps, vec![
+        (0, Opcode::PUSH1, 0),
+        (2, Opcode::PUSH1, 1),
+        (4, Opcode::ADD, 2),
+        (5, Opcode::STOP, 1),
+    ]);
+    assert!(recorder.steps.windows(2).all(|w| w[0].2 > w[1].2));
+
+    let mut adapter = exec_adapter();
+    let (noop_resp, _) = AxonExecutor.exec_with_tracer(&mut adapter, &txs, &[], NoopTracer);
+    assert_eq!(noop_resp.state_root, resp.state_root);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_access_list_gas() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let receiver = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let (key_1, key_2) = (H256::random(), H256::random());
+    let mut tx = gen_tx(sender, receiver, 0, vec![]);
+    if let UnsignedTransaction::Eip1559(ref mut utx) = tx.transaction.unsigned {
+        utx.access_list = vec![
+            AccessListItem {
+                address:      receiver,
+                storage_keys: vec![key_1, key_2, key_1],
+            },
+            AccessListItem {
+                address:      receiver,
+                storage_keys: vec![key_2],
+            },
+        ];
+    }
+
+    let mut adapter = exec_adapter();
+    let r = EvmExecutor::evm_exec(
+        &mut adapter,
+        &Config::london(),
+        &build_precompile_set(),
+        &tx,
+    );
+    assert!(r.exit_reason.is_succeed());
+    // The address and each storage key are charged only once.
+    assert_eq!(r.gas_used, 21000 + 2400 + 1900 * 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_create_access_list_gas() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let created = H160::from(code_address(&sender, &U256::zero()));
+    // ADDRESS BALANCE POP PUSH1 0x00 SLOAD POP STOP
+    let init_code = hex_decode("3031506000545000").unwrap();
+    let intrinsic_gas = 21000 + 32000 + 16 * 6 + 4 * 2;
+
+    let mut tx = gen_tx(sender, H160::zero(), 0, init_code);
+    if let UnsignedTransaction::Eip1559(ref mut utx) = tx.transaction.unsigned {
+        utx.action = TransactionAction::Create;
+    }
+
+    // The created address is warm without being listed, the storage is cold.
+    let mut adapter = exec_adapter();
+    let r = EvmExecutor::evm_exec(
+        &mut adapter,
+        &Config::london(),
+        &build_precompile_set(),
+        &tx,
+    );
+    assert!(r.exit_reason.is_succeed());
+    assert_eq!(r.code_address.map(H160::from), Some(created));
+    assert_eq!(r.gas_used, intrinsic_gas + 2 + 100 + 2 + 3 + 2100 + 2);
+
+    // The listed storage key of the created contract is charged warm rates.
+    if let UnsignedTransaction::Eip1559(ref mut utx) = tx.transaction.unsigned {
+        utx.access_list = vec![AccessListItem {
+            address:      created,
+            storage_keys: vec![H256::zero()],
+        }];
+    }
+    let mut adapter = exec_adapter();
+    let r = EvmExecutor::evm_exec(
+        &mut adapter,
+        &Config::london(),
+        &build_precompile_set(),
+        &tx,
+    );
+    assert!(r.exit_reason.is_succeed());
+    assert_eq!(
+        r.gas_used,
+        intrinsic_gas + 2400 + 1900 + 2 + 100 + 2 + 3 + 100 + 2
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_eip1559_base_fee() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let receiver = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let balance = U256::from(u64::MAX);
+    let new_adapter = |block_number: u64| {
+        let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()), 20);
+        let ctx = ExecutorContext {
+            block_number: block_number.into(),
+            block_gas_limit: u32::MAX.into(),
+            block_base_fee_per_gas: 10u64.into(),
+            ..Default::default()
+        };
+        let mut adapter =
+            AxonExecutorApplyAdapter::new(Arc::new(MemoryDB::new(false)), Arc::new(storage), ctx)
+                .unwrap();
+        adapter.save_account(&sender, &Account {
+            nonce: U256::zero(),
+            balance,
+            storage_root: RLP_NULL,
+            code_hash: NIL_DATA,
+        });
+        adapter
+    };
+
+    let config = Config::london();
+    let precompiles = build_precompile_set();
+    let gen_eip1559_tx = |max_fee: u64, priority_fee: u64| {
+        let mut tx = gen_tx(sender, receiver, 0, vec![]);
+        if let UnsignedTransaction::Eip1559(ref mut utx) = tx.transaction.unsigned {
+            utx.gas_price = max_fee.into();
+            utx.max_priority_fee_per_gas = priority_fee.into();
+            utx.gas_limit = 21000u64.into();
+        }
+        tx
+    };
+
+    with_hardforks(100, &[HardforkName::Antlia], || {
+        // Before the hardfork, the gas price is charged as is even if it can not
+        // cover the base fee.
+        let mut adapter = new_adapter(100);
+        let tx = gen_eip1559_tx(5, 3);
+        let r = EvmExecutor::evm_exec(&mut adapter, &config, &precompiles, &tx);
+        assert!(r.exit_reason.is_succeed());
+        assert_eq!(adapter.gas_price(), U256::from(5u64));
+        assert_eq!(r.fee_cost, U256::from(5 * r.gas_used));
+        assert_eq!(
+            adapter.get_account(&sender).balance,
+            balance - U256::from(5 * r.gas_used)
+        );
+
+        // The max fee per gas is less than the base fee.
+        let mut adapter = new_adapter(101);
+        let tx = gen_eip1559_tx(5, 3);
+        let r = EvmExecutor::evm_exec(&mut adapter, &config, &precompiles, &tx);
+        assert!(r.exit_reason.is_error());
+        assert_eq!(adapter.get_account(&sender).balance, balance);
+        assert!(adapter.get_account(&sender).nonce.is_zero());
+
+        // The gas price of a legacy transaction is less than the base fee.
+        let mut tx = gen_tx(sender, receiver, 0, vec![]);
+        tx.transaction.unsigned = UnsignedTransaction::Legacy(LegacyTransaction {
+            nonce:     U256::zero(),
+            gas_price: 5u64.into(),
+            gas_limit: 21000u64.into(),
+            action:    TransactionAction::Call(receiver),
+            value:     U256::zero(),
+            data:      Bytes::new(),
+        });
+        let r = EvmExecutor::evm_exec(&mut adapter, &config, &precompiles, &tx);
+        assert!(r.exit_reason.is_error());
+        assert_eq!(adapter.get_account(&sender).balance, balance);
+        assert!(adapter.get_account(&sender).nonce.is_zero());
+
+        // The effective gas price is base fee + priority fee.
+        let tx = gen_eip1559_tx(100, 3);
+        let r = EvmExecutor::evm_exec(&mut adapter, &config, &precompiles, &tx);
+        assert!(r.exit_reason.is_succeed());
+        assert_eq!(adapter.gas_price(), U256::from(13u64));
+        assert_eq!(r.fee_cost, U256::from(3 * r.gas_used));
+        assert_eq!(
+            adapter.get_account(&sender).balance,
+            balance - U256::from(13 * r.gas_used)
+        );
+
+        // The effective gas price is capped by the max fee per gas.
+        let tx = gen_eip1559_tx(12, 3);
+        let r = EvmExecutor::evm_exec(&mut adapter, &config, &precompiles, &tx);
+        assert!(r.exit_reason.is_succeed());
+        assert_eq!(adapter.gas_price(), U256::from(12u64));
+        assert_eq!(r.fee_cost, U256::from(2 * r.gas_used));
+    });
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fee_allocation_before_antlia() {
+    let (sender, receiver) = (H160::random(), H160::random());
+    let validator = ValidatorExtend {
+        bls_pub_key:    Hex::empty(),
+        pub_key:        Hex::empty(),
+        address:        H160::random(),
+        propose_weight: 1,
+        vote_weight:    1,
+    };
+
+    // Return the gas used and the fee allocated to the validator.
+    let exec = |block_number: u64| {
+        let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()), 20);
+        let ctx = ExecutorContext {
+            block_number: block_number.into(),
+            block_gas_limit: u32::MAX.into(),
+            block_base_fee_per_gas: 10u64.into(),
+            ..Default::default()
+        };
+        let mut adapter =
+            AxonExecutorApplyAdapter::new(Arc::new(MemoryDB::new(false)), Arc::new(storage), ctx)
+                .unwrap();
+        adapter.save_account(&sender, &Account {
+            nonce:        U256::zero(),
+            balance:      u64::MAX.into(),
+            storage_root: RLP_NULL,
+            code_hash:    NIL_DATA,
+        });
+
+        let mut tx = gen_tx(sender, receiver, 0, vec![]);
+        if let UnsignedTransaction::Eip1559(ref mut utx) = tx.transaction.unsigned {
+            utx.gas_price = 100u64.into();
+            utx.max_priority_fee_per_gas = 3u64.into();
+            utx.gas_limit = 21000u64.into();
+        }
+
+        let resp = AxonExecutor.exec_with_hooks(&mut adapter, &[tx], &[validator.clone()], false);
+        assert!(resp.tx_resp[0].exit_reason.is_succeed());
+        (resp.gas_used, adapter.basic(validator.address).balance)
+    };
+
+    let ((gas_before, fee_before), (gas_after, fee_after)) =
+        with_hardforks(100, &[HardforkName::Antlia], || (exec(100), exec(101)));
+
+    // The whole gas price is collected before the hardfork, then the base fee
+    // is burnt and only the priority fee is collected.
+    assert_eq!(fee_before, U256::from(100 * gas_before));
+    assert_eq!(fee_after, U256::from(3 * gas_after));
+}
+
+fn with_access_list(
+    mut tx: SignedTransaction,
+    access_list: Vec<(H160, Vec<H256>)>,
+) -> SignedTransaction {
+    if let UnsignedTransaction::Eip1559(ref mut utx) = tx.transaction.unsigned {
+        utx.access_list = access_list
+            .into_iter()
+            .map(|(address, storage_keys)| AccessListItem {
+                address,
+                storage_keys,
+            })
+            .collect();
+    }
+    tx
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_exec_parallel() {
+    let senders = (0..3).map(|_| H160::random()).collect::<Vec<_>>();
+    let (store, copy, receiver) = (H160::random(), H160::random(), H160::random());
+    let (slot_0, slot_1) = (H256::zero(), H256::from_low_u64_be(1));
+
+    let prepare = || {
+        let mut adapter = exec_adapter();
+        let mut changes = senders
+            .iter()
+            .map(|address| Apply::Modify {
+                address:       *address,
+                basic:         Basic {
+                    balance: 100u64.into(),
+                    nonce:   U256::zero(),
+                },
+                code:          None,
+                storage:       vec![],
+                reset_storage: false,
+            })
+            .collect::<Vec<_>>();
+        changes.extend([
+            // PUSH1 0x2a PUSH1 0x00 SSTORE STOP
+            Apply::Modify {
+                address:       store,
+                basic:         Basic::default(),
+                code:          Some(hex_decode("602a60005500").unwrap()),
+                storage:       vec![],
+                reset_storage: false,
+            },
+            // PUSH1 0x01 SLOAD PUSH1 0x01 ADD PUSH1 0x00 SSTORE STOP
+            Apply::Modify {
+                address:       copy,
+                basic:         Basic::default(),
+                code:          Some(hex_decode("60015460010160005500").unwrap()),
+                storage:       vec![(slot_1, H256::from_low_u64_be(7))],
+                reset_storage: false,
+            },
+        ]);
+        ApplyBackend::apply(&mut adapter, changes, vec![], false);
+        adapter
+    };
+
+    for copy_keys in [vec![slot_0, slot_1], vec![slot_0]] {
+        // The groups are {0, 2}, {1} and {3}. The copy contract reads an
+        // undeclared slot in the second round, which falls back to the
+        // sequential execution.
+        let txs = vec![
+            with_access_list(gen_tx(senders[0], store, 1, vec![]), vec![(store, vec![
+                slot_0,
+            ])]),
+            with_access_list(gen_tx(senders[1], copy, 0, vec![]), vec![(copy, copy_keys)]),
+            with_access_list(gen_tx(senders[0], store, 2, vec![]), vec![(store, vec![
+                slot_0,
+            ])]),
+            with_access_list(gen_tx(senders[2], receiver, 5, vec![]), vec![(
+                receiver,
+                vec![],
+            )]),
+        ];
+
+        let mut adapter = prepare();
+        let expect = AxonExecutor.exec(&mut adapter, &txs, &[]);
+        let mut adapter = prepare();
+        let resp = AxonExecutor.exec_parallel(&mut adapter, &txs, &[]);
+
+        assert_eq!(resp.state_root, expect.state_root);
+        assert_eq!(resp.receipt_root, expect.receipt_root);
+        assert_eq!(resp.gas_used, expect.gas_used);
+        assert_eq!(resp.cumulative_gas_used, expect.cumulative_gas_used);
+        assert!(resp.tx_resp.iter().all(|r| r.exit_reason.is_succeed()));
+        assert_eq!(adapter.storage(copy, slot_0), H256::from_low_u64_be(8));
+        assert_eq!(adapter.get_account(&receiver).balance, 5u64.into());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_storage_refund_cap() {
+    let sender = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let receiver = H160::from_str("0x1000000000000000000000000000000000000000").unwrap();
+    let (balance, gas_limit) = (10_000_000u64, 1_000_000u64);
+
+    // PUSH1 0x00 PUSH1 i SSTORE for i in 0..10, which clears 10 slots.
+    let code = (0..10u8)
+        .flat_map(|i| [0x60, 0x00, 0x60, i, 0x55])
+        .collect::<Vec<_>>();
+    let exec = |block_number| {
+        let mut adapter = exec_adapter_at(block_number);
+        ApplyBackend::apply(
+            &mut adapter,
+            vec![
+                Apply::Modify {
+                    address:       sender,
+                    basic:         Basic {
+                        balance: balance.into(),
+                        nonce:   U256::zero(),
+                    },
+                    code:          None,
+                    storage:       vec![],
+                    reset_storage: false,
+                },
+                Apply::Modify {
+                    address:       receiver,
+                    basic:         Basic::default(),
+                    code:          Some(code),
+                    storage:       (0..10u64)
+                        .map(|i| (H256::from_low_u64_be(i), H256::from_low_u64_be(1)))
+                        .collect(),
+                    reset_storage: false,
+                },
+            ],
+            vec![],
+            false,
+        );
+
+        let mut tx = gen_tx(sender, receiver, 0, vec![]);
+        if let UnsignedTransaction::Eip1559(ref mut utx) = tx.transaction.unsigned {
+            utx.gas_price = U256::one();
+            utx.max_priority_fee_per_gas = U256::one();
+            utx.gas_limit = gas_limit.into();
+        }
+
+        let r = EvmExecutor::evm_exec(
+            &mut adapter,
+            &Config::london(),
+            &build_precompile_set(),
+            &tx,
+        );
+        assert!(r.exit_reason.is_succeed());
+        assert!((0..10u64).all(|i| adapter
+            .storage(receiver, H256::from_low_u64_be(i))
+            .is_zero()));
+        (r, adapter.get_account(&sender).balance)
+    };
+    let ((before, before_balance), (after, after_balance)) =
+        with_hardforks(100, &[HardforkName::Antlia], || (exec(100), exec(101)));
+
+    // Each clear costs 2100 for the cold slot plus 2900 and 6 for the pushes,
+    // and is refunded 4800. The refund 48000 is capped at one fifth.
+    let consumed = 21000 + 10 * (2100 + 2900 + 6);
+    assert_eq!(before.gas_used, consumed - consumed / 5);
+    assert_eq!(after.gas_used, consumed - consumed / 5);
+
+    // The refund is only returned to the sender since the Antlia hardfork.
+    assert_eq!(before.remain_gas, gas_limit - consumed);
+    assert_eq!(before_balance, U256::from(balance - consumed));
+    assert_eq!(after.remain_gas, gas_limit - after.gas_used);
+    assert_eq!(after_balance, U256::from(balance - after.gas_used));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_code_size() {
+    let (contract, eoa, absent) = (H160::random(), H160::random(), H160::random());
+    let mut adapter = exec_adapter();
+    ApplyBackend::apply(
+        &mut adapter,
+        vec![
+            Apply::Modify {
+                address:       contract,
+                basic:         Basic::default(),
+                code:          Some(hex_decode("602a60005500").unwrap()),
+                storage:       vec![],
+                reset_storage: false,
+            },
+            Apply::Modify {
+                address:       eoa,
+                basic:         Basic {
+                    balance: U256::one(),
+                    nonce:   U256::zero(),
+                },
+                code:          None,
+                storage:       vec![],
+                reset_storage: false,
+            },
+        ],
+        vec![],
+        false,
+    );
+
+    for addr in [contract, eoa, absent] {
+        assert_eq!(adapter.code_size(&addr), adapter.code(addr).len());
+    }
+    assert_eq!(adapter.code_size(&contract), 6);
+
+    // The size recorded when the code is saved is answered without loading the
+    // code, which is absent in the storage of another adapter.
+    let code_hash = adapter.get_account(&contract).code_hash;
+    let mut other = exec_adapter();
+    other.save_account(&contract, &Account {
+        nonce: U256::zero(),
+        balance: U256::zero(),
+        storage_root: RLP_NULL,
+        code_hash,
+    });
+    assert!(other.code(contract).is_empty());
+    assert_eq!(other.code_size(&contract), 6);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_create_collision() {
+    let sender = H160::random();
+    let target = H160::from(code_address(&sender, &U256::zero()));
+    let seeded_code = hex_decode("602a60005500").unwrap();
+
+    // The target is pre-seeded with code, or with a nonzero nonce (EIP-684).
+    for (code, nonce) in [
+        (Some(seeded_code.clone()), U256::zero()),
+        (None, U256::one()),
+    ] {
+        let mut adapter = exec_adapter();
+        ApplyBackend::apply(
+            &mut adapter,
+            vec![Apply::Modify {
+                address:       target,
+                basic:         Basic {
+                    balance: U256::zero(),
+                    nonce,
+                },
+                code:          code.clone(),
+                storage:       vec![],
+                reset_storage: false,
+            }],
+            vec![],
+            false,
+        );
+
+        let mut tx = gen_tx(sender, H160::default(), 0, vec![]);
+        tx.transaction
+            .unsigned
+            .set_action(TransactionAction::Create);
+        tx.transaction
+            .unsigned
+            .set_data(hex_decode("602a60005260206000f3").unwrap().into());
+
+        let config = Config::london();
+        let precompiles = build_precompile_set();
+        let r = EvmExecutor::evm_exec(&mut adapter, &config, &precompiles, &tx);
+
+        assert_eq!(r.exit_reason, ExitReason::Error(ExitError::CreateCollision));
+        assert!(r.code_address.is_none());
+        assert_eq!(adapter.code(target), code.unwrap_or_default());
+        assert_eq!(adapter.basic(target).nonce, nonce);
+        assert_eq!(adapter.basic(sender).nonce, U256::one());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_block_hash() {
+    // The after block hook updates the hardfork registry.
+    let _lock = hardfork_lock();
+    let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()), 20);
+    let parent_hash = H256::random();
+    let ctx = ExecutorContext {
+        block_number: 300u64.into(),
+        block_gas_limit: u32::MAX.into(),
+        block_base_fee_per_gas: U256::zero(),
+        parent_hash,
+        ..Default::default()
+    };
+    let mut adapter =
+        AxonExecutorApplyAdapter::new(Arc::new(MemoryDB::new(false)), Arc::new(storage), ctx)
+            .unwrap();
+
+    // Store `BLOCKHASH(299)` to slot 0 and `BLOCKHASH(0)` to slot 1, which is
+    // pre-seeded with a nonzero value.
+    let contract = H160::random();
+    let code = hex_decode("61012b4060005560004060015500").unwrap();
+    ApplyBackend::apply(
+        &mut adapter,
+        vec![Apply::Modify {
+            address:       contract,
+            basic:         Basic {
+                balance: U256::zero(),
+                nonce:   U256::one(),
+            },
+            code:          Some(code),
+            storage:       vec![(H256::from_low_u64_be(1), H256::from_low_u64_be(1))],
+            reset_storage: false,
+        }],
+        vec![],
+        false,
+    );
+
+    let tx = gen_tx(H160::random(), contract, 0, vec![]);
+    let resp = AxonExecutor.exec(&mut adapter, &[tx], &[]);
+    assert!(resp.tx_resp[0].exit_reason.is_succeed());
+
+    assert_eq!(adapter.block_hash(299u64.into()), parent_hash);
+    assert_eq!(adapter.storage(contract, H256::zero()), parent_hash);
+    assert_eq!(adapter.block_hash(U256::zero()), H256::zero());
+    assert_eq!(
+        adapter.storage(contract, H256::from_low_u64_be(1)),
+        H256::zero()
+    );
+    assert_eq!(adapter.block_hash(300u64.into()), H256::zero());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_create_contract_limit() {
+    let mut config = Config::london();
+    config.create_contract_limit = Some(0x100);
+    let precompiles = build_precompile_set();
+    let sender = H160::random();
+
+    // The init code returns a runtime code of 0x100 and 0x101 bytes.
+    for (init_code, exceeded) in [("6101006000f3", false), ("6101016000f3", true)] {
+        let mut adapter = exec_adapter();
+        let mut tx = gen_tx(sender, H160::default(), 0, vec![]);
+        tx.transaction
+            .unsigned
+            .set_action(TransactionAction::Create);
+        tx.transaction
+            .unsigned
+            .set_data(hex_decode(init_code).unwrap().into());
+
+        let r = EvmExecutor::evm_exec(&mut adapter, &config, &precompiles, &tx);
+        let target = H160::from(code_address(&sender, &U256::zero()));
+
+        if exceeded {
+            assert_eq!(
+                r.exit_reason,
+                ExitReason::Error(ExitError::Other(
+                    "contract size exceeds the limit of 256 bytes".into()
+                ))
+            );
+            assert!(r.code_address.is_none());
+            assert!(adapter.code(target).is_empty());
+        } else {
+            assert!(r.exit_reason.is_succeed());
+            assert_eq!(adapter.code(target).len(), 0x100);
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_exec_with_hooks() {
+    // The after block hook updates the hardfork registry.
+    let _lock = hardfork_lock();
+    let parent_hash = H256::random();
+    let contract = H160::random();
+    let sender = H160::random();
+
+    let exec = |hooks: bool| {
+        let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()), 20);
+        let ctx = ExecutorContext {
+            block_number: 1u64.into(),
+            block_gas_limit: u32::MAX.into(),
+            block_base_fee_per_gas: U256::zero(),
+            parent_hash,
+            ..Default::default()
+        };
+        let mut adapter =
+            AxonExecutorApplyAdapter::new(Arc::new(MemoryDB::new(false)), Arc::new(storage), ctx)
+                .unwrap();
+
+        // Store `BLOCKHASH(0)` to slot 0, the parent hash is recorded by the
+        // before block hook.
+        ApplyBackend::apply(
+            &mut adapter,
+            vec![Apply::Modify {
+                address:       contract,
+                basic:         Basic {
+                    balance: U256::zero(),
+                    nonce:   U256::one(),
+                },
+                code:          Some(hex_decode("60004060005500").unwrap()),
+                storage:       vec![],
+                reset_storage: false,
+            }],
+            vec![],
+            false,
+        );
+
+        let tx = gen_tx(sender, contract, 0, vec![]);
+        let resp = AxonExecutor.exec_with_hooks(&mut adapter, &[tx], &[], hooks);
+        assert!(resp.tx_resp[0].exit_reason.is_succeed());
+        (resp.state_root, adapter.storage(contract, H256::zero()))
+    };
+
+    let (root_with_hooks, slot_with_hooks) = exec(true);
+    let (root_without_hooks, slot_without_hooks) = exec(false);
+    assert_eq!(slot_with_hooks, parent_hash);
+    assert_eq!(slot_without_hooks, H256::zero());
+    assert_ne!(root_with_hooks, root_without_hooks);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_genesis_builder() {
+    let db = Arc::new(MemoryDB::new(false));
+    let storage = Arc::new(ImplStorage::new(Arc::new(MemoryAdapter::new()), 20));
+    let mut adapter =
+        AxonExecutorApplyAdapter::new(Arc::clone(&db), Arc::clone(&storage), Default::default())
+            .unwrap();
+
+    let (eoa, contract) = (H160::random(), H160::random());
+    let code = hex_decode("602a60005500").unwrap();
+    let slot = H256::random();
+    let root = adapter
+        .genesis_builder()
+        .set_balance(eoa, 1000u64.into())
+        .set_nonce(eoa, 3u64.into())
+        .set_code(contract, code.clone())
+        .set_nonce(contract, U256::one())
+        .set_storage(contract, slot, H256::from_low_u64_be(42))
+        .commit();
+
+    let reader =
+        AxonExecutorReadOnlyAdapter::at_root(Arc::clone(&db), Arc::clone(&storage), root).unwrap();
+    let account = reader.get_account(&eoa);
+    assert_eq!(account.balance, 1000u64.into());
+    assert_eq!(account.nonce, 3u64.into());
+    assert_eq!(account.code_hash, NIL_DATA);
+
+    let account = reader.get_account(&contract);
+    assert_eq!(account.balance, U256::zero());
+    assert_eq!(account.nonce, U256::one());
+    assert_ne!(account.storage_root, RLP_NULL);
+    assert_eq!(reader.code(contract), code);
+    assert_eq!(reader.storage(contract, slot), H256::from_low_u64_be(42));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_replay_tx() {
+    let db = Arc::new(MemoryDB::new(false));
+    let storage = Arc::new(ImplStorage::new(Arc::new(MemoryAdapter::new()), 20));
+    let ctx = ExecutorContext {
+        block_gas_limit: u32::MAX.into(),
+        block_base_fee_per_gas: U256::zero(),
+        ..Default::default()
+    };
+    let mut adapter =
+        AxonExecutorApplyAdapter::new(Arc::clone(&db), Arc::clone(&storage), ctx.clone()).unwrap();
+
+    // The first contract emits a log and the second one writes a slot.
+    let (logger, writer) = (H160::random(), H160::random());
+    let pre_state_root = adapter
+        .genesis_builder()
+        .set_code(logger, hex_decode("60006000a000").unwrap())
+        .set_code(writer, hex_decode("602a60005500").unwrap())
+        .commit();
+
+    let sender = H160::random();
+    let txs = vec![
+        gen_tx(sender, logger, 0, vec![]),
+        gen_tx(sender, writer, 0, vec![]),
+    ];
+    let resp = AxonExecutor.exec(&mut adapter, &txs, &[]);
+
+    let mut adapter =
+        AxonExecutorApplyAdapter::from_root(pre_state_root, db, storage, ctx).unwrap();
+    for (tx, expect) in txs.iter().zip(resp.tx_resp.iter()) {
+        let r = AxonExecutor.replay_tx(&mut adapter, tx);
+        assert!(r.exit_reason.is_succeed());
+        assert_eq!(r.gas_used, expect.gas_used);
+        assert_eq!(r.logs, expect.logs);
+    }
+    assert_eq!(resp.tx_resp[0].logs.len(), 1);
+    assert_eq!(
+        adapter.storage(writer, H256::zero()),
+        H256::from_low_u64_be(0x2a)
+    );
+}
+
+#[test]
+fn test_system_contract_roots_on_fresh_thread() {
+    let roots = SystemContractRoots {
+        header_cell_root: H256::random(),
+        metadata_root:    H256::random(),
+    };
+    roots.install();
+    assert_eq!(SystemContractRoots::current(), roots);
+
+    std::thread::spawn(move || {
+        // The roots of a fresh thread are the default ones until installed.
+        assert_eq!(
+            SystemContractRoots::current(),
+            SystemContractRoots::default()
+        );
+        roots.install();
+        assert_eq!(SystemContractRoots::current(), roots);
+    })
+    .join()
+    .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_state_diff() {
+    let db = Arc::new(MemoryDB::new(false));
+    let storage = Arc::new(ImplStorage::new(Arc::new(MemoryAdapter::new()), 20));
+    let ctx = ExecutorContext {
+        block_gas_limit: u32::MAX.into(),
+        block_base_fee_per_gas: U256::zero(),
+        ..Default::default()
+    };
+    let mut adapter = AxonExecutorApplyAdapter::new(Arc::clone(&db), storage, ctx).unwrap();
+
+    // The untouched accounts are expected to be skipped.
+    let (sender, recipient) = (H160::random(), H160::random());
+    let mut builder = adapter
+        .genesis_builder()
+        .set_balance(sender, 1000u64.into());
+    for _ in 0..20 {
+        builder = builder.set_balance(H160::random(), 1u64.into());
+    }
+    let root_a = builder.commit();
+
+    let tx = gen_tx(sender, recipient, 100, vec![]);
+    let r = EvmExecutor::evm_exec(
+        &mut adapter,
+        &Config::london(),
+        &build_precompile_set(),
+        &tx,
+    );
+    assert!(r.exit_reason.is_succeed());
+    let root_b = adapter.commit();
+
+    let diff = state_diff(db.as_ref(), root_a, root_b).unwrap();
+    assert_eq!(diff.len(), 2);
+    let find = |address: H160| {
+        let key = Hasher::digest(address.as_bytes());
+        diff.iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, d)| d)
+            .unwrap()
+    };
+
+    let sender_diff = find(sender);
+    assert_eq!(sender_diff.kind, DiffKind::Changed);
+    assert_eq!(sender_diff.balance, Some((1000u64.into(), 900u64.into())));
+    assert_eq!(sender_diff.nonce, Some((U256::zero(), U256::one())));
+    assert_eq!(sender_diff.code_hash, None);
+
+    let recipient_diff = find(recipient);
+    assert_eq!(recipient_diff.kind, DiffKind::Added);
+    assert_eq!(recipient_diff.balance, Some((U256::zero(), 100u64.into())));
+    assert_eq!(recipient_diff.nonce, None);
+
+    assert!(state_diff(db.as_ref(), root_b, root_b).unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_code_by_hash() {
+    let db = Arc::new(MemoryDB::new(false));
+    let storage = Arc::new(ImplStorage::new(Arc::new(MemoryAdapter::new()), 20));
+    let mut adapter =
+        AxonExecutorApplyAdapter::new(Arc::clone(&db), Arc::clone(&storage), Default::default())
+            .unwrap();
+
+    // Deploy a contract whose runtime code is `PUSH1 0x2a PUSH1 0x00 SSTORE STOP`.
+    let sender = H160::random();
+    let init_code = hex_decode("6006600c60003960066000f3602a60005500").unwrap();
+    let mut tx = gen_tx(sender, H160::default(), 0, init_code);
+    tx.transaction
+        .unsigned
+        .set_action(TransactionAction::Create);
+    let r = EvmExecutor::evm_exec(
+        &mut adapter,
+        &Config::london(),
+        &build_precompile_set(),
+        &tx,
+    );
+    assert!(r.exit_reason.is_succeed());
+    let contract = H160::from(r.code_address.unwrap());
+    let root = adapter.commit();
+
+    let reader =
+        AxonExecutorReadOnlyAdapter::at_root(Arc::clone(&db), Arc::clone(&storage), root).unwrap();
+    let code_hash = reader.get_account(&contract).code_hash;
+    let code = reader.code_by_hash(&code_hash).unwrap();
+    assert_eq!(code.to_vec(), hex_decode("602a60005500").unwrap());
+    assert_eq!(code.to_vec(), reader.code(contract));
+
+    assert!(reader.code_by_hash(&NIL_DATA).is_none());
+    assert!(reader.code_by_hash(&H256::random()).is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_account_proof() {
+    let db = Arc::new(MemoryDB::new(false));
+    let storage = Arc::new(ImplStorage::new(Arc::new(MemoryAdapter::new()), 20));
+    let mut adapter =
+        AxonExecutorApplyAdapter::new(Arc::clone(&db), Arc::clone(&storage), Default::default())
+            .unwrap();
+
+    let (contract, eoa, absent) = (H160::random(), H160::random(), H160::random());
+    let (slot, empty_slot) = (H256::random(), H256::random());
+    ApplyBackend::apply(
+        &mut adapter,
+        vec![
+            Apply::Modify {
+                address:       contract,
+                basic:         Basic::default(),
+                code:          Some(hex_decode("602a60005500").unwrap()),
+                storage:       vec![(slot, H256::from_low_u64_be(42))],
+                reset_storage: false,
+            },
+            Apply::Modify {
+                address:       eoa,
+                basic:         Basic {
+                    balance: U256::one(),
+                    nonce:   U256::zero(),
+                },
+                code:          None,
+                storage:       vec![],
+                reset_storage: false,
+            },
+        ],
+        vec![],
+        false,
+    );
+    let state_root = adapter.commit();
+
+    let reader = AxonExecutorReadOnlyAdapter::from_root(
+        state_root,
+        Arc::clone(&db),
+        storage,
+        Default::default(),
+    )
+    .unwrap();
+    let state_trie = MPTTrie::from_root(state_root, Arc::clone(&db)).unwrap();
+    let verify = |trie: &MPTTrie<MemoryDB>, root: H256, key: &[u8], proof: Vec<Bytes>| {
+        let proof = proof.into_iter().map(|node| node.to_vec()).collect();
+        trie.verify_proof(root.as_bytes(), key, proof).unwrap()
+    };
+
+    let (account_proof, storage_proofs) = reader
+        .account_proof(&contract, &[slot, empty_slot])
+        .unwrap();
+    let raw = verify(&state_trie, state_root, contract.as_bytes(), account_proof).unwrap();
+    let account = Account::decode(raw).unwrap();
+    assert_eq!(account, reader.get_account(&contract));
+
+    let storage_trie = MPTTrie::from_root(account.storage_root, Arc::clone(&db)).unwrap();
+    assert_eq!(storage_proofs.len(), 2);
+    let (key, proof) = storage_proofs[0].clone();
+    assert_eq!(key, slot);
+    let raw = verify(&storage_trie, account.storage_root, slot.as_bytes(), proof).unwrap();
+    assert_eq!(U256::decode(raw).unwrap(), 42u64.into());
+    let (key, proof) = storage_proofs[1].clone();
+    assert_eq!(key, empty_slot);
+    assert!(verify(
+        &storage_trie,
+        account.storage_root,
+        empty_slot.as_bytes(),
+        proof
+    )
+    .is_none());
+
+    // The account without storage has empty storage proofs.
+    let (account_proof, storage_proofs) = reader.account_proof(&eoa, &[slot]).unwrap();
+    assert!(verify(&state_trie, state_root, eoa.as_bytes(), account_proof).is_some());
+    assert_eq!(storage_proofs, vec![(slot, vec![])]);
+
+    // The proof of an absent account proves its absence.
+    let (account_proof, _) = reader.account_proof(&absent, &[]).unwrap();
+    assert!(verify(&state_trie, state_root, absent.as_bytes(), account_proof).is_none());
+}
+
+#[derive(Default)]
+struct RecordObserver {
+    before: std::sync::Mutex<Vec<(U256, H256, H256)>>,
+    after:  std::sync::Mutex<Vec<(U256, H256)>>,
+}
+
+impl BlockHookObserver for Arc<RecordObserver> {
+    fn on_before(&self, block_number: U256, metadata_root: H256, header_cell_root: H256) {
+        self.before
+            .lock()
+            .unwrap()
+            .push((block_number, metadata_root, header_cell_root));
+    }
+
+    fn on_after(&self, block_number: U256, new_root: H256) {
+        self.after.lock().unwrap().push((block_number, new_root));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_block_hook_observer() {
+    // The other tests may execute blocks at the same time, so the roots are
+    // marked by a random header cell root.
+    let header_cell_root = H256::random();
+    let mut adapter = exec_adapter();
+    ApplyBackend::apply(
+        &mut adapter,
+        vec![Apply::Modify {
+            address:       CKB_LIGHT_CLIENT_CONTRACT_ADDRESS,
+            basic:         Basic::default(),
+            code:          None,
+            storage:       vec![(*HEADER_CELL_ROOT_KEY, header_cell_root)],
+            reset_storage: false,
+        }],
+        vec![],
+        false,
+    );
+
+    let observer = Arc::new(RecordObserver::default());
+    BLOCK_HOOK_OBSERVER.store(Some(Arc::new(Box::new(Arc::clone(&observer)))));
+    let resp = AxonExecutor.exec(&mut adapter, &[], &[]);
+    BLOCK_HOOK_OBSERVER.store(None);
+
+    assert!(observer.before.lock().unwrap().contains(&(
+        U256::zero(),
+        H256::zero(),
+        header_cell_root
+    )));
+    assert!(observer
+        .after
+        .lock()
+        .unwrap()
+        .contains(&(U256::zero(), resp.state_root)));
+}
+
+#[test]
+fn test_hardfork_of_block() {
+    with_hardforks(100, &[HardforkName::Andromeda], || {
+        // The block is executed with the hardforks enabled at its parent block.
+        assert_eq!(hardfork_of_block(100), HardforkName::None);
+        assert_eq!(hardfork_of_block(101), HardforkName::Andromeda);
+        assert!(!is_enabled_in_block(HardforkName::Andromeda, 100));
+        assert!(is_enabled_in_block(HardforkName::Andromeda, 101));
+    });
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reject_contract_sender_since_andromeda() {
+    let (sender, receiver) = (H160::random(), H160::random());
+    let exec = |block_number: u64| {
+        let storage = ImplStorage::new(Arc::new(MemoryAdapter::new()), 20);
+        let ctx = ExecutorContext {
+            block_number: block_number.into(),
+            block_gas_limit: u32::MAX.into(),
+            block_base_fee_per_gas: U256::zero(),
+            ..Default::default()
+        };
+        let mut adapter =
+            AxonExecutorApplyAdapter::new(Arc::new(MemoryDB::new(false)), Arc::new(storage), ctx)
+                .unwrap();
+        ApplyBackend::apply(
+            &mut adapter,
+            vec![Apply::Modify {
+                address:       sender,
+                basic:         Basic {
+                    balance: 100u64.into(),
+                    nonce:   U256::zero(),
+                },
+                code:          Some(hex_decode("00").unwrap()),
+                storage:       vec![],
+                reset_storage: false,
+            }],
+            vec![],
+            false,
+        );
+
+        let tx = gen_tx(sender, receiver, 10, vec![]);
+        EvmExecutor::evm_exec(
+            &mut adapter,
+            &Config::london(),
+            &build_precompile_set(),
+            &tx,
+        )
+    };
+
+    let (before, after) =
+        with_hardforks(100, &[HardforkName::Andromeda], || (exec(100), exec(101)));
+    assert!(before.exit_reason.is_succeed());
+    assert_eq!(
+        after.exit_reason,
+        ExitReason::Error(ExitError::Other("sender not an eoa".into()))
+    );
+}
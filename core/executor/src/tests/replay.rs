@@ -0,0 +1,31 @@
+use protocol::types::{ExitReason, ExitSucceed, H256};
+
+use crate::replay::{replay_fixture, ReplayFixture};
+
+const TRANSFER_FIXTURE: &str = "./res/replay_transfer.json";
+
+#[test]
+fn test_replay_fixture() {
+    let resp = replay_fixture(TRANSFER_FIXTURE).unwrap();
+    assert_eq!(resp.tx_resp.len(), 1);
+    assert_eq!(
+        resp.tx_resp[0].exit_reason,
+        ExitReason::Succeed(ExitSucceed::Stopped)
+    );
+    assert_eq!(resp.gas_used, 21000);
+
+    // Record the response, then the replay of the recorded fixture must reach
+    // the same state root.
+    let mut fixture = ReplayFixture::load(TRANSFER_FIXTURE).unwrap();
+    assert_eq!(fixture.record().unwrap(), &resp);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("replay.json");
+    fixture.save(&path).unwrap();
+    assert_eq!(ReplayFixture::load(&path).unwrap(), fixture);
+    assert_eq!(replay_fixture(&path).unwrap(), resp);
+
+    fixture.resp.as_mut().unwrap().state_root = H256::random();
+    fixture.save(&path).unwrap();
+    assert!(replay_fixture(&path).is_err());
+}
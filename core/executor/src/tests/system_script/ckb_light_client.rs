@@ -3,9 +3,13 @@ use std::str::FromStr;
 
 use ethers::abi::AbiEncode;
 
+use evm::Context;
+
 use core_db::RocksAdapter;
 use protocol::types::{Backend, MemoryBackend, TxResp, H160, H256, U256};
 
+use crate::precompiles::{PrecompileContract, VerifyCkbHeader};
+
 use crate::system_contract::ckb_light_client::{
     ckb_light_client_abi, CkbHeaderReader, CkbLightClientContract,
 };
@@ -14,6 +18,7 @@ use crate::system_contract::{
     HEADER_CELL_ROOT_KEY, IMAGE_CELL_CONTRACT_ADDRESS,
 };
 use crate::tests::{gen_tx, gen_vicinity};
+use crate::CURRENT_HEADER_CELL_ROOT;
 
 static ROCKSDB_PATH: &str = "./free-space/system-contract/ckb-light-client";
 
@@ -30,6 +35,7 @@ pub fn test_write_functions() {
     // need to refactor to be OO
     test_update_first(&mut backend, &executor);
     test_update_second(&mut backend, &executor);
+    test_verify_header(&backend);
 
     test_roll_back_first(&mut backend, &executor);
     test_roll_back_second(&mut backend, &executor);
@@ -105,6 +111,34 @@ fn test_update_second<'a>(
     assert_eq!(queried_header, header);
 }
 
+fn test_verify_header(backend: &MemoryBackend<'_>) {
+    let root = backend.storage(CKB_LIGHT_CLIENT_CONTRACT_ADDRESS, *HEADER_CELL_ROOT_KEY);
+    CURRENT_HEADER_CELL_ROOT.with(|r| *r.borrow_mut() = root);
+
+    let context = Context {
+        address:        Default::default(),
+        caller:         Default::default(),
+        apparent_value: Default::default(),
+    };
+    let verify = |hash: H256| {
+        VerifyCkbHeader::exec_fn(hash.as_bytes(), Some(u64::MAX), &context, false)
+            .unwrap()
+            .0
+            .output
+    };
+
+    for (hash, found) in [
+        (H256::default(), true),
+        (H256::from_slice(&prepare_header_2().block_hash), true),
+        (H256::random(), false),
+    ] {
+        assert_eq!(verify(hash), H256::from_low_u64_be(found as u64).0.to_vec());
+    }
+
+    assert!(VerifyCkbHeader::exec_fn(&[0u8; 31], None, &context, false).is_err());
+    assert!(VerifyCkbHeader::exec_fn(&[0u8; 32], Some(21000), &context, false).is_err());
+}
+
 fn test_roll_back_first<'a>(
     backend: &mut MemoryBackend<'a>,
     executor: &CkbLightClientContract<MemoryBackend<'a>>,
@@ -3,10 +3,12 @@ use std::str::FromStr;
 
 use ckb_types::{bytes::Bytes, packed, prelude::*};
 use ethers::abi::AbiEncode;
+use evm::{executor::stack::PrecompileFailure, Context, ExitError};
 
 use core_db::RocksAdapter;
-use protocol::types::{Backend, MemoryBackend, TxResp, H160, U256};
+use protocol::types::{Backend, MemoryBackend, TxResp, H160, H256, U256};
 
+use crate::precompiles::{Cell, GetCell, PrecompileContract, PrecompileError};
 use crate::system_contract::image_cell::{
     image_cell_abi, CellInfo, CellKey, ImageCellContract, ImageCellReader,
 };
@@ -62,6 +64,59 @@ fn test_update_first<'a>(
     let cell_key = CellKey::new([7u8; 32], 0x0);
     let get_cell = ImageCellReader.get_cell(root, &cell_key).unwrap().unwrap();
     check_cell(&get_cell, 0x1, None);
+
+    test_get_cell_precompile(root);
+}
+
+fn test_get_cell_precompile(root: H256) {
+    CURRENT_HEADER_CELL_ROOT.with(|r| *r.borrow_mut() = root);
+
+    let cell = &prepare_outputs()[0];
+    let input = cell.out_point.clone().encode();
+    let context = Context {
+        address:        Default::default(),
+        caller:         Default::default(),
+        apparent_value: Default::default(),
+    };
+    let (output, gas) = GetCell::exec_fn(&input, None, &context, false).unwrap();
+
+    let expect = Cell {
+        cell_output:     cell.output.clone(),
+        cell_data:       cell.data.clone(),
+        is_consumed:     false,
+        created_number:  0x1,
+        consumed_number: 0,
+    }
+    .encode();
+    assert_eq!(output.output, expect);
+    assert_eq!(
+        gas,
+        GetCell::gas_cost(&input) + ((expect.len() + 31) / 32) as u64 * 3
+    );
+
+    // The gas limit can not cover the cost of the returned cell.
+    assert_eq!(
+        GetCell::exec_fn(&input, Some(GetCell::MIN_GAS), &context, false).unwrap_err(),
+        PrecompileFailure::from(PrecompileError::OutOfGas {
+            cost:  gas,
+            limit: GetCell::MIN_GAS,
+        })
+    );
+
+    // The cell is not found.
+    let input = image_cell_abi::OutPoint {
+        tx_hash: [1u8; 32],
+        index:   0x0,
+    }
+    .encode();
+    assert_eq!(
+        GetCell::exec_fn(&input, None, &context, false).unwrap_err(),
+        PrecompileFailure::Error {
+            exit_status: ExitError::Other(
+                format!("cell {:#x}:0 not found", H256([1u8; 32])).into()
+            ),
+        }
+    );
 }
 
 fn test_update_second<'a>(
@@ -1,25 +1,40 @@
 use std::{collections::BTreeMap, str::FromStr, sync::Arc};
 
+use common_config_parser::types::spec::HardforkName;
 use ethers::abi::AbiEncode;
+use evm::backend::{Apply, Basic, MemoryAccount};
+use evm::executor::stack::{MemoryStackState, StackExecutor, StackSubstateMetadata};
+use evm::Config;
 
 use core_db::RocksAdapter;
-use protocol::types::{CkbRelatedInfo, MemoryBackend, SignedTransaction, H160, H256, U256};
+use protocol::codec::hex_decode;
+use protocol::traits::{ApplyBackend, Executor, ExecutorAdapter, ExecutorReadOnlyAdapter};
+use protocol::types::{
+    Account, CkbRelatedInfo, ExitReason, MemoryBackend, SignedTransaction, UnsignedTransaction,
+    H160, H256, NIL_DATA, RLP_NULL, U256,
+};
 
 use crate::{
+    precompiles::{EpochValidators, GetMetadata, PrecompileContract, Validator},
     system_contract::{
         init_system_contract_db,
         metadata::{
-            metadata_abi::{self, ConsensusConfig, Metadata, MetadataVersion, ValidatorExtend},
-            MetadataContract, MetadataStore,
+            metadata_abi::{
+                self, ConsensusConfig, ConsensusConfigV1, Metadata, MetadataVersion,
+                ValidatorExtend,
+            },
+            MetadataContract, MetadataStore, HARDFORK_INFO,
         },
-        SystemContract, METADATA_CONTRACT_ADDRESS, METADATA_DB,
+        system_contract_dispatch, SystemContract, METADATA_CONTRACT_ADDRESS, METADATA_DB,
     },
-    tests::{gen_tx, gen_vicinity},
-    RocksTrieDB, CURRENT_METADATA_ROOT,
+    tests::{exec_adapter, exec_adapter_at, gen_tx, gen_vicinity, with_hardforks},
+    AxonExecutor, RocksTrieDB, CURRENT_BLOCK_NUMBER, CURRENT_METADATA_ROOT,
 };
 
 static ROCKSDB_PATH: &str = "./free-space/system-contract/metadata";
 static CKB_INFO_ROCKSDB_PATH: &str = "./free-space/system-contract/ckb_info";
+static DISPATCH_ROCKSDB_PATH: &str = "./free-space/system-contract/metadata-dispatch";
+static PRECOMPILE_ROCKSDB_PATH: &str = "./free-space/system-contract/metadata-precompile";
 
 #[test]
 fn test_write_functions() {
@@ -91,6 +106,134 @@ fn test_validator<'a>(
     assert!(r.exit_reason.is_revert());
 }
 
+#[test]
+fn test_get_metadata_precompile() {
+    let vicinity = gen_vicinity();
+    let mut backend = MemoryBackend::new(&vicinity, BTreeMap::new());
+    let inner_db = RocksAdapter::new(PRECOMPILE_ROCKSDB_PATH, Default::default())
+        .unwrap()
+        .inner_db();
+    init_system_contract_db(inner_db, &mut backend);
+
+    // Seed the metadata of epoch 0, which covers the blocks from 1 to 100.
+    MetadataStore::new(H256::default())
+        .unwrap()
+        .append_metadata(&prepare_metadata().into())
+        .unwrap();
+    CURRENT_BLOCK_NUMBER.with(|n| *n.borrow_mut() = 1);
+
+    let context = evm::Context {
+        address:        Default::default(),
+        caller:         Default::default(),
+        apparent_value: Default::default(),
+    };
+    let (output, gas) = GetMetadata::exec_fn(&[], None, &context, false).unwrap();
+
+    let validator = prepare_validator();
+    let expect = EpochValidators {
+        epoch:      0,
+        validators: vec![Validator {
+            address:        validator.address,
+            propose_weight: validator.propose_weight,
+            vote_weight:    validator.vote_weight,
+        }],
+    };
+    assert_eq!(output.output, expect.encode());
+    assert_eq!(gas, GetMetadata::MIN_GAS + 2100);
+
+    // The gas limit can not cover the cost of the validators.
+    assert!(GetMetadata::exec_fn(&[], Some(GetMetadata::MIN_GAS), &context, false).is_err());
+}
+
+#[test]
+fn test_dispatch_gas() {
+    let inner_db = RocksAdapter::new(DISPATCH_ROCKSDB_PATH, Default::default())
+        .unwrap()
+        .inner_db();
+    let addr = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let balance = U256::from(u64::MAX);
+
+    let mut tx = prepare_tx_1(&addr);
+    if let UnsignedTransaction::Eip1559(ref mut utx) = tx.transaction.unsigned {
+        utx.gas_price = 2u64.into();
+    }
+    let gas_limit = tx.transaction.unsigned.gas_limit().as_u64();
+
+    let dispatch = |block_number: u64| {
+        let mut adapter = exec_adapter_at(block_number);
+        let (m_root, _) = init_system_contract_db(Arc::clone(&inner_db), &mut adapter);
+        CURRENT_METADATA_ROOT.with(|r| *r.borrow_mut() = m_root);
+        adapter.save_account(&addr, &Account {
+            nonce: U256::zero(),
+            balance,
+            storage_root: RLP_NULL,
+            code_hash: NIL_DATA,
+        });
+
+        let r = system_contract_dispatch(&mut adapter, &tx).unwrap();
+        (r, adapter.get_account(&addr))
+    };
+
+    let ((r_0, account_0), (r_100, account_100), (r_101, account_101)) =
+        with_hardforks(100, &[HardforkName::Antlia], || {
+            (dispatch(0), dispatch(100), dispatch(101))
+        });
+
+    // The metadata call at the genesis uses no gas, so nothing is charged.
+    assert!(r_0.exit_reason.is_succeed());
+    assert_eq!(r_0.remain_gas, gas_limit);
+    assert!(r_0.fee_cost.is_zero());
+    assert_eq!(account_0.balance, balance);
+    assert_eq!(account_0.nonce, U256::one());
+
+    // The sender is not a validator, so the call is reverted and uses all the
+    // gas but one. It is free before the hardfork.
+    assert!(r_100.exit_reason.is_revert());
+    assert_eq!(account_100.balance, balance);
+
+    // Since the hardfork the sender is charged the fee of the used gas, which
+    // is collected as the fee of the transaction.
+    assert!(r_101.exit_reason.is_revert());
+    assert_eq!(r_101.gas_used, gas_limit - 1);
+    assert_eq!(r_101.fee_cost, U256::from(2 * (gas_limit - 1)));
+    assert_eq!(account_101.balance, balance - r_101.fee_cost);
+}
+
+#[test]
+fn test_dispatch_precedence() {
+    let mut adapter = exec_adapter();
+
+    // The code at the metadata contract address returns 0x2a if it is ever
+    // executed by the EVM.
+    ApplyBackend::apply(
+        &mut adapter,
+        vec![Apply::Modify {
+            address:       METADATA_CONTRACT_ADDRESS,
+            basic:         Basic {
+                balance: U256::zero(),
+                nonce:   U256::one(),
+            },
+            code:          Some(hex_decode("602a60005260206000f3").unwrap()),
+            storage:       vec![],
+            reset_storage: false,
+        }],
+        vec![],
+        false,
+    );
+
+    let addr = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
+    let tx = gen_tx(addr, METADATA_CONTRACT_ADDRESS, 0, vec![
+        0xde, 0xad, 0xbe, 0xef,
+    ]);
+    let r = system_contract_dispatch(&mut adapter, &tx).unwrap();
+    assert!(r.exit_reason.is_revert());
+    assert!(r.ret.is_empty());
+
+    let resp = AxonExecutor.exec(&mut adapter, &[tx], &[]);
+    assert!(resp.tx_resp[0].exit_reason.is_revert());
+    assert!(resp.tx_resp[0].ret.is_empty());
+}
+
 fn prepare_tx_1(addr: &H160) -> SignedTransaction {
     let data = metadata_abi::AppendMetadataCall {
         metadata: prepare_metadata(),
@@ -154,12 +297,39 @@ fn prepare_tx_with_consensus_config(addr: &H160, interval: u64) -> SignedTransac
     gen_tx(*addr, METADATA_CONTRACT_ADDRESS, 1000, data.encode())
 }
 
+fn prepare_tx_with_consensus_config_v1(
+    addr: &H160,
+    interval: u64,
+    max_contract_limit: u64,
+    max_call_depth: u64,
+) -> SignedTransaction {
+    let config = prepare_metadata().consensus_config;
+    let data = metadata_abi::UpdateConsensusConfigV1Call {
+        config: ConsensusConfigV1 {
+            propose_ratio: config.propose_ratio,
+            prevote_ratio: config.prevote_ratio,
+            precommit_ratio: config.precommit_ratio,
+            brake_ratio: config.brake_ratio,
+            tx_num_limit: config.tx_num_limit,
+            max_tx_size: config.max_tx_size,
+            gas_limit: config.gas_limit,
+            interval,
+            max_contract_limit,
+            max_call_depth,
+        },
+    };
+
+    gen_tx(*addr, METADATA_CONTRACT_ADDRESS, 1000, data.encode())
+}
+
 // change consensus interval test
 fn test_update_consensus_config<'a>(
     backend: &mut MemoryBackend<'a>,
     executor: &MetadataContract<MemoryBackend<'a>>,
 ) {
     let interval = 10;
+    let max_contract_limit = 0x8000;
+    let max_call_depth = 4;
     let addr = H160::from_str("0xf000000000000000000000000000000000000000").unwrap();
     let tx = prepare_tx_with_consensus_config(&addr, interval);
 
@@ -172,7 +342,96 @@ fn test_update_consensus_config<'a>(
 
     let current_config = store.get_metadata(1).unwrap().consensus_config;
 
-    assert_eq!(current_config.interval, interval)
+    assert_eq!(current_config.interval, interval);
+
+    // The call depth is only modifiable since the Antlia hardfork.
+    let tx =
+        prepare_tx_with_consensus_config_v1(&addr, interval, max_contract_limit, max_call_depth);
+    let r = executor.exec_(backend, &tx);
+    assert!(r.exit_reason.is_revert());
+
+    let flags = HardforkName::Andromeda as u64 | HardforkName::Antlia as u64;
+    let r = with_hardforks(0, &[HardforkName::Andromeda, HardforkName::Antlia], || {
+        HARDFORK_INFO.store(Arc::new(H256::from_low_u64_be(flags.to_be())));
+        let r = executor.exec_(backend, &tx);
+        HARDFORK_INFO.store(Arc::new(H256::zero()));
+        r
+    });
+    assert!(r.exit_reason.is_succeed());
+
+    let root = CURRENT_METADATA_ROOT.with(|r| *r.borrow());
+    let current_config = MetadataStore::new(root)
+        .unwrap()
+        .get_metadata(1)
+        .unwrap()
+        .consensus_config;
+    assert_eq!(current_config.max_contract_limit, max_contract_limit);
+    assert_eq!(current_config.max_call_depth, max_call_depth);
+
+    // The Andromeda config is based on London with the contract size limit in
+    // metadata.
+    let config = AxonExecutor.config_for(HardforkName::Andromeda);
+    let london = Config::london();
+    assert_eq!(
+        config.create_contract_limit,
+        Some(max_contract_limit as usize)
+    );
+    assert_eq!(config.call_stack_limit, max_call_depth as usize);
+    assert_eq!(config.has_base_fee, london.has_base_fee);
+    assert_eq!(config.gas_sload_cold, london.gas_sload_cold);
+
+    // The calls deeper than the configured depth fail with `CallTooDeep`, which
+    // makes the recursive contract revert.
+    assert!(exec_recursive_call(&config, 2).is_succeed());
+    assert!(matches!(
+        exec_recursive_call(&config, 10),
+        ExitReason::Revert(_)
+    ));
+    assert!(exec_recursive_call(&london, 10).is_succeed());
+
+    let config = AxonExecutor.config_for(HardforkName::None);
+    assert_eq!(config.create_contract_limit, london.create_contract_limit);
+    assert_eq!(config.call_stack_limit, london.call_stack_limit);
+}
+
+// Call a contract that calls itself recursively `depth` times and reverts if
+// any of the calls fails.
+fn exec_recursive_call(config: &Config, depth: u64) -> ExitReason {
+    // PUSH1 0x00 CALLDATALOAD DUP1 ISZERO PUSH1 0x26 JUMPI
+    // PUSH1 0x01 SWAP1 SUB PUSH1 0x00 MSTORE
+    // PUSH1 0x00 PUSH1 0x00 PUSH1 0x20 PUSH1 0x00 PUSH1 0x00 ADDRESS GAS CALL
+    // PUSH1 0x24 JUMPI PUSH1 0x00 PUSH1 0x00 REVERT
+    // JUMPDEST STOP JUMPDEST STOP
+    let code = hex_decode(
+        "60003580156026576001900360005260006000602060006000305af1602457600060\
+         00fd5b005b00",
+    )
+    .unwrap();
+    let contract = H160::from_low_u64_be(0x1000);
+    let vicinity = gen_vicinity();
+    let backend = MemoryBackend::new(
+        &vicinity,
+        BTreeMap::from([(contract, MemoryAccount {
+            nonce: U256::one(),
+            balance: U256::zero(),
+            storage: BTreeMap::new(),
+            code,
+        })]),
+    );
+
+    let gas_limit = 10_000_000;
+    let metadata = StackSubstateMetadata::new(gas_limit, config);
+    let mut executor =
+        StackExecutor::new_with_precompiles(MemoryStackState::new(metadata, &backend), config, &());
+    let (exit, _) = executor.transact_call(
+        H160::default(),
+        contract,
+        U256::zero(),
+        H256::from_low_u64_be(depth).0.to_vec(),
+        gas_limit,
+        Vec::new(),
+    );
+    exit
 }
 
 fn prepare_metadata() -> Metadata {
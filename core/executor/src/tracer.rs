@@ -0,0 +1,357 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::{cell::Cell, rc::Rc};
+
+use evm::backend::Basic;
+use evm::executor::stack::{MemoryStackState, StackState, StackSubstateMetadata};
+use evm::{ExitError, Opcode, Transfer};
+use evm_gasometer::tracing::{
+    Event as GasEvent, EventListener as GasEventListener, Snapshot as GasSnapshot,
+};
+use evm_runtime::tracing::{Event as StepEvent, EventListener as StepEventListener};
+
+use protocol::traits::Backend;
+use protocol::types::{AccessList, AccessListItem, H160, H256, U256};
+
+/// A tracer receives a callback before each opcode is executed by
+/// [`AxonExecutor::exec_with_tracer`](crate::AxonExecutor::exec_with_tracer).
+pub trait Tracer {
+    /// A disabled tracer is not installed at all, so that the execution goes
+    /// the same path as the one without tracer.
+    const ENABLED: bool = true;
+
+    /// Called before the `opcode` at `pc` is executed. The `gas_remaining` is
+    /// the gas left in the current call frame before the opcode is charged and
+    /// the `stack_depth` is the number of items on the EVM stack.
+    fn step(&mut self, pc: usize, opcode: Opcode, gas_remaining: u64, stack_depth: usize);
+}
+
+/// The tracer that does nothing, it is never installed.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct NoopTracer;
+
+impl Tracer for NoopTracer {
+    const ENABLED: bool = false;
+
+    fn step(&mut self, _pc: usize, _opcode: Opcode, _gas_remaining: u64, _stack_depth: usize) {}
+}
+
+/// Listens to the gasometer events to record the gas left after the latest
+/// charge.
+pub(crate) struct GasListener {
+    pub(crate) gas_remaining: Rc<Cell<u64>>,
+}
+
+impl GasEventListener for GasListener {
+    fn event(&mut self, event: GasEvent) {
+        let remaining = match event {
+            GasEvent::RecordCost { cost, snapshot }
+            | GasEvent::RecordTransaction { cost, snapshot } => {
+                snapshot.map(|s| snapshot_remaining(&s).saturating_sub(cost))
+            }
+            GasEvent::RecordDynamicCost {
+                gas_cost,
+                memory_gas,
+                snapshot,
+                ..
+            } => snapshot.map(|s| {
+                s.gas_limit
+                    .saturating_sub(s.used_gas)
+                    .saturating_sub(s.memory_gas.max(memory_gas))
+                    .saturating_sub(gas_cost)
+            }),
+            GasEvent::RecordStipend { stipend, snapshot } => {
+                snapshot.map(|s| snapshot_remaining(&s).saturating_add(stipend))
+            }
+            _ => None,
+        };
+
+        if let Some(remaining) = remaining {
+            self.gas_remaining.set(remaining);
+        }
+    }
+}
+
+/// Listens to the runtime step events and forwards them to the tracer.
+pub(crate) struct StepListener<T> {
+    pub(crate) tracer:        T,
+    pub(crate) gas_remaining: Rc<Cell<u64>>,
+}
+
+impl<T: Tracer> StepEventListener for StepListener<T> {
+    fn event(&mut self, event: StepEvent) {
+        if let StepEvent::Step {
+            opcode,
+            position: Ok(pc),
+            stack,
+            ..
+        } = event
+        {
+            self.tracer
+                .step(*pc, opcode, self.gas_remaining.get(), stack.data().len());
+        }
+    }
+}
+
+/// Counts the executed opcodes and raises the shared `exceeded` flag once the
+/// count exceeds the limit. The EVM provides no way for a listener to halt the
+/// machine, so the flag is acted on by [`StepLimitedState`].
+pub(crate) struct StepLimiter {
+    steps:    u64,
+    limit:    u64,
+    exceeded: Rc<Cell<bool>>,
+}
+
+impl StepLimiter {
+    pub(crate) fn new(limit: u64, exceeded: Rc<Cell<bool>>) -> Self {
+        StepLimiter {
+            steps: 0,
+            limit,
+            exceeded,
+        }
+    }
+}
+
+impl StepEventListener for StepLimiter {
+    fn event(&mut self, event: StepEvent) {
+        if let StepEvent::Step { .. } = event {
+            self.steps += 1;
+            if self.steps > self.limit {
+                self.exceeded.set(true);
+            }
+        }
+    }
+}
+
+/// Wraps a [`MemoryStackState`] to stop the execution once the step limit is
+/// exceeded. The step event is emitted right before the opcode is charged
+/// through [`StackState::metadata_mut`], where the gasometer of the current
+/// frame is failed, so the opcode exits with [`ExitError::OutOfGas`] and so do
+/// all the outer frames on their next opcode.
+pub(crate) struct StepLimitedState<'backend, 'config, B> {
+    inner:    MemoryStackState<'backend, 'config, B>,
+    exceeded: Rc<Cell<bool>>,
+}
+
+impl<'backend, 'config, B: Backend> StepLimitedState<'backend, 'config, B> {
+    pub(crate) fn new(
+        inner: MemoryStackState<'backend, 'config, B>,
+        exceeded: Rc<Cell<bool>>,
+    ) -> Self {
+        StepLimitedState { inner, exceeded }
+    }
+
+    pub(crate) fn into_inner(self) -> MemoryStackState<'backend, 'config, B> {
+        self.inner
+    }
+}
+
+impl<'backend, 'config, B: Backend> Backend for StepLimitedState<'backend, 'config, B> {
+    fn gas_price(&self) -> U256 {
+        self.inner.gas_price()
+    }
+
+    fn origin(&self) -> H160 {
+        self.inner.origin()
+    }
+
+    fn block_hash(&self, number: U256) -> H256 {
+        self.inner.block_hash(number)
+    }
+
+    fn block_number(&self) -> U256 {
+        self.inner.block_number()
+    }
+
+    fn block_coinbase(&self) -> H160 {
+        self.inner.block_coinbase()
+    }
+
+    fn block_timestamp(&self) -> U256 {
+        self.inner.block_timestamp()
+    }
+
+    fn block_difficulty(&self) -> U256 {
+        self.inner.block_difficulty()
+    }
+
+    fn block_gas_limit(&self) -> U256 {
+        self.inner.block_gas_limit()
+    }
+
+    fn block_base_fee_per_gas(&self) -> U256 {
+        self.inner.block_base_fee_per_gas()
+    }
+
+    fn chain_id(&self) -> U256 {
+        self.inner.chain_id()
+    }
+
+    fn exists(&self, address: H160) -> bool {
+        self.inner.exists(address)
+    }
+
+    fn basic(&self, address: H160) -> Basic {
+        self.inner.basic(address)
+    }
+
+    fn code(&self, address: H160) -> Vec<u8> {
+        self.inner.code(address)
+    }
+
+    fn storage(&self, address: H160, index: H256) -> H256 {
+        self.inner.storage(address, index)
+    }
+
+    fn original_storage(&self, address: H160, index: H256) -> Option<H256> {
+        self.inner.original_storage(address, index)
+    }
+}
+
+impl<'backend, 'config, B: Backend> StackState<'config> for StepLimitedState<'backend, 'config, B> {
+    fn metadata(&self) -> &StackSubstateMetadata<'config> {
+        self.inner.metadata()
+    }
+
+    fn metadata_mut(&mut self) -> &mut StackSubstateMetadata<'config> {
+        let metadata = self.inner.metadata_mut();
+        if self.exceeded.get() {
+            let _ = metadata.gasometer_mut().fail();
+        }
+        metadata
+    }
+
+    fn enter(&mut self, gas_limit: u64, is_static: bool) {
+        self.inner.enter(gas_limit, is_static)
+    }
+
+    fn exit_commit(&mut self) -> Result<(), ExitError> {
+        self.inner.exit_commit()
+    }
+
+    fn exit_revert(&mut self) -> Result<(), ExitError> {
+        self.inner.exit_revert()
+    }
+
+    fn exit_discard(&mut self) -> Result<(), ExitError> {
+        self.inner.exit_discard()
+    }
+
+    fn is_empty(&self, address: H160) -> bool {
+        self.inner.is_empty(address)
+    }
+
+    fn deleted(&self, address: H160) -> bool {
+        self.inner.deleted(address)
+    }
+
+    fn is_cold(&self, address: H160) -> bool {
+        self.inner.is_cold(address)
+    }
+
+    fn is_storage_cold(&self, address: H160, key: H256) -> bool {
+        self.inner.is_storage_cold(address, key)
+    }
+
+    fn inc_nonce(&mut self, address: H160) {
+        self.inner.inc_nonce(address)
+    }
+
+    fn set_storage(&mut self, address: H160, key: H256, value: H256) {
+        self.inner.set_storage(address, key, value)
+    }
+
+    fn reset_storage(&mut self, address: H160) {
+        self.inner.reset_storage(address)
+    }
+
+    fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) {
+        self.inner.log(address, topics, data)
+    }
+
+    fn set_deleted(&mut self, address: H160) {
+        self.inner.set_deleted(address)
+    }
+
+    fn set_code(&mut self, address: H160, code: Vec<u8>) {
+        self.inner.set_code(address, code)
+    }
+
+    fn transfer(&mut self, transfer: Transfer) -> Result<(), ExitError> {
+        self.inner.transfer(transfer)
+    }
+
+    fn reset_balance(&mut self, address: H160) {
+        self.inner.reset_balance(address)
+    }
+
+    fn touch(&mut self, address: H160) {
+        self.inner.touch(address)
+    }
+}
+
+/// Records the accounts and the storage slots accessed by the executed
+/// opcodes to generate an access list.
+#[derive(Default)]
+pub(crate) struct AccessListRecorder {
+    accesses: BTreeMap<H160, BTreeSet<H256>>,
+}
+
+impl AccessListRecorder {
+    /// Build the access list sorted by the address and the storage key. The
+    /// accounts matching `excluded` are left out unless their storage slots
+    /// are accessed, since the slots of a warm account are still cold.
+    pub(crate) fn into_access_list(self, excluded: impl Fn(&H160) -> bool) -> AccessList {
+        self.accesses
+            .into_iter()
+            .filter(|(address, keys)| !keys.is_empty() || !excluded(address))
+            .map(|(address, keys)| AccessListItem {
+                address,
+                storage_keys: keys.into_iter().collect(),
+            })
+            .collect()
+    }
+
+    fn touch(&mut self, address: H160) -> &mut BTreeSet<H256> {
+        self.accesses.entry(address).or_default()
+    }
+}
+
+impl StepEventListener for AccessListRecorder {
+    fn event(&mut self, event: StepEvent) {
+        if let StepEvent::Step {
+            context,
+            opcode,
+            stack,
+            ..
+        } = event
+        {
+            // The index of the accessed address on the stack.
+            let address_index = match opcode {
+                Opcode::SLOAD | Opcode::SSTORE => {
+                    if let Ok(key) = stack.peek(0) {
+                        self.touch(context.address).insert(key);
+                    }
+                    return;
+                }
+                Opcode::BALANCE
+                | Opcode::EXTCODESIZE
+                | Opcode::EXTCODECOPY
+                | Opcode::EXTCODEHASH
+                | Opcode::SUICIDE => 0,
+                Opcode::CALL | Opcode::CALLCODE | Opcode::DELEGATECALL | Opcode::STATICCALL => 1,
+                _ => return,
+            };
+
+            if let Ok(address) = stack.peek(address_index) {
+                self.touch(address.into());
+            }
+        }
+    }
+}
+
+fn snapshot_remaining(snapshot: &GasSnapshot) -> u64 {
+    snapshot
+        .gas_limit
+        .saturating_sub(snapshot.used_gas)
+        .saturating_sub(snapshot.memory_gas)
+}
@@ -1,12 +1,12 @@
-use protocol::types::{Hasher, H160, H256, U256};
+use std::collections::HashMap;
 
-use crate::FeeAllocate;
+use protocol::traits::ExecutorAdapter;
+use protocol::types::{
+    Config, ExitError, ExitReason, Hasher, ValidatorExtend, H160, H256, U256, U512,
+};
+use protocol::ProtocolResult;
 
-const FUNC_SELECTOR_LEN: usize = 4;
-const U256_BE_BYTES_LEN: usize = 32;
-const REVERT_MSG_LEN_OFFSET: usize = FUNC_SELECTOR_LEN + U256_BE_BYTES_LEN;
-const REVERT_EFFECT_MSG_OFFSET: usize = REVERT_MSG_LEN_OFFSET + U256_BE_BYTES_LEN;
-const EXEC_REVERT: &str = "execution reverted: ";
+use crate::FeeAllocate;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct FeeInlet {
@@ -14,6 +14,29 @@ pub struct FeeInlet {
     pub amount:  U256,
 }
 
+impl FeeInlet {
+    /// Merge the inlets to the same address by summing the amounts, the order
+    /// of the first occurrence of each address is kept. The allocated fee is
+    /// always aggregated before crediting the accounts, so an allocator
+    /// returning several inlets to one address credits it only once.
+    pub fn aggregate(inlets: Vec<FeeInlet>) -> Vec<FeeInlet> {
+        let mut index = HashMap::with_capacity(inlets.len());
+        let mut ret: Vec<FeeInlet> = Vec::with_capacity(inlets.len());
+
+        for inlet in inlets.into_iter() {
+            match index.get(&inlet.address) {
+                Some(&i) => ret[i].amount = ret[i].amount.saturating_add(inlet.amount),
+                None => {
+                    index.insert(inlet.address, ret.len());
+                    ret.push(inlet);
+                }
+            }
+        }
+
+        ret
+    }
+}
+
 #[derive(Default, Clone, Debug)]
 pub struct DefaultFeeAllocator;
 
@@ -23,21 +46,96 @@ impl FeeAllocate for DefaultFeeAllocator {
         block_number: U256,
         fee_collect: U256,
         _proposer: H160,
-        validators: &[protocol::types::ValidatorExtend],
-    ) -> Vec<FeeInlet> {
+        validators: &[ValidatorExtend],
+    ) -> ProtocolResult<Vec<FeeInlet>> {
         if fee_collect.is_zero() || block_number.is_zero() {
-            return Vec::new();
+            return Ok(Vec::new());
         }
 
         let weight_sum = U256::from(validators.iter().map(|v| v.vote_weight).sum::<u32>());
 
-        validators
+        Ok(validators
             .iter()
             .map(|v| FeeInlet {
                 address: v.address,
                 amount:  (fee_collect / weight_sum) * v.vote_weight,
             })
-            .collect()
+            .collect())
+    }
+}
+
+/// The weight of a validator used by [`WeightedFeeAllocator`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeWeight {
+    #[default]
+    Vote,
+    Propose,
+}
+
+/// Allocate the collected fee to the validators proportionally to their
+/// weights, the remainder that can not be divided evenly goes to the
+/// proposer. The sum of the allocated amounts always equals the collected fee.
+#[derive(Default, Clone, Debug)]
+pub struct WeightedFeeAllocator {
+    pub weight: FeeWeight,
+}
+
+impl WeightedFeeAllocator {
+    pub fn new(weight: FeeWeight) -> Self {
+        WeightedFeeAllocator { weight }
+    }
+
+    fn weight_of(&self, validator: &ValidatorExtend) -> u64 {
+        match self.weight {
+            FeeWeight::Vote => validator.vote_weight as u64,
+            FeeWeight::Propose => validator.propose_weight as u64,
+        }
+    }
+}
+
+impl FeeAllocate for WeightedFeeAllocator {
+    fn allocate(
+        &self,
+        block_number: U256,
+        fee_collect: U256,
+        proposer: H160,
+        validators: &[ValidatorExtend],
+    ) -> ProtocolResult<Vec<FeeInlet>> {
+        if fee_collect.is_zero() || block_number.is_zero() {
+            return Ok(Vec::new());
+        }
+
+        let weight_sum = U256::from(validators.iter().map(|v| self.weight_of(v)).sum::<u64>());
+        let mut inlets = Vec::with_capacity(validators.len() + 1);
+        let mut allocated = U256::zero();
+
+        if !weight_sum.is_zero() {
+            for v in validators.iter() {
+                // The amount is no more than the collected fee, so the conversion never fails.
+                let amount = U256::try_from(
+                    fee_collect.full_mul(self.weight_of(v).into()) / U512::from(weight_sum),
+                )
+                .unwrap_or(fee_collect);
+                allocated += amount;
+                inlets.push(FeeInlet {
+                    address: v.address,
+                    amount,
+                });
+            }
+        }
+
+        let dust = fee_collect - allocated;
+        if !dust.is_zero() {
+            match inlets.iter_mut().find(|inlet| inlet.address == proposer) {
+                Some(inlet) => inlet.amount += dust,
+                None => inlets.push(FeeInlet {
+                    address: proposer,
+                    amount:  dust,
+                }),
+            }
+        }
+
+        Ok(inlets)
     }
 }
 
@@ -48,28 +146,93 @@ pub fn code_address(sender: &H160, nonce: &U256) -> H256 {
     Hasher::digest(&stream.out())
 }
 
-pub fn decode_revert_msg(input: &[u8]) -> String {
-    if input.is_empty() {
-        return EXEC_REVERT.to_string();
+/// Predict the address of a contract deployed by `CREATE` from `from` with the
+/// `nonce` of it, without executing the deployment. It is the last 20 bytes of
+/// [`code_address`].
+pub fn predicted_create_address(from: H160, nonce: U256) -> H160 {
+    code_address(&from, &nonce).into()
+}
+
+/// Replace the [`ExitError::CreateContractLimit`] returned by the EVM with a
+/// readable [`ExitError::Other`] carrying the contract size limit of the
+/// `config`, which is the `max_contract_limit` in metadata since the
+/// Andromeda hardfork. The other exit reasons are returned as is.
+pub fn explain_contract_limit(exit: ExitReason, config: &Config) -> ExitReason {
+    match (exit, config.create_contract_limit) {
+        (ExitReason::Error(ExitError::CreateContractLimit), Some(limit)) => ExitReason::Error(
+            ExitError::Other(format!("contract size exceeds the limit of {limit} bytes").into()),
+        ),
+        (exit, _) => exit,
     }
+}
 
-    let decode_reason = |i: &[u8]| -> String {
-        let reason = String::from_iter(i.iter().map(|c| *c as char));
-        EXEC_REVERT.to_string() + &reason
-    };
+/// Calculate the address of a contract deployed by `CREATE2`, which is the last
+/// 20 bytes of `keccak256(0xff ++ sender ++ salt ++ keccak256(init_code))` as
+/// [`EIP-1014`](https://eips.ethereum.org/EIPS/eip-1014) defines.
+pub fn code_address_create2(sender: &H160, salt: &H256, init_code_hash: &H256) -> H160 {
+    let mut preimage = [0u8; 85];
+    preimage[0] = 0xff;
+    preimage[1..21].copy_from_slice(sender.as_bytes());
+    preimage[21..53].copy_from_slice(salt.as_bytes());
+    preimage[53..85].copy_from_slice(init_code_hash.as_bytes());
+    Hasher::digest(preimage).into()
+}
 
-    if input.len() < REVERT_EFFECT_MSG_OFFSET {
-        return decode_reason(input);
+/// The gas limit of a transaction and the gas fee to prepay at the gas price.
+/// The gas limit beyond u64 or the overflowed pre-pay gas can not pass the
+/// mempool, but they are rejected here as well rather than panicking.
+pub(crate) fn checked_prepay_gas(
+    gas_limit: U256,
+    gas_price: U256,
+) -> Result<(u64, U256), ExitReason> {
+    if gas_limit > U256::from(u64::MAX) {
+        return Err(ExitReason::Error(ExitError::Other(
+            "gas limit exceeds u64".into(),
+        )));
     }
 
-    let end_offset = REVERT_EFFECT_MSG_OFFSET
-        + U256::from_big_endian(&input[REVERT_MSG_LEN_OFFSET..REVERT_EFFECT_MSG_OFFSET]).as_usize();
+    let prepay_gas = gas_price
+        .checked_mul(gas_limit)
+        .ok_or_else(|| ExitReason::Error(ExitError::Other("pre-pay gas overflow".into())))?;
+    Ok((gas_limit.as_u64(), prepay_gas))
+}
 
-    if input.len() < end_offset {
-        return decode_reason(input);
+/// Deduct the prepaid gas fee from the sender before executing a transaction.
+pub(crate) fn deduct_prepay_gas<Adapter: ExecutorAdapter>(
+    adapter: &mut Adapter,
+    sender: H160,
+    prepay_gas: U256,
+) {
+    if prepay_gas.is_zero() {
+        return;
     }
 
-    decode_reason(&input[REVERT_EFFECT_MSG_OFFSET..end_offset])
+    let mut account = adapter.get_account(&sender);
+    account.balance = account.balance.saturating_sub(prepay_gas);
+    adapter.save_account(&sender, &account);
+}
+
+/// Refund the fee of the remaining gas to the sender after executing a
+/// transaction.
+pub(crate) fn refund_remaining_gas<Adapter: ExecutorAdapter>(
+    adapter: &mut Adapter,
+    sender: H160,
+    remained_gas: u64,
+    gas_price: U256,
+) {
+    if remained_gas == 0 || gas_price.is_zero() {
+        return;
+    }
+
+    let refund = U256::from(remained_gas)
+        .checked_mul(gas_price)
+        .unwrap_or_else(U256::max_value);
+    let mut account = adapter.get_account(&sender);
+    account.balance = account
+        .balance
+        .checked_add(refund)
+        .unwrap_or_else(U256::max_value);
+    adapter.save_account(&sender, &account);
 }
 
 #[cfg(test)]
@@ -101,6 +264,88 @@ mod tests {
         assert_eq!(
             hex_encode(addr.0).as_str(),
             "cd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"
-        )
+        );
+        assert_eq!(predicted_create_address(sender, nonce), addr);
+    }
+
+    #[test]
+    fn test_code_address_create2() {
+        // The examples of EIP-1014.
+        let addr = code_address_create2(
+            &H160::zero(),
+            &H256::zero(),
+            &Hasher::digest(hex_decode("00").unwrap()),
+        );
+        assert_eq!(
+            hex_encode(addr.0).as_str(),
+            "4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38"
+        );
+
+        let addr = code_address_create2(
+            &H160::from_slice(&hex_decode("deadbeef00000000000000000000000000000000").unwrap()),
+            &H256::zero(),
+            &Hasher::digest(hex_decode("00").unwrap()),
+        );
+        assert_eq!(
+            hex_encode(addr.0).as_str(),
+            "b928f69bb1d91cd65274e3c79d8986362984fda3"
+        );
+
+        let addr = code_address_create2(&H160::zero(), &H256::zero(), &Hasher::digest(b""));
+        assert_eq!(
+            hex_encode(addr.0).as_str(),
+            "e33c0c7f7df4809055c3eba6c09cfe4baf1bd9e0"
+        );
+    }
+
+    fn mock_validator(address: H160, vote_weight: u32) -> ValidatorExtend {
+        ValidatorExtend {
+            bls_pub_key: Default::default(),
+            pub_key: Default::default(),
+            address,
+            propose_weight: 1,
+            vote_weight,
+        }
+    }
+
+    #[test]
+    fn test_weighted_fee_allocator() {
+        let (addr_1, addr_2, addr_3) = (H160::random(), H160::random(), H160::random());
+        let validators = vec![
+            mock_validator(addr_1, 1),
+            mock_validator(addr_2, 1),
+            mock_validator(addr_3, 1),
+        ];
+        let allocator = WeightedFeeAllocator::default();
+        let sum = |inlets: &[FeeInlet]| inlets.iter().fold(U256::zero(), |acc, i| acc + i.amount);
+
+        // The dust goes to the proposer.
+        let inlets = allocator
+            .allocate(U256::one(), 100u64.into(), addr_2, &validators)
+            .unwrap();
+        assert_eq!(sum(&inlets), 100u64.into());
+        assert_eq!(inlets[0].amount, 33u64.into());
+        assert_eq!(inlets[1].amount, 34u64.into());
+        assert_eq!(inlets[2].amount, 33u64.into());
+
+        // The proposer not in the validator list gets the dust as well.
+        let proposer = H160::random();
+        let validators = vec![mock_validator(addr_1, 2), mock_validator(addr_2, 5)];
+        let inlets = allocator
+            .allocate(U256::one(), 1000u64.into(), proposer, &validators)
+            .unwrap();
+        assert_eq!(sum(&inlets), 1000u64.into());
+        assert_eq!(inlets.len(), 3);
+        assert_eq!(inlets[0].amount, 285u64.into());
+        assert_eq!(inlets[1].amount, 714u64.into());
+        assert_eq!(inlets[2].address, proposer);
+        assert_eq!(inlets[2].amount, U256::one());
+
+        // Weighted by the propose weight, which is the same for all.
+        let inlets = WeightedFeeAllocator::new(FeeWeight::Propose)
+            .allocate(U256::one(), U256::max_value(), addr_1, &validators)
+            .unwrap();
+        assert_eq!(sum(&inlets), U256::max_value());
+        assert_eq!(inlets[0].amount, U256::max_value() / 2 + 1);
     }
 }
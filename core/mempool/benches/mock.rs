@@ -157,12 +157,12 @@ pub fn mock_signed_tx(
     valid: bool,
 ) -> SignedTransaction {
     let raw = mock_transaction(nonce, false);
-    let mut tx = UnverifiedTransaction {
-        unsigned:  UnsignedTransaction::Eip1559(raw),
-        signature: None,
-        chain_id:  Some(random::<u64>()),
-        hash:      Default::default(),
-    };
+    let mut tx = UnverifiedTransaction::new(
+        UnsignedTransaction::Eip1559(raw),
+        None,
+        Some(random::<u64>()),
+        Default::default(),
+    );
 
     let signature = if valid {
         Secp256k1Recoverable::sign_message(tx.signature_hash(true).as_bytes(), &priv_key.to_bytes())
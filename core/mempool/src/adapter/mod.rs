@@ -9,15 +9,14 @@ use futures::{
     stream::StreamExt,
 };
 use log::{debug, error};
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 
 use protocol::traits::{
     Context, Gossip, Interoperation, MemPoolAdapter, PeerTrust, Priority, ReadOnlyStorage, Rpc,
     TrustFeedback,
 };
 use protocol::types::{
-    recover_intact_pub_key, Backend, BatchSignedTxs, CellDepWithPubKey, Hash, MerkleRoot,
-    SignedTransaction, H160, U256,
+    Backend, Basic, BatchSignedTxs, Hash, MerkleRoot, SignedTransaction, H160, U256,
 };
 use protocol::{
     async_trait,
@@ -27,15 +26,17 @@ use protocol::{
 };
 
 use common_apm_derive::trace_span;
-use common_crypto::{Crypto, Secp256k1Recoverable};
+use common_config_parser::types::spec::HardforkName;
+use common_crypto::Crypto;
 use core_executor::{
-    is_call_system_script, AxonExecutorReadOnlyAdapter, DataProvider, MetadataHandle,
+    is_call_system_script, is_enabled_in_block, AxonExecutorReadOnlyAdapter, DataProvider,
+    MetadataHandle, NonceCheck,
 };
 use core_interoperation::InteroperationImpl;
 
 use crate::adapter::message::MsgPullTxs;
 use crate::context::TxContext;
-use crate::MemPoolError;
+use crate::{validate_transaction, MemPoolError, ValidationContext};
 
 struct IntervalTxsBroadcaster;
 
@@ -120,10 +121,13 @@ pub struct DefaultMemPoolAdapter<C, N, S, DB, I> {
     storage: Arc<S>,
     trie_db: Arc<DB>,
 
-    addr_nonce:  DashMap<H160, (U256, U256)>,
-    gas_limit:   AtomicU64,
-    max_tx_size: AtomicUsize,
-    chain_id:    u64,
+    addr_nonce:    DashMap<H160, (U256, U256)>,
+    base_fee:      RwLock<Option<U256>>,
+    gas_limit:     AtomicU64,
+    max_tx_size:   AtomicUsize,
+    min_gas_price: U256,
+    max_nonce_gap: u64,
+    chain_id:      u64,
 
     stx_tx: UnboundedSender<(Option<usize>, SignedTransaction)>,
     err_rx: Mutex<UnboundedReceiver<ProtocolError>>,
@@ -149,6 +153,8 @@ where
         max_tx_size: usize,
         broadcast_txs_size: usize,
         broadcast_txs_interval: u64,
+        min_gas_price: u64,
+        max_nonce_gap: u64,
     ) -> Self {
         let (stx_tx, stx_rx) = unbounded();
         let (err_tx, err_rx) = unbounded();
@@ -167,8 +173,11 @@ where
             trie_db,
 
             addr_nonce: DashMap::new(),
+            base_fee: RwLock::new(None),
             gas_limit: AtomicU64::new(gas_limit),
             max_tx_size: AtomicUsize::new(max_tx_size),
+            min_gas_price: U256::from(min_gas_price),
+            max_nonce_gap,
             chain_id,
 
             stx_tx,
@@ -199,23 +208,21 @@ where
         .into())
     }
 
-    fn verify_chain_id(&self, ctx: Context, stx: &SignedTransaction) -> ProtocolResult<()> {
-        if stx.transaction.chain_id.is_some() && Some(self.chain_id) != stx.transaction.chain_id {
-            if ctx.is_network_origin_txs() {
-                self.network.report(
-                    ctx,
-                    TrustFeedback::Worse(format!(
-                        "Mempool wrong chain of tx {:#x}",
-                        stx.transaction.hash
-                    )),
-                );
-            }
-            let wrong_chain_id = MemPoolError::WrongChain(stx.transaction.hash);
-
-            return Err(wrong_chain_id.into());
+    /// Report the peer which sends a transaction of another chain, the
+    /// transaction itself is rejected by [`validate_transaction`].
+    fn report_wrong_chain(&self, ctx: Context, stx: &SignedTransaction) {
+        if ctx.is_network_origin_txs()
+            && stx.transaction.chain_id.is_some()
+            && Some(self.chain_id) != stx.transaction.chain_id
+        {
+            self.network.report(
+                ctx,
+                TrustFeedback::Worse(format!(
+                    "Mempool wrong chain of tx {:#x}",
+                    stx.transaction.hash
+                )),
+            );
         }
-
-        Ok(())
     }
 
     fn verify_tx_size(&self, ctx: Context, stx: &SignedTransaction) -> ProtocolResult<()> {
@@ -243,7 +250,7 @@ where
 
     fn verify_gas_price(&self, stx: &SignedTransaction) -> ProtocolResult<()> {
         let gas_price = stx.transaction.unsigned.gas_price();
-        if gas_price == U256::zero() || gas_price >= U256::from(u64::MAX) {
+        if gas_price.is_zero() || gas_price >= U256::from(u64::MAX) {
             return Err(MemPoolError::InvalidGasPrice(gas_price).into());
         }
 
@@ -273,34 +280,64 @@ where
         Ok(())
     }
 
+    /// Verify the interoperation signature with the CKB-VM, the secp256k1
+    /// signature is verified by [`validate_transaction`].
     async fn verify_signature(&self, ctx: Context, stx: &SignedTransaction) -> ProtocolResult<()> {
-        let signature = stx.transaction.signature.clone().unwrap();
-        if signature.is_eth_sig() {
-            // Verify secp256k1 signature
-            Secp256k1Recoverable::verify_signature(
-                stx.transaction.signature_hash(true).as_bytes(),
-                signature.as_bytes().as_ref(),
-                recover_intact_pub_key(&stx.public.unwrap()).as_bytes(),
+        let signature = match stx.transaction.signature.as_ref() {
+            Some(signature) if !signature.is_eth_sig() => signature,
+            _ => return Ok(()),
+        };
+
+        let root = self.executor_backend(ctx).await?.get_image_cell_root();
+
+        // Verify interoperation signature call CKB-VM mode, every cell dep should
+        // verify the signature S.
+        for r in signature.interoperation_cell_deps()? {
+            InteroperationImpl::call_ckb_vm(
+                Default::default(),
+                &DataProvider::new(root),
+                r.cell_dep,
+                &[r.pub_key, signature.s.clone()],
+                u64::MAX,
             )
-            .map_err(|err| AdapterError::VerifySignature(err.to_string()))?;
+            .map_err(|e| AdapterError::VerifySignature(e.to_string()))?;
+        }
 
-            return Ok(());
+        Ok(())
+    }
+
+    /// Get the nonce and the balance of an account, which are cached until the
+    /// next block is committed.
+    async fn account(&self, ctx: Context, addr: &H160) -> ProtocolResult<Basic> {
+        if let Some(res) = self.addr_nonce.get(addr) {
+            let (nonce, balance) = *res.value();
+            return Ok(Basic { nonce, balance });
         }
 
-        let root = self.executor_backend(ctx).await?.get_image_cell_root();
+        let account = self.executor_backend(ctx).await?.basic(*addr);
+        self.addr_nonce
+            .insert(*addr, (account.nonce, account.balance));
+        Ok(account)
+    }
 
-        // Verify interoperation signature call CKB-VM mode
-        let r = rlp::decode::<CellDepWithPubKey>(&signature.r[1..]).map_err(AdapterError::Rlp)?;
-        InteroperationImpl::call_ckb_vm(
-            Default::default(),
-            &DataProvider::new(root),
-            r.cell_dep,
-            &[r.pub_key, signature.s],
-            u64::MAX,
-        )
-        .map_err(|e| AdapterError::VerifySignature(e.to_string()))?;
+    /// Get the base fee of the next block, which is cached until the next
+    /// block is committed, so that a batch of transactions reads the latest
+    /// header once.
+    async fn next_base_fee(&self, ctx: Context) -> ProtocolResult<U256> {
+        let cached = *self.base_fee.read();
+        if let Some(base_fee) = cached {
+            return Ok(base_fee);
+        }
 
-        Ok(())
+        // The base fee is only charged since the `Antlia` hardfork.
+        let header = self.storage.get_latest_block_header(ctx).await?;
+        let base_fee = if is_enabled_in_block(HardforkName::Antlia, header.number + 1) {
+            header.base_fee_per_gas
+        } else {
+            U256::zero()
+        };
+        *self.base_fee.write() = Some(base_fee);
+        Ok(base_fee)
     }
 
     async fn executor_backend(
@@ -372,65 +409,52 @@ where
         ctx: Context,
         tx: &SignedTransaction,
     ) -> ProtocolResult<U256> {
-        if is_call_system_script(tx.transaction.unsigned.action())? {
-            return self.check_system_script_tx_authorization(ctx, tx).await;
-        }
+        let is_system_script = is_call_system_script(tx.transaction.unsigned.action())?;
+        let account = if is_system_script {
+            Basic::default()
+        } else {
+            self.account(ctx.clone(), &tx.sender).await?
+        };
 
-        let addr = &tx.sender;
-        if let Some(res) = self.addr_nonce.get(addr) {
-            if tx.transaction.unsigned.nonce() < &res.value().0 {
-                return Err(MemPoolError::InvalidNonce {
-                    current:  res.value().0.as_u64(),
-                    tx_nonce: tx.transaction.unsigned.nonce().as_u64(),
-                }
-                .into());
-            } else if res.value().1 < tx.transaction.unsigned.may_cost() {
-                return Err(MemPoolError::ExceedBalance {
-                    tx_hash:         tx.transaction.hash,
-                    account_balance: res.value().1,
-                    tx_gas_limit:    *tx.transaction.unsigned.gas_limit(),
-                }
-                .into());
-            } else {
-                return Ok(tx.transaction.unsigned.nonce() - res.value().0);
-            }
+        let base_fee = self.next_base_fee(ctx.clone()).await?;
+
+        // The context borrows a non-`Send` lookup, so it must be dropped before
+        // the next await.
+        let nonce_check = {
+            let lookup = |_: &H160| account.clone();
+            let validation = ValidationContext {
+                chain_id: self.chain_id,
+                base_fee,
+                gas_floor: self.min_gas_price,
+                max_nonce_gap: self.max_nonce_gap,
+                account: &lookup,
+            };
+            validate_transaction(tx, &validation)
+        };
+        if nonce_check.is_err() {
+            self.report_wrong_chain(ctx.clone(), tx);
         }
+        let nonce_check = nonce_check?;
 
-        let backend = self.executor_backend(ctx).await?;
-        let account = backend.basic(*addr);
-        self.addr_nonce
-            .insert(*addr, (account.nonce, account.balance));
+        if is_system_script {
+            return self.check_system_script_tx_authorization(ctx, tx).await;
+        }
 
-        if &account.nonce > tx.transaction.unsigned.nonce() {
-            return Err(MemPoolError::InvalidNonce {
+        match nonce_check {
+            NonceCheck::Ready => Ok(U256::zero()),
+            NonceCheck::Queued(gap) => Ok(gap),
+            NonceCheck::Stale => Err(MemPoolError::InvalidNonce {
                 current:  account.nonce.as_u64(),
                 tx_nonce: tx.transaction.unsigned.nonce().as_u64(),
             }
-            .into());
-        }
-
-        if account.balance < tx.transaction.unsigned.may_cost() {
-            return Err(MemPoolError::ExceedBalance {
-                tx_hash:         tx.transaction.hash,
-                account_balance: account.balance,
-                tx_gas_limit:    *tx.transaction.unsigned.gas_limit(),
-            }
-            .into());
+            .into()),
         }
-
-        Ok(tx.transaction.unsigned.nonce() - account.nonce)
     }
 
+    /// The checks of [`validate_transaction`] are done by
+    /// [`check_authorization`](Self::check_authorization), only the limits of
+    /// the node and the interoperation signature are checked here.
     async fn check_transaction(&self, ctx: Context, stx: &SignedTransaction) -> ProtocolResult<()> {
-        if stx.transaction.signature.is_none() {
-            return Err(AdapterError::VerifySignature("missing signature".to_string()).into());
-        }
-
-        if stx.public.is_none() {
-            return Err(AdapterError::VerifySignature("missing public key".to_string()).into());
-        }
-
-        self.verify_chain_id(ctx.clone(), stx)?;
         self.verify_tx_size(ctx.clone(), stx)?;
         self.verify_gas_price(stx)?;
         self.verify_gas_limit(ctx.clone(), stx)?;
@@ -480,10 +504,12 @@ where
         self.max_tx_size
             .store(max_tx_size as usize, Ordering::Release);
         self.addr_nonce.clear();
+        *self.base_fee.write() = None;
     }
 
     fn clear_nonce_cache(&self) {
-        self.addr_nonce.clear()
+        self.addr_nonce.clear();
+        *self.base_fee.write() = None;
     }
 
     fn report_good(&self, ctx: Context) {
@@ -534,9 +560,19 @@ mod tests {
     };
     use parking_lot::Mutex;
 
-    use protocol::{traits::MessageCodec, types::Bytes};
+    use common_crypto::{PrivateKey, Secp256k1, Secp256k1RecoverablePrivateKey};
+    use core_db::MemoryAdapter;
+    use core_executor::AxonExecutorApplyAdapter;
+    use core_storage::ImplStorage;
+    use protocol::rand::rngs::OsRng;
+    use protocol::traits::{MessageCodec, Storage};
+    use protocol::trie::MemoryDB;
+    use protocol::types::{Block, Bytes, Header};
 
-    use crate::tests::default_mock_txs;
+    use crate::tests::{default_mock_txs, raw_tx, sign_tx};
+
+    const CHAIN_ID: u64 = 5;
+    const MAX_NONCE_GAP: u64 = 3;
 
     #[derive(Clone)]
     struct MockGossip {
@@ -612,6 +648,34 @@ mod tests {
         }
     }
 
+    #[async_trait]
+    impl Rpc for MockGossip {
+        async fn call<M, R>(&self, _: Context, _: &str, _: M, _: Priority) -> ProtocolResult<R>
+        where
+            M: MessageCodec,
+            R: MessageCodec,
+        {
+            Err(AdapterError::Internal.into())
+        }
+
+        async fn response<M>(
+            &self,
+            _: Context,
+            _: &str,
+            _: ProtocolResult<M>,
+            _: Priority,
+        ) -> ProtocolResult<()>
+        where
+            M: MessageCodec,
+        {
+            Ok(())
+        }
+    }
+
+    impl PeerTrust for MockGossip {
+        fn report(&self, _: Context, _: TrustFeedback) {}
+    }
+
     macro_rules! pop_msg {
         ($msgs:expr) => {{
             let msg = $msgs.pop().expect("should have one message");
@@ -708,4 +772,71 @@ mod tests {
         let msg = pop_msg!(msgs);
         assert_eq!(msg.0.len(), 10, "first message should only have 10 stx");
     }
+
+    #[tokio::test]
+    async fn test_check_authorization_nonce_gap() {
+        let db = Arc::new(MemoryDB::new(false));
+        let storage = Arc::new(ImplStorage::new(Arc::new(MemoryAdapter::new()), 20));
+        let priv_key = Secp256k1RecoverablePrivateKey::generate(&mut OsRng);
+        let tx_with_nonce = |nonce: u64| {
+            let mut raw = raw_tx();
+            raw.nonce = nonce.into();
+            sign_tx(&priv_key, raw, CHAIN_ID)
+        };
+
+        let state_root = AxonExecutorApplyAdapter::new(
+            Arc::clone(&db),
+            Arc::clone(&storage),
+            Default::default(),
+        )
+        .unwrap()
+        .genesis_builder()
+        .set_balance(tx_with_nonce(0).sender, U256::from(u64::MAX))
+        .commit();
+        let block = Block {
+            header: Header {
+                state_root,
+                chain_id: CHAIN_ID,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        storage
+            .set_latest_block(Context::new(), block)
+            .await
+            .unwrap();
+
+        let (signal_tx, _signal_rx) = unbounded();
+        let adapter = DefaultMemPoolAdapter::<Secp256k1, _, _, _, InteroperationImpl>::new(
+            MockGossip::new(signal_tx),
+            storage,
+            db,
+            CHAIN_ID,
+            u64::MAX,
+            1024 * 1024,
+            200,
+            200,
+            1,
+            MAX_NONCE_GAP,
+        );
+
+        let ctx = Context::new();
+        let gap = adapter
+            .check_authorization(ctx.clone(), &tx_with_nonce(0))
+            .await
+            .unwrap();
+        assert_eq!(gap, U256::zero());
+        let gap = adapter
+            .check_authorization(ctx.clone(), &tx_with_nonce(MAX_NONCE_GAP))
+            .await
+            .unwrap();
+        assert_eq!(gap, MAX_NONCE_GAP.into());
+
+        // The nonce is too far ahead of the sender's to be queued.
+        let err = adapter
+            .check_authorization(ctx, &tx_with_nonce(MAX_NONCE_GAP + 1))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("nonce"), "{err}");
+    }
 }
@@ -4,9 +4,11 @@ mod pool;
 #[cfg(test)]
 mod tests;
 mod tx_wrapper;
+mod validator;
 
 pub use adapter::message::{MsgPullTxs, NewTxsHandler, PullTxsHandler};
 pub use adapter::{AdapterError, DefaultMemPoolAdapter};
+pub use validator::{validate_transaction, ValidationContext};
 
 use std::collections::HashSet;
 use std::error::Error;
@@ -375,6 +377,18 @@ pub enum MemPoolError {
     #[display(fmt = "Invalid gas price {:?}", _0)]
     InvalidGasPrice(U256),
 
+    #[display(
+        fmt = "Tx: {:?} gas price {} is lower than base fee {}",
+        tx_hash,
+        gas_price,
+        base_fee
+    )]
+    FeeCapTooLow {
+        tx_hash:   Hash,
+        gas_price: U256,
+        base_fee:  U256,
+    },
+
     #[display(
         fmt = "Tx: {:?} exceeds size limit, now: {}, limit: {} Bytes",
         tx_hash,
@@ -399,6 +413,18 @@ pub enum MemPoolError {
         gas_limit_tx:     u64,
     },
 
+    #[display(
+        fmt = "Tx: {:?} gas limit {} is lower than intrinsic gas {}",
+        tx_hash,
+        gas_limit_tx,
+        intrinsic_gas
+    )]
+    IntrinsicGasTooLow {
+        tx_hash:       Hash,
+        gas_limit_tx:  u64,
+        intrinsic_gas: u64,
+    },
+
     #[display(fmt = "Tx nonce {} is invalid current nonce {}", tx_nonce, current)]
     InvalidNonce { current: u64, tx_nonce: u64 },
 
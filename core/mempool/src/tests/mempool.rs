@@ -241,6 +241,10 @@ async fn test_nonce_insert() {
             }
             UnsignedTransaction::Eip2930(ref mut p) => p.gas_price = 2.into(),
             UnsignedTransaction::Legacy(ref mut p) => p.gas_price = 2.into(),
+            UnsignedTransaction::Eip4844(ref mut p) => {
+                p.gas_price = 2.into();
+                p.max_priority_fee_per_gas = 2.into();
+            }
         }
         tx.transaction.hash = H256::from_low_u64_le(2);
 
@@ -1,4 +1,5 @@
 mod mempool;
+mod validator;
 
 use std::sync::Arc;
 
@@ -276,12 +277,12 @@ pub fn mock_signed_tx(
     valid: bool,
 ) -> SignedTransaction {
     let raw = mock_transaction(nonce, false);
-    let mut tx = UnverifiedTransaction {
-        unsigned:  UnsignedTransaction::Eip1559(raw),
-        signature: None,
-        chain_id:  Some(random::<u64>()),
-        hash:      Default::default(),
-    };
+    let mut tx = UnverifiedTransaction::new(
+        UnsignedTransaction::Eip1559(raw),
+        None,
+        Some(random::<u64>()),
+        Default::default(),
+    );
 
     let signature = if valid {
         Secp256k1Recoverable::sign_message(tx.signature_hash(true).as_bytes(), &priv_key.to_bytes())
@@ -302,6 +303,46 @@ pub fn mock_signed_tx(
     }
 }
 
+pub fn raw_tx() -> Eip1559Transaction {
+    Eip1559Transaction {
+        nonce:                    U256::one(),
+        max_priority_fee_per_gas: 2u64.into(),
+        gas_price:                10u64.into(),
+        gas_limit:                100_000u64.into(),
+        action:                   TransactionAction::Call(H160::random()),
+        value:                    U256::one(),
+        data:                     Bytes::new(),
+        access_list:              vec![],
+    }
+}
+
+pub fn sign_tx(
+    priv_key: &Secp256k1RecoverablePrivateKey,
+    raw: Eip1559Transaction,
+    chain_id: u64,
+) -> SignedTransaction {
+    let mut tx = UnverifiedTransaction::new(
+        UnsignedTransaction::Eip1559(raw),
+        None,
+        Some(chain_id),
+        Default::default(),
+    );
+    let signature = Secp256k1Recoverable::sign_message(
+        tx.signature_hash(true).as_bytes(),
+        &priv_key.to_bytes(),
+    )
+    .unwrap()
+    .to_bytes();
+    tx.signature = Some(signature.into());
+
+    let pub_key = Public::from_slice(&priv_key.pub_key().to_uncompressed_bytes()[1..65]);
+    SignedTransaction {
+        transaction: tx.calc_hash(),
+        sender:      public_to_address(&pub_key),
+        public:      Some(pub_key),
+    }
+}
+
 fn mock_system_script_signed_tx(
     priv_key: &Secp256k1RecoverablePrivateKey,
     pub_key: &Secp256k1RecoverablePublicKey,
@@ -310,12 +351,12 @@ fn mock_system_script_signed_tx(
     valid: bool,
 ) -> SignedTransaction {
     let raw = mock_transaction(nonce, true);
-    let mut tx = UnverifiedTransaction {
-        unsigned:  UnsignedTransaction::Eip1559(raw),
-        signature: None,
-        chain_id:  Some(random::<u64>()),
-        hash:      Default::default(),
-    };
+    let mut tx = UnverifiedTransaction::new(
+        UnsignedTransaction::Eip1559(raw),
+        None,
+        Some(random::<u64>()),
+        Default::default(),
+    );
 
     let signature = if valid {
         Secp256k1Recoverable::sign_message(tx.signature_hash(true).as_bytes(), &priv_key.to_bytes())
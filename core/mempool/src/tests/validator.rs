@@ -0,0 +1,146 @@
+use protocol::types::Basic;
+
+use core_executor::NonceCheck;
+
+use crate::{validate_transaction, ValidationContext};
+
+use super::*;
+
+const CHAIN_ID: u64 = 5;
+const MAX_NONCE_GAP: u64 = 3;
+
+fn check(
+    stx: &SignedTransaction,
+    base_fee: u64,
+    gas_floor: u64,
+    account: Basic,
+) -> Result<NonceCheck, String> {
+    let lookup = move |_: &H160| account.clone();
+    let ctx = ValidationContext {
+        chain_id:      CHAIN_ID,
+        base_fee:      base_fee.into(),
+        gas_floor:     gas_floor.into(),
+        max_nonce_gap: MAX_NONCE_GAP,
+        account:       &lookup,
+    };
+
+    validate_transaction(stx, &ctx).map_err(|e| e.to_string())
+}
+
+fn validate(stx: &SignedTransaction, base_fee: u64, gas_floor: u64, account: Basic) -> String {
+    check(stx, base_fee, gas_floor, account)
+        .err()
+        .unwrap_or_default()
+}
+
+fn rich_account() -> Basic {
+    Basic {
+        balance: U256::from(u64::MAX),
+        nonce:   U256::one(),
+    }
+}
+
+#[test]
+fn test_validate_transaction() {
+    let priv_key = Secp256k1RecoverablePrivateKey::generate(&mut OsRng);
+    let stx = sign_tx(&priv_key, raw_tx(), CHAIN_ID);
+    assert_eq!(validate(&stx, 5, 1, rich_account()), "");
+
+    // Missing signature.
+    let mut tx = stx.clone();
+    tx.transaction.signature = None;
+    assert!(validate(&tx, 5, 1, rich_account()).contains("missing signature"));
+
+    // Invalid signature.
+    let mut tx = stx.clone();
+    tx.transaction.signature = Some(Bytes::copy_from_slice(&[0u8; 65]).into());
+    assert!(validate(&tx, 5, 1, rich_account()).contains("verify signature"));
+
+    // Wrong chain id.
+    let tx = sign_tx(&priv_key, raw_tx(), CHAIN_ID + 1);
+    assert!(validate(&tx, 5, 1, rich_account()).contains("doesn't match our chain id"));
+
+    // The nonce reaches the max one.
+    let mut raw = raw_tx();
+    raw.nonce = U256::from(u64::MAX);
+    let tx = sign_tx(&priv_key, raw, CHAIN_ID);
+    assert!(validate(&tx, 5, 1, rich_account()).contains("max nonce"));
+
+    // The max fee per gas is less than the max priority fee per gas.
+    let mut raw = raw_tx();
+    raw.max_priority_fee_per_gas = 11u64.into();
+    let tx = sign_tx(&priv_key, raw, CHAIN_ID);
+    assert!(validate(&tx, 5, 1, rich_account()).contains("less than max priority fee per gas"));
+
+    // The gas price is lower than the floor or the base fee.
+    assert!(validate(&stx, 5, 11, rich_account()).contains("Invalid gas price"));
+    assert!(validate(&stx, 11, 1, rich_account()).contains("is lower than base fee 11"));
+
+    // The gas limit can not cover the intrinsic gas.
+    let mut raw = raw_tx();
+    raw.gas_limit = 20_000u64.into();
+    let tx = sign_tx(&priv_key, raw, CHAIN_ID);
+    assert!(validate(&tx, 5, 1, rich_account()).contains("is lower than intrinsic gas"));
+
+    // The balance can not cover the cost.
+    let account = Basic {
+        balance: 1_000_000u64.into(),
+        ..rich_account()
+    };
+    assert!(validate(&stx, 5, 1, account).contains("exceeds balance"));
+}
+
+#[test]
+fn test_validate_nonce_gap() {
+    let priv_key = Secp256k1RecoverablePrivateKey::generate(&mut OsRng);
+    let with_nonce = |nonce: u64| {
+        let mut raw = raw_tx();
+        raw.nonce = nonce.into();
+        sign_tx(&priv_key, raw, CHAIN_ID)
+    };
+    let account = Basic {
+        nonce: 5u64.into(),
+        ..rich_account()
+    };
+
+    // Equal to the account nonce, ready to be packed.
+    assert_eq!(
+        check(&with_nonce(5), 5, 1, account.clone()),
+        Ok(NonceCheck::Ready)
+    );
+
+    // Above the account nonce, queued with the gap.
+    assert_eq!(
+        check(&with_nonce(6), 5, 1, account.clone()),
+        Ok(NonceCheck::Queued(1u64.into()))
+    );
+    assert_eq!(
+        check(&with_nonce(5 + MAX_NONCE_GAP), 5, 1, account.clone()),
+        Ok(NonceCheck::Queued(MAX_NONCE_GAP.into()))
+    );
+    assert!(check(&with_nonce(6 + MAX_NONCE_GAP), 5, 1, account.clone())
+        .unwrap_err()
+        .contains("Tx nonce 9 is invalid current nonce 5"));
+
+    // Below the account nonce, stale.
+    assert_eq!(check(&with_nonce(4), 5, 1, account), Ok(NonceCheck::Stale));
+}
+
+#[test]
+fn test_validate_system_script_tx() {
+    let priv_key = Secp256k1RecoverablePrivateKey::generate(&mut OsRng);
+    let mut raw = raw_tx();
+    raw.action = TransactionAction::Call(NATIVE_TOKEN_ISSUE_ADDRESS);
+    raw.gas_limit = U256::one();
+    let stx = sign_tx(&priv_key, raw, CHAIN_ID);
+
+    // Neither the gas floor, the base fee nor the intrinsic gas is checked, and
+    // the sender is not looked up.
+    assert_eq!(check(&stx, 11, 11, Basic::default()), Ok(NonceCheck::Ready));
+
+    // The chain id is still checked.
+    let mut raw = raw_tx();
+    raw.action = TransactionAction::Call(NATIVE_TOKEN_ISSUE_ADDRESS);
+    let stx = sign_tx(&priv_key, raw, CHAIN_ID + 1);
+    assert!(validate(&stx, 5, 1, Basic::default()).contains("doesn't match our chain id"));
+}
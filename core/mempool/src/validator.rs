@@ -0,0 +1,137 @@
+use protocol::types::{recover_intact_pub_key, Basic, SignedTransaction, TypesError, H160, U256};
+use protocol::ProtocolResult;
+
+use common_crypto::{Crypto, Secp256k1Recoverable};
+use core_executor::{is_call_system_script, NonceCheck};
+
+use crate::{adapter::AdapterError, MemPoolError};
+
+/// The context to validate a transaction against, which is usually built from
+/// the latest block.
+pub struct ValidationContext<'a> {
+    pub chain_id:      u64,
+    /// The base fee per gas of the next block, a transaction whose gas price
+    /// can not cover it is rejected.
+    pub base_fee:      U256,
+    /// The minimal gas price accepted.
+    pub gas_floor:     U256,
+    /// The max gap between the nonce of a transaction and the nonce of its
+    /// sender, the transaction beyond it is rejected rather than queued.
+    pub max_nonce_gap: u64,
+    /// Look up the nonce and the balance of an account.
+    pub account:       &'a dyn Fn(&H160) -> Basic,
+}
+
+/// Validate a transaction before admitting it to the mempool. All the checks
+/// not depending on the other transactions in the pool are performed here,
+/// which are the signature, the chain id, the nonce, the intrinsic gas, the
+/// fee cap and the balance of the sender.
+///
+/// The nonce is classified rather than required to be the next one of the
+/// sender, so that the mempool can queue a future transaction whose nonce gap
+/// is within the tolerance. A stale transaction is returned as
+/// [`NonceCheck::Stale`] for the mempool to drop. The system script
+/// transactions are not charged by EVM, so they skip the gas floor, the base
+/// fee and the intrinsic gas checks, and they are always
+/// [`NonceCheck::Ready`] without looking up the sender.
+///
+/// The interoperation signatures are verified with the CKB-VM against the
+/// image cells, so only the secp256k1 signatures are verified here.
+pub fn validate_transaction(
+    stx: &SignedTransaction,
+    ctx: &ValidationContext,
+) -> ProtocolResult<NonceCheck> {
+    let tx = &stx.transaction;
+    let unsigned = &tx.unsigned;
+
+    if tx.signature.is_none() {
+        return Err(AdapterError::VerifySignature("missing signature".to_string()).into());
+    }
+    if stx.public.is_none() {
+        return Err(AdapterError::VerifySignature("missing public key".to_string()).into());
+    }
+    if unsigned.is_eip4844() {
+        return Err(TypesError::UnsupportedTransactionType(0x03).into());
+    }
+
+    if tx.chain_id.is_some() && tx.chain_id != Some(ctx.chain_id) {
+        return Err(MemPoolError::WrongChain(tx.hash).into());
+    }
+
+    unsigned.validate_nonce()?;
+    unsigned.check_fee_cap()?;
+
+    // The system script transactions are not charged by EVM.
+    let is_system_script = is_call_system_script(unsigned.action())?;
+    if !is_system_script {
+        let gas_price = unsigned.gas_price();
+        if !unsigned.meets_min_gas_price(ctx.gas_floor) {
+            return Err(MemPoolError::InvalidGasPrice(gas_price).into());
+        }
+        if gas_price < ctx.base_fee {
+            return Err(MemPoolError::FeeCapTooLow {
+                tx_hash: tx.hash,
+                gas_price,
+                base_fee: ctx.base_fee,
+            }
+            .into());
+        }
+
+        let gas_limit_tx = *unsigned.gas_limit();
+        let intrinsic_gas = unsigned.intrinsic_gas();
+        if gas_limit_tx < U256::from(intrinsic_gas) {
+            return Err(MemPoolError::IntrinsicGasTooLow {
+                tx_hash: tx.hash,
+                gas_limit_tx: gas_limit_tx.low_u64(),
+                intrinsic_gas,
+            }
+            .into());
+        }
+    }
+
+    if tx.signature.as_ref().unwrap().is_eth_sig() {
+        verify_secp256k1_signature(stx)?;
+    }
+
+    if is_system_script {
+        return Ok(NonceCheck::Ready);
+    }
+
+    let account = (ctx.account)(&stx.sender);
+    let nonce_check = NonceCheck::new(account.nonce, *unsigned.nonce());
+    if matches!(nonce_check, NonceCheck::Queued(gap) if gap > ctx.max_nonce_gap.into()) {
+        return Err(MemPoolError::InvalidNonce {
+            current:  account.nonce.low_u64(),
+            tx_nonce: unsigned.nonce().low_u64(),
+        }
+        .into());
+    }
+    if account.balance < unsigned.may_cost()? {
+        return Err(MemPoolError::ExceedBalance {
+            tx_hash:         tx.hash,
+            account_balance: account.balance,
+            tx_gas_limit:    *unsigned.gas_limit(),
+        }
+        .into());
+    }
+
+    Ok(nonce_check)
+}
+
+/// Verify the secp256k1 signature of a transaction with its public key. The
+/// signature and the public key must be present.
+fn verify_secp256k1_signature(stx: &SignedTransaction) -> ProtocolResult<()> {
+    Secp256k1Recoverable::verify_signature(
+        stx.transaction.signature_hash(true).as_bytes(),
+        stx.transaction
+            .signature
+            .as_ref()
+            .unwrap()
+            .as_bytes()
+            .as_ref(),
+        recover_intact_pub_key(&stx.public.unwrap()).as_bytes(),
+    )
+    .map_err(|err| AdapterError::VerifySignature(err.to_string()))?;
+
+    Ok(())
+}
@@ -349,6 +349,8 @@ where
         config.pool_size as usize,
         config.broadcast_txs_size,
         config.broadcast_txs_interval,
+        config.min_gas_price,
+        config.max_nonce_gap,
     );
     let mempool = Arc::new(
         MemPoolImpl::new(
@@ -314,7 +314,7 @@ fn generate_memory_mpt_root(metadata_0: Metadata, metadata_1: Metadata) -> Vec<u
         .unwrap();
 
     let info = HardforkInfoInner {
-        flags:        H256::from_low_u64_be(HardforkName::all().to_be()),
+        flags:        H256::from_low_u64_be((HardforkName::Andromeda as u64).to_be()),
         block_number: 0,
     };
     let hardfork = HardforkInfo { inner: vec![info] }
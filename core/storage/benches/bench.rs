@@ -113,8 +113,8 @@ fn criterion_insert_receipts(c: &mut Criterion) {
 }
 
 fn mock_signed_tx() -> SignedTransaction {
-    let mut utx = UnverifiedTransaction {
-        unsigned:  UnsignedTransaction::Eip1559(Eip1559Transaction {
+    let mut utx = UnverifiedTransaction::new(
+        UnsignedTransaction::Eip1559(Eip1559Transaction {
             nonce:                    Default::default(),
             max_priority_fee_per_gas: Default::default(),
             gas_price:                Default::default(),
@@ -124,14 +124,14 @@ fn mock_signed_tx() -> SignedTransaction {
             data:                     Bytes::new(),
             access_list:              vec![],
         }),
-        signature: Some(SignatureComponents {
+        Some(SignatureComponents {
             standard_v: 4,
             r:          Default::default(),
             s:          Default::default(),
         }),
-        chain_id:  Some(random::<u64>()),
-        hash:      Default::default(),
-    }
+        Some(random::<u64>()),
+        Default::default(),
+    )
     .calc_hash();
 
     let priv_key = Secp256k1RecoverablePrivateKey::generate(&mut OsRng);
@@ -1,6 +1,8 @@
 #![allow(clippy::diverging_sub_expression)]
 
-use axon::{async_trait, FeeAllocate, FeeInlet, KeyProvider, ValidatorExtend, H160, U256};
+use axon::{
+    async_trait, FeeAllocate, FeeInlet, KeyProvider, ProtocolResult, ValidatorExtend, H160, U256,
+};
 
 #[derive(Default, Clone, Debug)]
 struct CustomFeeAllocator;
@@ -12,7 +14,7 @@ impl FeeAllocate for CustomFeeAllocator {
         _fee_collect: U256,
         _proposer: H160,
         _validators: &[ValidatorExtend],
-    ) -> Vec<FeeInlet> {
+    ) -> ProtocolResult<Vec<FeeInlet>> {
         // Write your custom fee allocation process below.
         todo!()
     }
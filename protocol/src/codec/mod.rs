@@ -10,7 +10,10 @@ use ethers_core::utils::parse_checksummed;
 use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
 use serde::{Deserialize as _, Deserializer, Serializer};
 
-use crate::types::{Address, Bytes, DBBytes, Hex, Key256Bits, TypesError, H160, U256};
+use crate::types::{
+    default_max_call_depth, Address, Bytes, ConsensusConfig, DBBytes, Hex, Key256Bits, TypesError,
+    H160, U256,
+};
 use crate::ProtocolResult;
 
 static CHARS: &[u8] = b"0123456789abcdef";
@@ -68,6 +71,30 @@ impl Decodable for Hex {
     }
 }
 
+impl Decodable for ConsensusConfig {
+    fn decode(r: &Rlp) -> Result<Self, DecoderError> {
+        // The config encoded before `max_call_depth` was introduced has 9 items.
+        let max_call_depth = match r.item_count()? {
+            9 => default_max_call_depth(),
+            10 => r.val_at(9)?,
+            _ => return Err(DecoderError::RlpIncorrectListLen),
+        };
+
+        Ok(ConsensusConfig {
+            gas_limit: r.val_at(0)?,
+            interval: r.val_at(1)?,
+            propose_ratio: r.val_at(2)?,
+            prevote_ratio: r.val_at(3)?,
+            precommit_ratio: r.val_at(4)?,
+            brake_ratio: r.val_at(5)?,
+            tx_num_limit: r.val_at(6)?,
+            max_tx_size: r.val_at(7)?,
+            max_contract_limit: r.val_at(8)?,
+            max_call_depth,
+        })
+    }
+}
+
 pub fn hex_encode<T: AsRef<[u8]>>(src: T) -> String {
     faster_hex::hex_string(src.as_ref())
 }
@@ -208,4 +235,29 @@ mod tests {
 
         assert_eq!(origin, decode);
     }
+
+    #[test]
+    fn test_consensus_config_rlp() {
+        let config = ConsensusConfig {
+            gas_limit: 1,
+            max_contract_limit: 0x6000,
+            max_call_depth: 16,
+            ..Default::default()
+        };
+        let raw = config.rlp_bytes();
+        assert_eq!(
+            <ConsensusConfig as Decodable>::decode(&Rlp::new(raw.as_ref())).unwrap(),
+            config
+        );
+
+        // The config encoded without `max_call_depth`.
+        let mut s = RlpStream::new_list(9);
+        for _ in 0..8 {
+            s.append(&1u64);
+        }
+        s.append(&0x6000u64);
+        let decode = <ConsensusConfig as Decodable>::decode(&Rlp::new(&s.out())).unwrap();
+        assert_eq!(decode.max_contract_limit, 0x6000);
+        assert_eq!(decode.max_call_depth, default_max_call_depth());
+    }
 }
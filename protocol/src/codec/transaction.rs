@@ -2,13 +2,12 @@ use bytes::BufMut;
 use ethereum_types::BigEndianHash;
 use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
 
-use common_crypto::secp256k1_recover;
-
 use crate::types::{
     public_to_address, AccessList, AccessListItem, Bytes, BytesMut, Eip1559Transaction,
-    Eip2930Transaction, Hasher, LegacyTransaction, Public, SignatureComponents, SignedTransaction,
-    UnsignedTransaction, UnverifiedTransaction, H256, U256,
+    Eip2930Transaction, Eip4844Transaction, Hasher, LegacyTransaction, Public, SignatureComponents,
+    SignedTransaction, TypesError, UnsignedTransaction, UnverifiedTransaction, H256, U256,
 };
+use crate::ProtocolResult;
 
 pub fn truncate_slice<T>(s: &[T], n: usize) -> &[T] {
     match s.len() {
@@ -97,7 +96,7 @@ impl LegacyTransaction {
             .append(&self.data);
 
         if let Some(sig) = signature {
-            rlp.append(&sig.add_chain_replay_protection(chain_id));
+            rlp.append(&sig.full_v(chain_id));
 
             if sig.is_eth_sig() {
                 rlp.append(&U256::from(truncate_slice(&sig.r, 32)))
@@ -110,27 +109,40 @@ impl LegacyTransaction {
         }
     }
 
-    fn rlp_decode(r: &Rlp) -> Result<UnverifiedTransaction, DecoderError> {
-        if r.item_count()? != 9 {
-            return Err(DecoderError::RlpIncorrectListLen);
-        }
-
-        let tx = LegacyTransaction {
+    fn rlp_decode_fields(r: &Rlp) -> Result<Self, DecoderError> {
+        Ok(LegacyTransaction {
             nonce:     r.val_at(0)?,
             gas_price: r.val_at(1)?,
             gas_limit: r.val_at(2)?,
             action:    r.val_at(3)?,
             value:     r.val_at(4)?,
             data:      r.val_at(5)?,
-        };
+        })
+    }
+
+    fn rlp_decode_unsigned(r: &Rlp) -> Result<Self, DecoderError> {
+        let item_count = r.item_count()?;
+        if item_count != 6 && item_count != 9 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        Self::rlp_decode_fields(r)
+    }
+
+    fn rlp_decode(r: &Rlp) -> Result<UnverifiedTransaction, DecoderError> {
+        if r.item_count()? != 9 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        let tx = Self::rlp_decode_fields(r)?;
         let v: u64 = r.val_at(6)?;
 
-        Ok(UnverifiedTransaction {
-            unsigned:  UnsignedTransaction::Legacy(tx),
-            signature: Some(SignatureComponents::rlp_decode(r, 6, Some(v))?),
-            chain_id:  SignatureComponents::extract_chain_id(v),
-            hash:      Hasher::digest(r.as_raw()),
-        })
+        Ok(UnverifiedTransaction::new(
+            UnsignedTransaction::Legacy(tx),
+            Some(SignatureComponents::rlp_decode(r, 6, Some(v))?),
+            SignatureComponents::extract_chain_id(v),
+            Hasher::digest(r.as_raw()),
+        ))
     }
 }
 
@@ -166,44 +178,42 @@ impl Eip2930Transaction {
         }
     }
 
-    fn rlp_decode(r: &Rlp) -> Result<UnverifiedTransaction, DecoderError> {
-        if r.item_count()? != 11 {
-            return Err(DecoderError::RlpIncorrectListLen);
-        }
-
-        let id: u64 = r.val_at(0)?;
-        let tx = UnsignedTransaction::Eip2930(Eip2930Transaction {
+    fn rlp_decode_fields(r: &Rlp) -> Result<Self, DecoderError> {
+        Ok(Eip2930Transaction {
             nonce:       r.val_at(1)?,
             gas_price:   r.val_at(2)?,
             gas_limit:   r.val_at(3)?,
             action:      r.val_at(4)?,
             value:       r.val_at(5)?,
             data:        r.val_at(6)?,
-            access_list: {
-                let accl_rlp = r.at(7)?;
-                let mut access_list: AccessList = Vec::new();
-                for i in 0..accl_rlp.item_count()? {
-                    let accounts = accl_rlp.at(i)?;
-                    if accounts.item_count()? != 2 {
-                        return Err(DecoderError::Custom("Unknown access list length"));
-                    }
-
-                    access_list.push(AccessListItem {
-                        address:      accounts.val_at(0)?,
-                        storage_keys: accounts.list_at(1)?,
-                    });
-                }
-                access_list
-            },
-        });
-
-        Ok(UnverifiedTransaction {
-            hash:      Hasher::digest([&[tx.as_u8()], r.as_raw()].concat()),
-            unsigned:  tx,
-            signature: Some(SignatureComponents::rlp_decode(r, 8, None)?),
-            chain_id:  Some(id),
+            access_list: rlp_decode_access_list(&r.at(7)?)?,
         })
     }
+
+    fn rlp_decode_unsigned(r: &Rlp) -> Result<Self, DecoderError> {
+        if r.item_count()? != 8 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        Self::rlp_decode_fields(r)
+    }
+
+    fn rlp_decode(r: &Rlp) -> Result<UnverifiedTransaction, DecoderError> {
+        if r.item_count()? != 11 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        let id: u64 = r.val_at(0)?;
+        let tx = UnsignedTransaction::Eip2930(Self::rlp_decode_fields(r)?);
+        let hash = Hasher::digest([&[tx.as_u8()], r.as_raw()].concat());
+
+        Ok(UnverifiedTransaction::new(
+            tx,
+            Some(SignatureComponents::rlp_decode(r, 8, None)?),
+            Some(id),
+            hash,
+        ))
+    }
 }
 
 impl Eip1559Transaction {
@@ -239,13 +249,83 @@ impl Eip1559Transaction {
         }
     }
 
+    fn rlp_decode_fields(r: &Rlp) -> Result<Self, DecoderError> {
+        Ok(Eip1559Transaction {
+            nonce:                    r.val_at(1)?,
+            max_priority_fee_per_gas: r.val_at(2)?,
+            gas_price:                r.val_at(3)?,
+            gas_limit:                r.val_at(4)?,
+            action:                   r.val_at(5)?,
+            value:                    r.val_at(6)?,
+            data:                     r.val_at(7)?,
+            access_list:              rlp_decode_access_list(&r.at(8)?)?,
+        })
+    }
+
+    fn rlp_decode_unsigned(r: &Rlp) -> Result<Self, DecoderError> {
+        if r.item_count()? != 9 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        Self::rlp_decode_fields(r)
+    }
+
     fn rlp_decode(r: &Rlp) -> Result<UnverifiedTransaction, DecoderError> {
         if r.item_count()? != 12 {
             return Err(DecoderError::RlpIncorrectListLen);
         }
 
         let id: u64 = r.val_at(0)?;
-        let tx = UnsignedTransaction::Eip1559(Eip1559Transaction {
+        let tx = UnsignedTransaction::Eip1559(Self::rlp_decode_fields(r)?);
+        let hash = Hasher::digest([&[tx.as_u8()], r.as_raw()].concat());
+
+        Ok(UnverifiedTransaction::new(
+            tx,
+            Some(SignatureComponents::rlp_decode(r, 9, None)?),
+            Some(id),
+            hash,
+        ))
+    }
+}
+
+impl Eip4844Transaction {
+    fn rlp_encode(
+        &self,
+        rlp: &mut RlpStream,
+        chain_id: Option<u64>,
+        signature: Option<&SignatureComponents>,
+    ) {
+        let rlp_stream_len = if signature.is_some() { 14 } else { 11 };
+        rlp.begin_list(rlp_stream_len)
+            .append(&(if let Some(id) = chain_id { id } else { 0 }))
+            .append(&self.nonce)
+            .append(&self.max_priority_fee_per_gas)
+            .append(&self.gas_price)
+            .append(&self.gas_limit)
+            .append(&self.action)
+            .append(&self.value)
+            .append(&self.data);
+
+        rlp.begin_list(self.access_list.len());
+        for access in self.access_list.iter() {
+            rlp.begin_list(2);
+            rlp.append(&access.address);
+            rlp.begin_list(access.storage_keys.len());
+            for storage_key in access.storage_keys.iter() {
+                rlp.append(storage_key);
+            }
+        }
+
+        rlp.append(&self.max_fee_per_blob_gas)
+            .append_list(&self.blob_versioned_hashes);
+
+        if let Some(sig) = signature {
+            sig.rlp_append(rlp);
+        }
+    }
+
+    fn rlp_decode_fields(r: &Rlp) -> Result<Self, DecoderError> {
+        Ok(Eip4844Transaction {
             nonce:                    r.val_at(1)?,
             max_priority_fee_per_gas: r.val_at(2)?,
             gas_price:                r.val_at(3)?,
@@ -253,30 +333,73 @@ impl Eip1559Transaction {
             action:                   r.val_at(5)?,
             value:                    r.val_at(6)?,
             data:                     r.val_at(7)?,
-            access_list:              {
-                let accl_rlp = r.at(8)?;
-                let mut access_list: AccessList = Vec::new();
-                for i in 0..accl_rlp.item_count()? {
-                    let accounts = accl_rlp.at(i)?;
-                    if accounts.item_count()? != 2 {
-                        return Err(DecoderError::Custom("Unknown access list length"));
-                    }
-
-                    access_list.push(AccessListItem {
-                        address:      accounts.val_at(0)?,
-                        storage_keys: accounts.list_at(1)?,
-                    });
-                }
-                access_list
-            },
+            access_list:              rlp_decode_access_list(&r.at(8)?)?,
+            max_fee_per_blob_gas:     r.val_at(9)?,
+            blob_versioned_hashes:    r.list_at(10)?,
+        })
+    }
+
+    fn rlp_decode_unsigned(r: &Rlp) -> Result<Self, DecoderError> {
+        if r.item_count()? != 11 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        Self::rlp_decode_fields(r)
+    }
+
+    fn rlp_decode(r: &Rlp) -> Result<UnverifiedTransaction, DecoderError> {
+        if r.item_count()? != 14 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        let id: u64 = r.val_at(0)?;
+        let tx = UnsignedTransaction::Eip4844(Self::rlp_decode_fields(r)?);
+        let hash = Hasher::digest([&[tx.as_u8()], r.as_raw()].concat());
+
+        Ok(UnverifiedTransaction::new(
+            tx,
+            Some(SignatureComponents::rlp_decode(r, 11, None)?),
+            Some(id),
+            hash,
+        ))
+    }
+}
+
+fn rlp_decode_access_list(r: &Rlp) -> Result<AccessList, DecoderError> {
+    let mut access_list: AccessList = Vec::new();
+    for i in 0..r.item_count()? {
+        let accounts = r.at(i)?;
+        if accounts.item_count()? != 2 {
+            return Err(DecoderError::Custom("Unknown access list length"));
+        }
+
+        access_list.push(AccessListItem {
+            address:      accounts.val_at(0)?,
+            storage_keys: accounts.list_at(1)?,
         });
+    }
+    Ok(access_list)
+}
 
-        Ok(UnverifiedTransaction {
-            hash:      Hasher::digest([&[tx.as_u8()], r.as_raw()].concat()),
-            unsigned:  tx,
-            signature: Some(SignatureComponents::rlp_decode(r, 9, None)?),
-            chain_id:  Some(id),
-        })
+impl UnsignedTransaction {
+    /// Decode an unsigned transaction from the output of
+    /// [`UnsignedTransaction::encode`] without signature. The `type_byte` is
+    /// the [`EIP-2718`] transaction type and `raw` is the payload following
+    /// it. A legacy transaction has no type prefix, its `type_byte` is `0x00`
+    /// and `raw` is the whole RLP bytes.
+    ///
+    /// [`EIP-2718`]: https://eips.ethereum.org/EIPS/eip-2718
+    pub fn decode(type_byte: u8, raw: &[u8]) -> ProtocolResult<Self> {
+        let r = Rlp::new(raw);
+        let ret = match type_byte {
+            0x00 => LegacyTransaction::rlp_decode_unsigned(&r).map(UnsignedTransaction::Legacy),
+            0x01 => Eip2930Transaction::rlp_decode_unsigned(&r).map(UnsignedTransaction::Eip2930),
+            0x02 => Eip1559Transaction::rlp_decode_unsigned(&r).map(UnsignedTransaction::Eip1559),
+            0x03 => Eip4844Transaction::rlp_decode_unsigned(&r).map(UnsignedTransaction::Eip4844),
+            _ => return Err(TypesError::UnknownTransactionType(type_byte).into()),
+        };
+
+        ret.map_err(|e| TypesError::DecodeTransaction(e).into())
     }
 }
 
@@ -292,6 +415,9 @@ impl Encodable for UnverifiedTransaction {
             UnsignedTransaction::Eip1559(tx) => {
                 tx.rlp_encode(s, self.chain_id, self.signature.as_ref())
             }
+            UnsignedTransaction::Eip4844(tx) => {
+                tx.rlp_encode(s, self.chain_id, self.signature.as_ref())
+            }
         };
     }
 
@@ -321,6 +447,7 @@ impl Decodable for UnverifiedTransaction {
         match header {
             0x01 => Eip2930Transaction::rlp_decode(&Rlp::new(&raw[1..])),
             0x02 => Eip1559Transaction::rlp_decode(&Rlp::new(&raw[1..])),
+            0x03 => Eip4844Transaction::rlp_decode(&Rlp::new(&raw[1..])),
             _ => Err(DecoderError::Custom("Invalid transaction header")),
         }
     }
@@ -341,11 +468,9 @@ impl Decodable for SignedTransaction {
             .ok_or(DecoderError::Custom("missing signature"))?;
 
         let (public, sender_addr) = if sig.is_eth_sig() {
-            let public = Public::from_slice(
-                &secp256k1_recover(utx.signature_hash(true).as_bytes(), sig.as_bytes().as_ref())
-                    .map_err(|_| DecoderError::Custom("recover signature"))?
-                    .serialize_uncompressed()[1..65],
-            );
+            let public = utx
+                .recover_public(true)
+                .map_err(|_| DecoderError::Custom("recover signature"))?;
             (public, public_to_address(&public))
         } else {
             (
@@ -368,9 +493,10 @@ mod tests {
     use super::*;
 
     use common_crypto::secp256k1_recover;
+    use rand::random;
 
     use crate::codec::hex_decode;
-    use crate::types::{Public, SignatureS, Witness, H160, U256};
+    use crate::types::{Public, SignatureS, TransactionAction, Witness, H160, U256};
 
     #[test]
     fn test_legacy_decode() {
@@ -480,6 +606,125 @@ mod tests {
         assert!(res.check_hash().is_ok());
     }
 
+    fn random_bytes(len: usize) -> Bytes {
+        (0..len).map(|_| random::<u8>()).collect::<Vec<_>>().into()
+    }
+
+    fn random_action() -> TransactionAction {
+        if random() {
+            TransactionAction::Call(H160::random())
+        } else {
+            TransactionAction::Create
+        }
+    }
+
+    fn random_access_list() -> AccessList {
+        (0..random::<usize>() % 3)
+            .map(|_| AccessListItem {
+                address:      H160::random(),
+                storage_keys: (0..random::<usize>() % 3).map(|_| H256::random()).collect(),
+            })
+            .collect()
+    }
+
+    fn random_unsigned_txs() -> Vec<UnsignedTransaction> {
+        vec![
+            UnsignedTransaction::Legacy(LegacyTransaction {
+                nonce:     random::<u64>().into(),
+                gas_price: random::<u64>().into(),
+                gas_limit: random::<u64>().into(),
+                action:    random_action(),
+                value:     random::<u64>().into(),
+                data:      random_bytes(random::<usize>() % 64),
+            }),
+            UnsignedTransaction::Eip2930(Eip2930Transaction {
+                nonce:       random::<u64>().into(),
+                gas_price:   random::<u64>().into(),
+                gas_limit:   random::<u64>().into(),
+                action:      random_action(),
+                value:       random::<u64>().into(),
+                data:        random_bytes(random::<usize>() % 64),
+                access_list: random_access_list(),
+            }),
+            UnsignedTransaction::Eip1559(Eip1559Transaction {
+                nonce:                    random::<u64>().into(),
+                max_priority_fee_per_gas: random::<u64>().into(),
+                gas_price:                random::<u64>().into(),
+                gas_limit:                random::<u64>().into(),
+                action:                   random_action(),
+                value:                    random::<u64>().into(),
+                data:                     random_bytes(random::<usize>() % 64),
+                access_list:              random_access_list(),
+            }),
+            UnsignedTransaction::Eip4844(Eip4844Transaction {
+                nonce:                    random::<u64>().into(),
+                max_priority_fee_per_gas: random::<u64>().into(),
+                gas_price:                random::<u64>().into(),
+                gas_limit:                random::<u64>().into(),
+                action:                   random_action(),
+                value:                    random::<u64>().into(),
+                data:                     random_bytes(random::<usize>() % 64),
+                access_list:              random_access_list(),
+                max_fee_per_blob_gas:     random::<u64>().into(),
+                blob_versioned_hashes:    (0..random::<usize>() % 3)
+                    .map(|_| H256::random())
+                    .collect(),
+            }),
+        ]
+    }
+
+    fn assert_unsigned_tx_round_trip(tx: &UnsignedTransaction, chain_id: Option<u64>) {
+        let encoded = tx.encode(chain_id, None);
+        let (type_byte, raw) = if tx.is_legacy() {
+            (0x00, &encoded[..])
+        } else {
+            (encoded[0], &encoded[1..])
+        };
+
+        assert_eq!(&UnsignedTransaction::decode(type_byte, raw).unwrap(), tx);
+    }
+
+    #[test]
+    fn test_unsigned_tx_codec() {
+        for _ in 0..100 {
+            for tx in random_unsigned_txs().iter() {
+                assert_unsigned_tx_round_trip(tx, Some(random()));
+                if tx.is_legacy() {
+                    assert_unsigned_tx_round_trip(tx, None);
+                }
+            }
+        }
+
+        let encoded = random_unsigned_txs()[2].encode(Some(random()), None);
+        assert!(UnsignedTransaction::decode(0x03, &encoded[1..]).is_err());
+        assert!(UnsignedTransaction::decode(0x01, &encoded[1..]).is_err());
+    }
+
+    #[test]
+    fn test_eip4844_codec() {
+        let unsigned = random_unsigned_txs().pop().unwrap();
+        assert!(unsigned.is_eip4844());
+        assert_eq!(unsigned.type_(), 0x03);
+
+        let utx = UnverifiedTransaction::new(
+            unsigned,
+            Some(SignatureComponents {
+                r:          Bytes::from(vec![0x11; 32]),
+                s:          Bytes::from(vec![0x22; 32]),
+                standard_v: 1,
+            }),
+            Some(random()),
+            Default::default(),
+        )
+        .calc_hash();
+
+        let encoded = utx.rlp_bytes();
+        assert_eq!(encoded[0], 0x03);
+        let decoded = UnverifiedTransaction::decode(&Rlp::new(&encoded)).unwrap();
+        assert_eq!(decoded, utx);
+        assert!(decoded.check_hash().is_ok());
+    }
+
     #[test]
     fn test_legacy_encode() {
         let raw = hex_decode("f885020883011493941c85638e118b37167e9298c2268758e058ddfda08203e8a4f9846e1f00000000000000000000000000000000000000000000000000000000000000012da05595614cb1397fb947b3512af6939c1704c85b49c9ab8c16121e12073350b4ca9fd08cd623473664607cbe7d13dbb11a44f06ad8ce499e585ef91929b6b6e2e7").unwrap();
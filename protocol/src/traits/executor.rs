@@ -1,8 +1,8 @@
 pub use evm::backend::{ApplyBackend, Backend, MemoryBackend};
 
 use crate::types::{
-    Account, Bytes, ExecResp, ExecutorContext, Log, MerkleRoot, SignedTransaction, TxResp,
-    ValidatorExtend, H160, U256,
+    Account, Bytes, ExecResp, ExecutorContext, Log, MerkleRoot, SignedTransaction, SnapshotId,
+    TxResp, ValidatorExtend, H160, U256,
 };
 
 pub trait ExecutorReadOnlyAdapter: Backend {
@@ -11,6 +11,23 @@ pub trait ExecutorReadOnlyAdapter: Backend {
     fn get_ctx(&self) -> ExecutorContext;
 
     fn get_account(&self, address: &H160) -> Account;
+
+    /// Get the accounts of the addresses in the same order. The default
+    /// implementation gets them one by one, the backends that can read in
+    /// batch should override it.
+    fn get_accounts(&self, addresses: &[H160]) -> Vec<Account> {
+        addresses
+            .iter()
+            .map(|addr| self.get_account(addr))
+            .collect()
+    }
+
+    /// Get the size of the code at the address. The default implementation
+    /// loads the code, the backends that can tell the size without copying
+    /// the code should override it.
+    fn code_size(&self, address: &H160) -> usize {
+        self.code(*address).len()
+    }
 }
 
 pub trait ExecutorAdapter: ExecutorReadOnlyAdapter + ApplyBackend {
@@ -22,7 +39,27 @@ pub trait ExecutorAdapter: ExecutorReadOnlyAdapter + ApplyBackend {
 
     fn commit(&mut self) -> MerkleRoot;
 
+    /// Reset the state to a committed state root, all the uncommitted changes
+    /// and logs are discarded.
+    fn reset(&mut self, state_root: MerkleRoot);
+
+    /// Take a snapshot of the current state for speculative execution, then
+    /// the state can be rolled back to the snapshot by
+    /// [`ExecutorAdapter::revert_to`]. Nothing is committed or written to the
+    /// database by taking a snapshot.
+    fn snapshot(&mut self) -> SnapshotId;
+
+    /// Roll the state back to the snapshot, the changes since then are
+    /// discarded even if they are committed. The uncommitted logs are
+    /// discarded the same as [`ExecutorAdapter::reset`].
+    fn revert_to(&mut self, id: SnapshotId);
+
     fn take_logs(&mut self) -> Vec<Log>;
+
+    /// Take the addresses of the accounts modified by `save_account` or
+    /// `apply` since the last call, in ascending order. The accounts modified
+    /// by the changes discarded by `reset` are included as well.
+    fn take_touched_accounts(&mut self) -> Vec<H160>;
 }
 
 pub trait Executor: Send + Sync {
@@ -76,7 +113,23 @@ impl<'a> ExecutorAdapter for MemoryBackend<'a> {
         unreachable!()
     }
 
+    fn reset(&mut self, _state_root: MerkleRoot) {
+        unreachable!()
+    }
+
+    fn snapshot(&mut self) -> SnapshotId {
+        unreachable!()
+    }
+
+    fn revert_to(&mut self, _id: SnapshotId) {
+        unreachable!()
+    }
+
     fn save_account(&mut self, _address: &H160, _account: &Account) {
         unreachable!()
     }
+
+    fn take_touched_accounts(&mut self) -> Vec<H160> {
+        unreachable!()
+    }
 }
@@ -52,8 +52,8 @@ mod tests {
     };
 
     fn mock_sign_tx() -> SignedTransaction {
-        let mut utx = UnverifiedTransaction {
-            unsigned:  UnsignedTransaction::Eip1559(Eip1559Transaction {
+        let mut utx = UnverifiedTransaction::new(
+            UnsignedTransaction::Eip1559(Eip1559Transaction {
                 nonce:                    Default::default(),
                 max_priority_fee_per_gas: Default::default(),
                 gas_price:                Default::default(),
@@ -63,14 +63,14 @@ mod tests {
                 data:                     Bytes::new(),
                 access_list:              vec![],
             }),
-            signature: Some(SignatureComponents {
+            Some(SignatureComponents {
                 standard_v: 4,
                 r:          Default::default(),
                 s:          Default::default(),
             }),
-            chain_id:  Some(random::<u64>()),
-            hash:      Default::default(),
-        }
+            Some(random::<u64>()),
+            Default::default(),
+        )
         .calc_hash();
 
         let priv_key = Secp256k1RecoverablePrivateKey::generate(&mut OsRng);
@@ -387,8 +387,9 @@ impl RichBlock {
 #[cfg(test)]
 mod tests {
     use crate::types::{
-        primitive::default_max_contract_limit, Block, BlockVersion, ConsensusConfig, Header, Hex,
-        Metadata, MetadataVersion, ProposeCount, RichBlock, ValidatorExtend, H160,
+        primitive::{default_max_call_depth, default_max_contract_limit},
+        Block, BlockVersion, ConsensusConfig, Header, Hex, Metadata, MetadataVersion, ProposeCount,
+        RichBlock, ValidatorExtend, H160,
     };
     use std::{
         str::FromStr,
@@ -468,7 +469,8 @@ mod tests {
                 brake_ratio: 10,
                 tx_num_limit: 20000,
                 max_tx_size: 1024,
-                max_contract_limit: default_max_contract_limit()
+                max_contract_limit: default_max_contract_limit(),
+                max_call_depth: default_max_call_depth()
             }
         };
 
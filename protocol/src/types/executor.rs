@@ -6,22 +6,51 @@ use rlp_derive::{RlpDecodable, RlpEncodable};
 use serde::{Deserialize, Serialize};
 
 use crate::types::{
-    Bloom, ExtraData, Hash, Hasher, Header, MerkleRoot, Proposal, H160, H256, U256,
+    Bloom, Bytes, ExtraData, Hash, Hasher, Header, MerkleRoot, Proposal, H160, H256, U256,
 };
 
 use super::Hex;
 
 const BLOOM_BYTE_LENGTH: usize = 256;
+const FUNC_SELECTOR_LEN: usize = 4;
+const U256_BE_BYTES_LEN: usize = 32;
+const REVERT_MSG_LEN_OFFSET: usize = FUNC_SELECTOR_LEN + U256_BE_BYTES_LEN;
+const REVERT_EFFECT_MSG_OFFSET: usize = REVERT_MSG_LEN_OFFSET + U256_BE_BYTES_LEN;
+const EXEC_REVERT: &str = "execution reverted: ";
+/// The selector of `Error(string)`.
+const ERROR_SELECTOR: &[u8] = &[0x08, 0xc3, 0x79, 0xa0];
+/// The selector of `Panic(uint256)`.
+const PANIC_SELECTOR: &[u8] = &[0x4e, 0x48, 0x7b, 0x71];
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// The identifier of a state snapshot taken by `ExecutorAdapter::snapshot`,
+/// which is the position in the journal of the adapter's changes at the
+/// snapshot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SnapshotId(pub usize);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct ExecResp {
-    pub state_root:   MerkleRoot,
-    pub receipt_root: MerkleRoot,
-    pub gas_used:     u64,
-    pub tx_resp:      Vec<TxResp>,
+    pub state_root:          MerkleRoot,
+    pub receipt_root:        MerkleRoot,
+    pub gas_used:            u64,
+    /// The cumulative gas used in the block after each transaction, which
+    /// is the `cumulativeGasUsed` field of the Ethereum receipt.
+    pub cumulative_gas_used: Vec<u64>,
+    /// The RLP encoded receipts of the transactions, which the receipt root is
+    /// calculated from.
+    pub encoded_receipts:    Vec<Bytes>,
+    pub tx_resp:             Vec<TxResp>,
+    /// The collected fee of the block overflows and is saturated, which
+    /// indicates a bug in the fee calculation.
+    #[serde(default)]
+    pub fee_overflow:        bool,
+    /// The accounts modified in the block, including the senders, the
+    /// recipients and the validators credited with the fee.
+    #[serde(default)]
+    pub touched_accounts:    Vec<H160>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct TxResp {
     pub exit_reason:  ExitReason,
     pub ret:          Vec<u8>,
@@ -48,7 +77,113 @@ impl Default for TxResp {
     }
 }
 
-#[derive(RlpEncodable, RlpDecodable, Default, Clone, Debug, PartialEq, Eq)]
+impl TxResp {
+    pub fn is_success(&self) -> bool {
+        self.exit_reason.is_succeed()
+    }
+
+    /// The decoded revert message, `None` is returned if the transaction is
+    /// not reverted.
+    pub fn revert_reason(&self) -> Option<String> {
+        match self.exit_reason {
+            ExitReason::Revert(_) => Some(decode_revert_msg(&self.ret).to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// The decoded revert data of a transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RevertReason {
+    /// Reverted by `revert(string)` or `require(bool, string)`, which is
+    /// encoded as `Error(string)`.
+    Error(String),
+    /// Reverted by a Solidity panic, which is encoded as `Panic(uint256)`.
+    Panic(U256),
+    /// The revert data is not ABI encoded with a known selector.
+    Raw(Vec<u8>),
+}
+
+impl std::fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RevertReason::Error(msg) => write!(f, "{}{}", EXEC_REVERT, msg),
+            RevertReason::Panic(code) => {
+                write!(
+                    f,
+                    "{}panic: {} ({:#x})",
+                    EXEC_REVERT,
+                    panic_reason(code),
+                    code
+                )
+            }
+            RevertReason::Raw(data) => write!(
+                f,
+                "{}{}",
+                EXEC_REVERT,
+                String::from_iter(data.iter().map(|c| *c as char))
+            ),
+        }
+    }
+}
+
+/// The description of the panic codes defined by Solidity.
+fn panic_reason(code: &U256) -> &'static str {
+    if code > &U256::from(u8::MAX) {
+        return "unknown panic code";
+    }
+
+    match code.low_u32() {
+        0x00 => "generic compiler panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to zero-initialized function",
+        _ => "unknown panic code",
+    }
+}
+
+/// Decode the revert data as `Error(string)` or `Panic(uint256)`, the data
+/// which can not be decoded is returned as [`RevertReason::Raw`].
+pub fn decode_revert_msg(input: &[u8]) -> RevertReason {
+    let raw = || RevertReason::Raw(input.to_vec());
+
+    if input.len() < FUNC_SELECTOR_LEN {
+        return raw();
+    }
+
+    match &input[..FUNC_SELECTOR_LEN] {
+        ERROR_SELECTOR if input.len() >= REVERT_EFFECT_MSG_OFFSET => {
+            let len =
+                U256::from_big_endian(&input[REVERT_MSG_LEN_OFFSET..REVERT_EFFECT_MSG_OFFSET]);
+            let end_offset = REVERT_EFFECT_MSG_OFFSET + len.min(U256::from(input.len())).as_usize();
+            if input.len() < end_offset {
+                return raw();
+            }
+
+            RevertReason::Error(String::from_iter(
+                input[REVERT_EFFECT_MSG_OFFSET..end_offset]
+                    .iter()
+                    .map(|c| *c as char),
+            ))
+        }
+        PANIC_SELECTOR if input.len() >= FUNC_SELECTOR_LEN + U256_BE_BYTES_LEN => {
+            RevertReason::Panic(U256::from_big_endian(
+                &input[FUNC_SELECTOR_LEN..FUNC_SELECTOR_LEN + U256_BE_BYTES_LEN],
+            ))
+        }
+        _ => raw(),
+    }
+}
+
+#[derive(
+    RlpEncodable, RlpDecodable, Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq,
+)]
 pub struct ExecutorContext {
     pub block_number:           U256,
     pub block_coinbase:         H160,
@@ -59,6 +194,10 @@ pub struct ExecutorContext {
     pub block_gas_limit:        U256,
     pub block_base_fee_per_gas: U256,
     pub extra_data:             Vec<ExtraData>,
+    /// The hash of the parent block, which is answered to the `BLOCKHASH`
+    /// opcode of the previous block number.
+    #[serde(default)]
+    pub parent_hash:            Hash,
 }
 
 impl From<Proposal> for ExecutorContext {
@@ -73,6 +212,7 @@ impl From<Proposal> for ExecutorContext {
             block_gas_limit:        h.gas_limit,
             block_base_fee_per_gas: h.base_fee_per_gas,
             extra_data:             h.extra_data,
+            parent_hash:            h.prev_hash,
         }
     }
 }
@@ -89,6 +229,7 @@ impl From<&Header> for ExecutorContext {
             block_gas_limit:        h.gas_limit,
             block_base_fee_per_gas: h.base_fee_per_gas,
             extra_data:             h.extra_data.clone(),
+            parent_hash:            h.prev_hash,
         }
     }
 }
@@ -128,6 +269,37 @@ where
     bloom
 }
 
+/// Filter the logs with the `eth_getLogs` semantics. A log matches if its
+/// address is one of `addresses` and its topics match `topics` position by
+/// position. An empty `addresses` matches any address. Each slot of `topics`
+/// matches any of the listed topics at that position, and a `None` or empty
+/// slot matches any topic. A log with fewer topics than `topics` never
+/// matches.
+pub fn filter_logs(logs: &[Log], addresses: &[H160], topics: &[Option<Vec<H256>>]) -> Vec<Log> {
+    logs.iter()
+        .filter(|log| log_matches(log, addresses, topics))
+        .cloned()
+        .collect()
+}
+
+fn log_matches(log: &Log, addresses: &[H160], topics: &[Option<Vec<H256>>]) -> bool {
+    if !addresses.is_empty() && !addresses.contains(&log.address) {
+        return false;
+    }
+
+    if topics.len() > log.topics.len() {
+        return false;
+    }
+
+    topics
+        .iter()
+        .zip(log.topics.iter())
+        .all(|(slot, topic)| match slot {
+            Some(candidates) if !candidates.is_empty() => candidates.contains(topic),
+            _ => true,
+        })
+}
+
 fn m3_2048(bloom: &mut Bloom, x: &[u8]) {
     let hash = Hasher::digest(x).0;
     for i in [0, 2, 4] {
@@ -135,3 +307,143 @@ fn m3_2048(bloom: &mut Bloom, x: &[u8]) {
         bloom.0[BLOOM_BYTE_LENGTH - 1 - bit / 8] |= 1 << (bit % 8);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::hex_decode;
+
+    use super::*;
+
+    #[test]
+    fn test_filter_logs() {
+        let (addr_a, addr_b) = (H160::random(), H160::random());
+        let (t0, t1, t2) = (H256::random(), H256::random(), H256::random());
+        let log = |address: H160, topics: Vec<H256>| Log {
+            address,
+            topics,
+            data: vec![],
+        };
+        let logs = vec![
+            log(addr_a, vec![t0, t1]),
+            log(addr_b, vec![t0, t2]),
+            log(addr_a, vec![t1]),
+            log(addr_b, vec![]),
+        ];
+
+        // Address only.
+        assert_eq!(filter_logs(&logs, &[], &[]), logs);
+        assert_eq!(filter_logs(&logs, &[addr_a], &[]), vec![
+            logs[0].clone(),
+            logs[2].clone()
+        ]);
+        assert_eq!(filter_logs(&logs, &[addr_a, addr_b], &[]), logs);
+        assert!(filter_logs(&logs, &[H160::random()], &[]).is_empty());
+
+        // Topics are matched by position.
+        assert_eq!(filter_logs(&logs, &[], &[Some(vec![t0])]), vec![
+            logs[0].clone(),
+            logs[1].clone()
+        ]);
+        assert_eq!(filter_logs(&logs, &[], &[Some(vec![t1])]), vec![
+            logs[2].clone()
+        ]);
+        assert_eq!(filter_logs(&logs, &[addr_b], &[Some(vec![t0])]), vec![logs
+            [1]
+        .clone()]);
+
+        // The topics in a slot are ORed.
+        assert_eq!(filter_logs(&logs, &[], &[None, Some(vec![t1, t2])]), vec![
+            logs[0].clone(),
+            logs[1].clone()
+        ]);
+
+        // A wildcard slot matches any topic, but the log must have the topic.
+        assert_eq!(filter_logs(&logs, &[], &[None]), vec![
+            logs[0].clone(),
+            logs[1].clone(),
+            logs[2].clone()
+        ]);
+        assert_eq!(filter_logs(&logs, &[], &[Some(vec![]), None]), vec![
+            logs[0].clone(),
+            logs[1].clone()
+        ]);
+        assert!(filter_logs(&logs, &[], &[None, None, None]).is_empty());
+    }
+
+    #[test]
+    fn test_decode_revert_msg() {
+        // Error("Ownable: caller is not the owner")
+        let input = hex_decode("08c379a0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000204f776e61626c653a2063616c6c6572206973206e6f7420746865206f776e6572").unwrap();
+        let reason = decode_revert_msg(&input);
+        assert_eq!(
+            reason,
+            RevertReason::Error("Ownable: caller is not the owner".to_string())
+        );
+        assert_eq!(
+            reason.to_string(),
+            "execution reverted: Ownable: caller is not the owner"
+        );
+
+        // Panic(0x11)
+        let input =
+            hex_decode("4e487b710000000000000000000000000000000000000000000000000000000000000011")
+                .unwrap();
+        let reason = decode_revert_msg(&input);
+        assert_eq!(reason, RevertReason::Panic(0x11.into()));
+        assert_eq!(
+            reason.to_string(),
+            "execution reverted: panic: arithmetic overflow or underflow (0x11)"
+        );
+
+        // Panic(0x32)
+        let input =
+            hex_decode("4e487b710000000000000000000000000000000000000000000000000000000000000032")
+                .unwrap();
+        assert_eq!(
+            decode_revert_msg(&input).to_string(),
+            "execution reverted: panic: array index out of bounds (0x32)"
+        );
+
+        // Raw revert data
+        assert_eq!(
+            decode_revert_msg(b"oops"),
+            RevertReason::Raw(b"oops".to_vec())
+        );
+        assert_eq!(
+            decode_revert_msg(b"oops").to_string(),
+            "execution reverted: oops"
+        );
+        assert_eq!(decode_revert_msg(&[]).to_string(), "execution reverted: ");
+
+        // The length of the message exceeds the revert data.
+        let mut input = hex_decode("08c379a0").unwrap();
+        input.extend_from_slice(&[0xff; 64]);
+        assert_eq!(decode_revert_msg(&input), RevertReason::Raw(input.clone()));
+    }
+
+    #[test]
+    fn test_tx_resp_revert_reason() {
+        let resp = TxResp::default();
+        assert!(resp.is_success());
+        assert_eq!(resp.revert_reason(), None);
+
+        // Error("Ownable: caller is not the owner")
+        let resp = TxResp {
+            exit_reason: ExitReason::Revert(ExitRevert::Reverted),
+            ret: hex_decode("08c379a0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000204f776e61626c653a2063616c6c6572206973206e6f7420746865206f776e6572").unwrap(),
+            ..Default::default()
+        };
+        assert!(!resp.is_success());
+        assert_eq!(
+            resp.revert_reason().unwrap(),
+            "execution reverted: Ownable: caller is not the owner"
+        );
+
+        let resp = TxResp {
+            exit_reason: ExitReason::Error(ExitError::OutOfGas),
+            ..Default::default()
+        };
+        assert!(!resp.is_success());
+        assert_eq!(resp.revert_reason(), None);
+    }
+}
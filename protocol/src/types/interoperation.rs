@@ -59,6 +59,19 @@ pub struct CellDepWithPubKey {
     pub pub_key:  Bytes,
 }
 
+/// The cell deps of the multi-cell-dep interoperation mode, each of which
+/// verifies the signature with its own public key.
+#[derive(RlpEncodable, RlpDecodable, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CellDepsWithPubKey {
+    pub cell_deps: Vec<CellDepWithPubKey>,
+}
+
+impl CellDepsWithPubKey {
+    pub fn new(cell_deps: Vec<CellDepWithPubKey>) -> Self {
+        CellDepsWithPubKey { cell_deps }
+    }
+}
+
 #[derive(
     RlpEncodable, RlpDecodable, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Display,
 )]
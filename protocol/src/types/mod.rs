@@ -6,8 +6,8 @@ pub use bytes::{Buf, BufMut, Bytes, BytesMut};
 pub use ckb_client::*;
 pub use evm::{backend::*, ExitError, ExitRevert, ExitSucceed};
 pub use executor::{
-    logs_bloom, AccessList, AccessListItem, Account, Config, EthAccountProof, EthStorageProof,
-    ExecResp, ExecutorContext, ExitReason, HasherKeccak, TxResp,
+    filter_logs, logs_bloom, AccessList, AccessListItem, Account, Config, EthAccountProof,
+    EthStorageProof, ExecResp, ExecutorContext, ExitReason, HasherKeccak, SnapshotId, TxResp,
 };
 pub use interoperation::*;
 pub use primitive::*;
@@ -73,8 +73,11 @@ pub enum TypesError {
     #[display(fmt = "Signature R is empty")]
     SignatureRIsEmpty,
 
-    #[display(fmt = "Invalid signature R type")]
-    InvalidSignatureRType,
+    #[display(fmt = "Unsupported interoperation mode {:#04x}", _0)]
+    UnsupportedInteropMode(u8),
+
+    #[display(fmt = "Interoperation signature has no cell dep")]
+    EmptyInteropCellDeps,
 
     #[display(fmt = "Invalid address source type")]
     InvalidAddressSourceType,
@@ -87,6 +90,46 @@ pub enum TypesError {
 
     #[display(fmt = "Decode interoperation signature R error {:?}", _0)]
     DecodeInteroperationSigR(rlp::DecoderError),
+
+    #[display(fmt = "Receipt payload is truncated")]
+    TruncatedReceipt,
+
+    #[display(fmt = "Invalid receipt type {:#04x}", _0)]
+    InvalidReceiptType(u8),
+
+    #[display(fmt = "Decode receipt error {:?}", _0)]
+    DecodeReceipt(rlp::DecoderError),
+
+    #[display(fmt = "Unknown transaction type {:#04x}", _0)]
+    UnknownTransactionType(u8),
+
+    #[display(fmt = "Unsupported transaction type {:#04x}", _0)]
+    UnsupportedTransactionType(u8),
+
+    #[display(fmt = "Decode transaction error {:?}", _0)]
+    DecodeTransaction(rlp::DecoderError),
+
+    #[display(fmt = "Chain id mismatch, expect {}, actual {}", expect, actual)]
+    ChainIdMismatch { expect: u64, actual: u64 },
+
+    #[display(fmt = "Transaction cost overflow")]
+    CostOverflow,
+
+    #[display(
+        fmt = "Max fee per gas {} is less than max priority fee per gas {}",
+        max_fee_per_gas,
+        max_priority_fee_per_gas
+    )]
+    InvalidFeeCap {
+        max_fee_per_gas:          U256,
+        max_priority_fee_per_gas: U256,
+    },
+
+    #[display(fmt = "Gas limit is zero")]
+    ZeroGasLimit,
+
+    #[display(fmt = "Nonce {} is not less than the max nonce 2^64 - 1", _0)]
+    NonceTooHigh(U256),
 }
 
 impl Error for TypesError {}
@@ -388,13 +388,73 @@ impl From<ConsensusConfigV0> for ConsensusConfig {
             tx_num_limit:       value.tx_num_limit,
             max_tx_size:        value.max_tx_size,
             max_contract_limit: default_max_contract_limit(),
+            max_call_depth:     default_max_call_depth(),
         }
     }
 }
 
+/// The consensus config stored since the `Andromeda` hardfork until the
+/// `Antlia` hardfork, which is before `max_call_depth` was introduced.
 #[derive(
     RlpEncodable, RlpDecodable, Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq,
 )]
+pub struct ConsensusConfigV1 {
+    #[cfg_attr(feature = "hex-serialize", serde(serialize_with = "serialize_uint"))]
+    pub gas_limit:          u64,
+    #[cfg_attr(feature = "hex-serialize", serde(serialize_with = "serialize_uint"))]
+    pub interval:           u64,
+    #[cfg_attr(feature = "hex-serialize", serde(serialize_with = "serialize_uint"))]
+    pub propose_ratio:      u64,
+    #[cfg_attr(feature = "hex-serialize", serde(serialize_with = "serialize_uint"))]
+    pub prevote_ratio:      u64,
+    #[cfg_attr(feature = "hex-serialize", serde(serialize_with = "serialize_uint"))]
+    pub precommit_ratio:    u64,
+    #[cfg_attr(feature = "hex-serialize", serde(serialize_with = "serialize_uint"))]
+    pub brake_ratio:        u64,
+    #[cfg_attr(feature = "hex-serialize", serde(serialize_with = "serialize_uint"))]
+    pub tx_num_limit:       u64,
+    #[cfg_attr(feature = "hex-serialize", serde(serialize_with = "serialize_uint"))]
+    pub max_tx_size:        u64,
+    #[cfg_attr(feature = "hex-serialize", serde(serialize_with = "serialize_uint"))]
+    pub max_contract_limit: u64,
+}
+
+impl From<ConsensusConfigV1> for ConsensusConfig {
+    fn from(value: ConsensusConfigV1) -> Self {
+        ConsensusConfig {
+            gas_limit:          value.gas_limit,
+            interval:           value.interval,
+            precommit_ratio:    value.precommit_ratio,
+            propose_ratio:      value.propose_ratio,
+            prevote_ratio:      value.prevote_ratio,
+            brake_ratio:        value.brake_ratio,
+            tx_num_limit:       value.tx_num_limit,
+            max_tx_size:        value.max_tx_size,
+            max_contract_limit: value.max_contract_limit,
+            max_call_depth:     default_max_call_depth(),
+        }
+    }
+}
+
+impl From<ConsensusConfig> for ConsensusConfigV1 {
+    fn from(value: ConsensusConfig) -> Self {
+        ConsensusConfigV1 {
+            gas_limit:          value.gas_limit,
+            interval:           value.interval,
+            precommit_ratio:    value.precommit_ratio,
+            propose_ratio:      value.propose_ratio,
+            prevote_ratio:      value.prevote_ratio,
+            brake_ratio:        value.brake_ratio,
+            tx_num_limit:       value.tx_num_limit,
+            max_tx_size:        value.max_tx_size,
+            max_contract_limit: value.max_contract_limit,
+        }
+    }
+}
+
+/// The RLP decoding is implemented in `codec` to accept the config encoded
+/// before `max_call_depth` was introduced.
+#[derive(RlpEncodable, Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
 pub struct ConsensusConfig {
     #[cfg_attr(feature = "hex-serialize", serde(serialize_with = "serialize_uint"))]
     pub gas_limit:          u64,
@@ -415,6 +475,10 @@ pub struct ConsensusConfig {
     #[cfg_attr(feature = "hex-serialize", serde(serialize_with = "serialize_uint"))]
     #[serde(default = "default_max_contract_limit")]
     pub max_contract_limit: u64,
+    /// The max depth of the call stack, `0` means the default depth of 1024.
+    #[cfg_attr(feature = "hex-serialize", serde(serialize_with = "serialize_uint"))]
+    #[serde(default = "default_max_call_depth")]
+    pub max_call_depth:     u64,
 }
 impl From<ConsensusConfig> for ConsensusConfigV0 {
     fn from(value: ConsensusConfig) -> Self {
@@ -435,6 +499,10 @@ pub fn default_max_contract_limit() -> u64 {
     0x6000
 }
 
+pub fn default_max_call_depth() -> u64 {
+    1024
+}
+
 impl From<Metadata> for DurationConfig {
     fn from(m: Metadata) -> Self {
         DurationConfig {
@@ -580,6 +648,24 @@ impl HardforkInfo {
             self.inner.push(other);
         }
     }
+
+    /// The hardfork flags enabled at the given block number.
+    pub fn flags_at(&self, block_number: BlockNumber) -> H256 {
+        self.inner
+            .iter()
+            .rev()
+            .find(|i| i.block_number <= block_number)
+            .map(|i| i.flags)
+            .unwrap_or_default()
+    }
+
+    /// The first block number at which all the given flags are enabled.
+    pub fn activation_number(&self, flags: H256) -> Option<BlockNumber> {
+        self.inner
+            .iter()
+            .find(|i| i.flags & flags == flags)
+            .map(|i| i.block_number)
+    }
 }
 
 #[derive(RlpEncodable, RlpDecodable, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -814,4 +900,32 @@ mod tests {
         });
         assert_eq!(a.inner.len(), 2);
     }
+
+    #[test]
+    fn test_hardfork_info_lookup() {
+        let flags = |f: u8| {
+            let mut a = [0; 32];
+            a[0] = f;
+            H256::from(a)
+        };
+        let mut a = HardforkInfo::default();
+        a.push(HardforkInfoInner {
+            block_number: 10,
+            flags:        flags(0b1),
+        });
+        a.push(HardforkInfoInner {
+            block_number: 20,
+            flags:        flags(0b10),
+        });
+
+        assert_eq!(a.flags_at(9), H256::zero());
+        assert_eq!(a.flags_at(10), flags(0b1));
+        assert_eq!(a.flags_at(19), flags(0b1));
+        assert_eq!(a.flags_at(20), flags(0b11));
+        assert_eq!(a.flags_at(u64::MAX), flags(0b11));
+
+        assert_eq!(a.activation_number(flags(0b1)), Some(10));
+        assert_eq!(a.activation_number(flags(0b10)), Some(20));
+        assert_eq!(a.activation_number(flags(0b100)), None);
+    }
 }
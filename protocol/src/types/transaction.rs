@@ -2,25 +2,33 @@ pub use ethereum::{
     AccessList, AccessListItem, EIP1559TransactionMessage as TransactionMessage, TransactionAction,
     TransactionRecoveryId, TransactionSignature,
 };
-use rlp::{Encodable, RlpStream};
+use std::collections::{HashMap, HashSet};
+
+use rlp::{DecoderError, Encodable, Rlp, RlpStream};
 use serde::{Deserialize, Serialize};
 
 use common_crypto::secp256k1_recover;
 
 use crate::types::{
-    Bloom, Bytes, BytesMut, CellDepWithPubKey, ExitReason, Hash, Hasher, Public, TxResp,
-    TypesError, H160, H256, H520, U256,
+    Bloom, BufMut, Bytes, BytesMut, CellDepWithPubKey, CellDepsWithPubKey, ExitReason, Hash,
+    Hasher, Log, Public, TxResp, TypesError, H160, H256, H520, U256,
 };
 use crate::ProtocolResult;
 
 pub const MAX_PRIORITY_FEE_PER_GAS: u64 = 1_337;
 pub const MIN_TRANSACTION_GAS_LIMIT: u64 = 21_000;
+const TRANSACTION_CREATE_GAS: u64 = 32_000;
+const TRANSACTION_ZERO_DATA_GAS: u64 = 4;
+const TRANSACTION_NON_ZERO_DATA_GAS: u64 = 16;
+const ACCESS_LIST_ADDRESS_GAS: u64 = 2_400;
+const ACCESS_LIST_STORAGE_KEY_GAS: u64 = 1_900;
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
 pub enum UnsignedTransaction {
     Legacy(LegacyTransaction),
     Eip2930(Eip2930Transaction),
     Eip1559(Eip1559Transaction),
+    Eip4844(Eip4844Transaction),
 }
 
 impl UnsignedTransaction {
@@ -29,17 +37,17 @@ impl UnsignedTransaction {
             UnsignedTransaction::Legacy(_) => 0x00,
             UnsignedTransaction::Eip2930(_) => 0x01,
             UnsignedTransaction::Eip1559(_) => 0x02,
+            UnsignedTransaction::Eip4844(_) => 0x03,
         }
     }
 
-    pub fn may_cost(&self) -> U256 {
-        if let Some(res) = self.gas_price().checked_mul(*self.gas_limit()) {
-            return res
-                .checked_add(*self.value())
-                .unwrap_or_else(U256::max_value);
-        }
-
-        U256::max_value()
+    /// The max cost of the transaction, which is `gas_price * gas_limit +
+    /// value`. Return [`TypesError::CostOverflow`] if it overflows.
+    pub fn may_cost(&self) -> ProtocolResult<U256> {
+        self.gas_price()
+            .checked_mul(*self.gas_limit())
+            .and_then(|prepay| prepay.checked_add(*self.value()))
+            .ok_or_else(|| TypesError::CostOverflow.into())
     }
 
     pub fn is_legacy(&self) -> bool {
@@ -50,11 +58,76 @@ impl UnsignedTransaction {
         matches!(self, UnsignedTransaction::Eip1559(_))
     }
 
+    /// The [`EIP-4844`](https://eips.ethereum.org/EIPS/eip-4844) blob
+    /// transactions can be decoded but are not supported to be executed.
+    pub fn is_eip4844(&self) -> bool {
+        matches!(self, UnsignedTransaction::Eip4844(_))
+    }
+
+    /// Calculate the intrinsic gas of the transaction, which is the gas charged
+    /// before executing any code. It consists of the base cost, the cost of
+    /// the data, the creation surcharge and the [`EIP-2930`] access list
+    /// cost.
+    ///
+    /// [`EIP-2930`]: https://eips.ethereum.org/EIPS/eip-2930
+    pub fn intrinsic_gas(&self) -> u64 {
+        let data_gas = self
+            .data()
+            .iter()
+            .map(|b| {
+                if *b == 0 {
+                    TRANSACTION_ZERO_DATA_GAS
+                } else {
+                    TRANSACTION_NON_ZERO_DATA_GAS
+                }
+            })
+            .sum::<u64>();
+        let create_gas = if self.is_create() {
+            TRANSACTION_CREATE_GAS
+        } else {
+            0
+        };
+
+        MIN_TRANSACTION_GAS_LIMIT + data_gas + create_gas + self.access_list_gas()
+    }
+
+    /// The numbers of the addresses and the storage keys in the access list.
+    /// The duplicated entries are counted as they are charged.
+    pub fn access_list_len(&self) -> (usize, usize) {
+        self.access_list()
+            .iter()
+            .fold((0, 0), |(addresses, keys), item| {
+                (addresses + 1, keys + item.storage_keys.len())
+            })
+    }
+
+    /// The intrinsic gas charged for the access list by [`EIP-2930`], which
+    /// is included in [`intrinsic_gas`](Self::intrinsic_gas).
+    ///
+    /// [`EIP-2930`]: https://eips.ethereum.org/EIPS/eip-2930
+    pub fn access_list_gas(&self) -> u64 {
+        let (addresses, keys) = self.access_list_len();
+        addresses as u64 * ACCESS_LIST_ADDRESS_GAS + keys as u64 * ACCESS_LIST_STORAGE_KEY_GAS
+    }
+
+    pub fn is_create(&self) -> bool {
+        matches!(self.action(), TransactionAction::Create)
+    }
+
+    /// Return the callee address if the transaction is a call.
+    pub fn is_call(&self) -> Option<H160> {
+        match self.action() {
+            TransactionAction::Call(addr) => Some(*addr),
+            TransactionAction::Create => None,
+        }
+    }
+
     pub fn data(&self) -> &[u8] {
         match self {
             UnsignedTransaction::Legacy(tx) => tx.data.as_ref(),
             UnsignedTransaction::Eip2930(tx) => tx.data.as_ref(),
             UnsignedTransaction::Eip1559(tx) => tx.data.as_ref(),
+            UnsignedTransaction::Eip4844(tx) => tx.data.as_ref(),
         }
     }
 
@@ -63,6 +136,7 @@ impl UnsignedTransaction {
             UnsignedTransaction::Legacy(tx) => tx.action = action,
             UnsignedTransaction::Eip2930(tx) => tx.action = action,
             UnsignedTransaction::Eip1559(tx) => tx.action = action,
+            UnsignedTransaction::Eip4844(tx) => tx.action = action,
         }
     }
 
@@ -71,6 +145,7 @@ impl UnsignedTransaction {
             UnsignedTransaction::Legacy(tx) => tx.data = data,
             UnsignedTransaction::Eip2930(tx) => tx.data = data,
             UnsignedTransaction::Eip1559(tx) => tx.data = data,
+            UnsignedTransaction::Eip4844(tx) => tx.data = data,
         }
     }
 
@@ -79,7 +154,70 @@ impl UnsignedTransaction {
             UnsignedTransaction::Legacy(tx) => tx.gas_price,
             UnsignedTransaction::Eip2930(tx) => tx.gas_price,
             UnsignedTransaction::Eip1559(tx) => tx.gas_price.max(tx.max_priority_fee_per_gas),
+            UnsignedTransaction::Eip4844(tx) => tx.gas_price.max(tx.max_priority_fee_per_gas),
+        }
+    }
+
+    /// Calculate the effective gas price under the given block base fee. For
+    /// an EIP-1559 transaction it is `min(max_fee_per_gas, base_fee +
+    /// max_priority_fee_per_gas)`, the same for an EIP-4844 transaction.
+    /// Other transactions pay the gas price. `None` is returned if the max fee
+    /// per gas or the gas price can not cover the base fee.
+    pub fn effective_gas_price(&self, base_fee: &U256) -> Option<U256> {
+        let (max_fee_per_gas, max_priority_fee_per_gas) = match self {
+            UnsignedTransaction::Legacy(tx) => (tx.gas_price, tx.gas_price),
+            UnsignedTransaction::Eip2930(tx) => (tx.gas_price, tx.gas_price),
+            UnsignedTransaction::Eip1559(tx) => (tx.gas_price, tx.max_priority_fee_per_gas),
+            UnsignedTransaction::Eip4844(tx) => (tx.gas_price, tx.max_priority_fee_per_gas),
+        };
+
+        if max_fee_per_gas < *base_fee {
+            return None;
+        }
+
+        Some(
+            base_fee
+                .saturating_add(max_priority_fee_per_gas)
+                .min(max_fee_per_gas),
+        )
+    }
+
+    /// Whether the gas price of the transaction reaches the floor. The gas
+    /// price of an EIP-1559 transaction is the same as [`Self::gas_price`].
+    pub fn meets_min_gas_price(&self, floor: U256) -> bool {
+        self.gas_price() >= floor
+    }
+
+    /// Check the max fee per gas of an EIP-1559 or EIP-4844 transaction is not
+    /// less than its max priority fee per gas.
+    pub fn check_fee_cap(&self) -> ProtocolResult<()> {
+        let (max_fee_per_gas, max_priority_fee_per_gas) = match self {
+            UnsignedTransaction::Legacy(_) | UnsignedTransaction::Eip2930(_) => return Ok(()),
+            UnsignedTransaction::Eip1559(tx) => (tx.gas_price, tx.max_priority_fee_per_gas),
+            UnsignedTransaction::Eip4844(tx) => (tx.gas_price, tx.max_priority_fee_per_gas),
+        };
+
+        if max_fee_per_gas < max_priority_fee_per_gas {
+            return Err(TypesError::InvalidFeeCap {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            }
+            .into());
         }
+
+        Ok(())
+    }
+
+    /// Check the nonce is less than `2^64 - 1` as
+    /// [`EIP-2681`](https://eips.ethereum.org/EIPS/eip-2681) requires, so the
+    /// nonce of the sender never overflows after executing the transaction.
+    pub fn validate_nonce(&self) -> ProtocolResult<()> {
+        let nonce = *self.nonce();
+        if nonce >= U256::from(u64::MAX) {
+            return Err(TypesError::NonceTooHigh(nonce).into());
+        }
+
+        Ok(())
     }
 
     pub fn max_priority_fee_per_gas(&self) -> &U256 {
@@ -87,6 +225,7 @@ impl UnsignedTransaction {
             UnsignedTransaction::Legacy(tx) => &tx.gas_price,
             UnsignedTransaction::Eip2930(tx) => &tx.gas_price,
             UnsignedTransaction::Eip1559(tx) => &tx.max_priority_fee_per_gas,
+            UnsignedTransaction::Eip4844(tx) => &tx.max_priority_fee_per_gas,
         }
     }
 
@@ -102,6 +241,7 @@ impl UnsignedTransaction {
             UnsignedTransaction::Legacy(_) => unreachable!(),
             UnsignedTransaction::Eip2930(_) => 1u8,
             UnsignedTransaction::Eip1559(_) => 2u8,
+            UnsignedTransaction::Eip4844(_) => 3u8,
         }
     }
 
@@ -110,13 +250,39 @@ impl UnsignedTransaction {
         chain_id: Option<u64>,
         signature: Option<SignatureComponents>,
     ) -> BytesMut {
-        UnverifiedTransaction {
-            unsigned: self.clone(),
-            chain_id,
-            signature,
-            hash: Default::default(),
-        }
-        .rlp_bytes()
+        UnverifiedTransaction::new(self.clone(), signature, chain_id, Default::default())
+            .rlp_bytes()
+    }
+
+    /// Sign the transaction with the private key and recover the sender from
+    /// the signature, which is used to build the transactions in tests and
+    /// tools.
+    #[cfg(any(test, feature = "tooling"))]
+    pub fn sign(
+        self,
+        priv_key: &common_crypto::Secp256k1RecoverablePrivateKey,
+        chain_id: Option<u64>,
+    ) -> ProtocolResult<SignedTransaction> {
+        use common_crypto::{Crypto, PrivateKey, Secp256k1Recoverable, Signature};
+
+        let mut utx = UnverifiedTransaction::new(self, None, chain_id, Default::default());
+        let signature = Secp256k1Recoverable::sign_message(
+            utx.signature_hash(true).as_bytes(),
+            &priv_key.to_bytes(),
+        )
+        .map_err(TypesError::Crypto)?
+        .to_bytes();
+        utx.signature = Some(signature.into());
+
+        SignedTransaction::from_unverified(utx)
+    }
+
+    /// A digest of the unsigned fields and the chain id, which is the same
+    /// for any signature over the payload. This helps to deduplicate the
+    /// transactions of the same content. It is NOT the transaction hash on
+    /// chain, use [`UnverifiedTransaction::get_hash`] for that.
+    pub fn content_hash(&self, chain_id: Option<u64>) -> H256 {
+        Hasher::digest(self.encode(chain_id, None))
     }
 
     pub fn to(&self) -> Option<H160> {
@@ -124,6 +290,7 @@ impl UnsignedTransaction {
             UnsignedTransaction::Legacy(tx) => tx.get_to(),
             UnsignedTransaction::Eip2930(tx) => tx.get_to(),
             UnsignedTransaction::Eip1559(tx) => tx.get_to(),
+            UnsignedTransaction::Eip4844(tx) => tx.get_to(),
         }
     }
 
@@ -132,6 +299,7 @@ impl UnsignedTransaction {
             UnsignedTransaction::Legacy(tx) => &tx.value,
             UnsignedTransaction::Eip2930(tx) => &tx.value,
             UnsignedTransaction::Eip1559(tx) => &tx.value,
+            UnsignedTransaction::Eip4844(tx) => &tx.value,
         }
     }
 
@@ -140,6 +308,7 @@ impl UnsignedTransaction {
             UnsignedTransaction::Legacy(tx) => &tx.gas_limit,
             UnsignedTransaction::Eip2930(tx) => &tx.gas_limit,
             UnsignedTransaction::Eip1559(tx) => &tx.gas_limit,
+            UnsignedTransaction::Eip4844(tx) => &tx.gas_limit,
         }
     }
 
@@ -148,6 +317,7 @@ impl UnsignedTransaction {
             UnsignedTransaction::Legacy(tx) => &tx.nonce,
             UnsignedTransaction::Eip2930(tx) => &tx.nonce,
             UnsignedTransaction::Eip1559(tx) => &tx.nonce,
+            UnsignedTransaction::Eip4844(tx) => &tx.nonce,
         }
     }
 
@@ -156,6 +326,7 @@ impl UnsignedTransaction {
             UnsignedTransaction::Legacy(tx) => &tx.action,
             UnsignedTransaction::Eip2930(tx) => &tx.action,
             UnsignedTransaction::Eip1559(tx) => &tx.action,
+            UnsignedTransaction::Eip4844(tx) => &tx.action,
         }
     }
 
@@ -164,6 +335,7 @@ impl UnsignedTransaction {
             UnsignedTransaction::Legacy(_) => Vec::new(),
             UnsignedTransaction::Eip2930(tx) => tx.access_list.clone(),
             UnsignedTransaction::Eip1559(tx) => tx.access_list.clone(),
+            UnsignedTransaction::Eip4844(tx) => tx.access_list.clone(),
         }
     }
 }
@@ -229,6 +401,32 @@ impl std::hash::Hash for Eip2930Transaction {
 }
 
 impl Eip2930Transaction {
+    /// Build a transaction with the access list normalized by
+    /// [`normalize_access_list`]. Return an error if the gas limit is zero.
+    pub fn new(
+        nonce: U256,
+        gas_price: U256,
+        gas_limit: U256,
+        action: TransactionAction,
+        value: U256,
+        data: Bytes,
+        access_list: AccessList,
+    ) -> ProtocolResult<Self> {
+        if gas_limit.is_zero() {
+            return Err(TypesError::ZeroGasLimit.into());
+        }
+
+        Ok(Eip2930Transaction {
+            nonce,
+            gas_price,
+            gas_limit,
+            action,
+            value,
+            data,
+            access_list: normalize_access_list(access_list),
+        })
+    }
+
     pub fn get_to(&self) -> Option<H160> {
         match self.action {
             TransactionAction::Call(to) => Some(to),
@@ -268,6 +466,92 @@ impl std::hash::Hash for Eip1559Transaction {
 }
 
 impl Eip1559Transaction {
+    /// Build a transaction with the access list normalized by
+    /// [`normalize_access_list`]. Return an error if the gas limit is zero or
+    /// the max fee per gas, which is the `gas_price`, is less than the max
+    /// priority fee per gas.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        nonce: U256,
+        max_priority_fee_per_gas: U256,
+        gas_price: U256,
+        gas_limit: U256,
+        action: TransactionAction,
+        value: U256,
+        data: Bytes,
+        access_list: AccessList,
+    ) -> ProtocolResult<Self> {
+        if gas_limit.is_zero() {
+            return Err(TypesError::ZeroGasLimit.into());
+        }
+
+        if gas_price < max_priority_fee_per_gas {
+            return Err(TypesError::InvalidFeeCap {
+                max_fee_per_gas: gas_price,
+                max_priority_fee_per_gas,
+            }
+            .into());
+        }
+
+        Ok(Eip1559Transaction {
+            nonce,
+            max_priority_fee_per_gas,
+            gas_price,
+            gas_limit,
+            action,
+            value,
+            data,
+            access_list: normalize_access_list(access_list),
+        })
+    }
+
+    pub fn get_to(&self) -> Option<H160> {
+        match self.action {
+            TransactionAction::Call(to) => Some(to),
+            TransactionAction::Create => None,
+        }
+    }
+}
+
+/// The [`EIP-4844`](https://eips.ethereum.org/EIPS/eip-4844) blob transaction.
+/// It is only decoded so that the tooling does not fail on it, the executor
+/// rejects it since the blobs are not supported.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Eip4844Transaction {
+    pub nonce:                    U256,
+    pub max_priority_fee_per_gas: U256,
+    pub gas_price:                U256,
+    pub gas_limit:                U256,
+    pub action:                   TransactionAction,
+    pub value:                    U256,
+    pub data:                     Bytes,
+    pub access_list:              AccessList,
+    pub max_fee_per_blob_gas:     U256,
+    pub blob_versioned_hashes:    Vec<H256>,
+}
+
+impl std::hash::Hash for Eip4844Transaction {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.nonce.hash(state);
+        self.max_priority_fee_per_gas.hash(state);
+        self.gas_price.hash(state);
+        self.gas_limit.hash(state);
+        self.value.hash(state);
+        self.data.hash(state);
+        if let TransactionAction::Call(addr) = self.action {
+            addr.hash(state);
+        }
+
+        for access in self.access_list.iter() {
+            access.address.hash(state);
+        }
+
+        self.max_fee_per_blob_gas.hash(state);
+        self.blob_versioned_hashes.hash(state);
+    }
+}
+
+impl Eip4844Transaction {
     pub fn get_to(&self) -> Option<H160> {
         match self.action {
             TransactionAction::Call(to) => Some(to),
@@ -285,6 +569,20 @@ pub struct UnverifiedTransaction {
 }
 
 impl UnverifiedTransaction {
+    pub fn new(
+        unsigned: UnsignedTransaction,
+        signature: Option<SignatureComponents>,
+        chain_id: Option<u64>,
+        hash: H256,
+    ) -> Self {
+        UnverifiedTransaction {
+            unsigned,
+            signature,
+            chain_id,
+            hash,
+        }
+    }
+
     pub fn calc_hash(mut self) -> Self {
         debug_assert!(self.signature.is_some());
         let hash = self.get_hash();
@@ -309,17 +607,21 @@ impl UnverifiedTransaction {
         Ok(())
     }
 
-    /// The `with_chain_id` argument is only used for tests
+    /// Calculate the hash signed by the sender. A legacy transaction signed
+    /// before [`EIP-155`](https://eips.ethereum.org/EIPS/eip-155) does not
+    /// include the chain id, so its hash should be calculated with
+    /// `with_chain_id` set to `false` to recover the sender correctly. The
+    /// EIP-2930, EIP-1559 and EIP-4844 transactions always sign the chain id
+    /// and ignore the flag.
     pub fn signature_hash(&self, with_chain_id: bool) -> Hash {
-        if !with_chain_id {
-            if let Some(legacy_tx) = self.unsigned.get_legacy() {
+        match &self.unsigned {
+            UnsignedTransaction::Legacy(tx) if !with_chain_id => {
                 let mut s = RlpStream::new();
-                legacy_tx.rlp_encode(&mut s, None, None);
-                return Hasher::digest(s.out());
+                tx.rlp_encode(&mut s, None, None);
+                Hasher::digest(s.out())
             }
+            _ => Hasher::digest(self.unsigned.encode(self.chain_id, None)),
         }
-
-        Hasher::digest(self.unsigned.encode(self.chain_id, None))
     }
 
     pub fn recover_public(&self, with_chain_id: bool) -> ProtocolResult<Public> {
@@ -368,6 +670,12 @@ impl From<SignatureComponents> for Bytes {
 }
 
 impl SignatureComponents {
+    /// The mode byte of the interoperation signature R which calls CKB-VM with
+    /// multiple cell deps.
+    pub const INTEROP_MULTI_CELL_DEP: u8 = 1;
+    /// The mode byte of the interoperation signature R which calls CKB-VM with
+    /// a single cell dep.
+    pub const INTEROP_SINGLE_CELL_DEP: u8 = 0;
     pub const SECP256K1_SIGNATURE_LEN: usize = 65;
 
     pub fn as_bytes(&self) -> Bytes {
@@ -379,6 +687,13 @@ impl SignatureComponents {
     }
 
     pub fn add_chain_replay_protection(&self, chain_id: Option<u64>) -> u64 {
+        self.full_v(chain_id)
+    }
+
+    /// The full `v` of the signature, which is `27 + standard_v` without chain
+    /// id, otherwise `chain_id * 2 + 35 + standard_v` as EIP-155. It is the
+    /// inverse of [`Self::extract_standard_v`] and [`Self::extract_chain_id`].
+    pub fn full_v(&self, chain_id: Option<u64>) -> u64 {
         (self.standard_v as u64) + chain_id.map(|i| i * 2 + 35).unwrap_or(27)
     }
 
@@ -399,16 +714,62 @@ impl SignatureComponents {
         }
     }
 
-    pub(crate) fn extract_interoperation_tx_sender(&self) -> ProtocolResult<H160> {
-        // Only call CKB-VM mode is supported now
-        if self.r[0] == 0 {
-            let r = rlp::decode::<CellDepWithPubKey>(&self.r[1..])
-                .map_err(TypesError::DecodeInteroperationSigR)?;
+    /// Build the signature of an interoperation transaction. A single cell
+    /// dep is encoded in the single-cell-dep mode, otherwise in the
+    /// multi-cell-dep mode.
+    pub fn interoperation(cell_deps: Vec<CellDepWithPubKey>, s: Bytes) -> Self {
+        let mut r = BytesMut::new();
+        if cell_deps.len() == 1 {
+            r.put_u8(Self::INTEROP_SINGLE_CELL_DEP);
+            r.extend_from_slice(&rlp::encode(&cell_deps[0]));
+        } else {
+            r.put_u8(Self::INTEROP_MULTI_CELL_DEP);
+            r.extend_from_slice(&rlp::encode(&CellDepsWithPubKey::new(cell_deps)));
+        }
+
+        SignatureComponents {
+            r: r.freeze(),
+            s,
+            standard_v: 0,
+        }
+    }
+
+    /// Decode the cell deps with the public keys from the signature R of an
+    /// interoperation transaction. Every cell dep must verify the signature S.
+    pub fn interoperation_cell_deps(&self) -> ProtocolResult<Vec<CellDepWithPubKey>> {
+        if self.r.is_empty() {
+            return Err(TypesError::SignatureRIsEmpty.into());
+        }
+
+        let cell_deps = match self.r[0] {
+            Self::INTEROP_SINGLE_CELL_DEP => vec![rlp::decode::<CellDepWithPubKey>(&self.r[1..])
+                .map_err(TypesError::DecodeInteroperationSigR)?],
+            Self::INTEROP_MULTI_CELL_DEP => {
+                rlp::decode::<CellDepsWithPubKey>(&self.r[1..])
+                    .map_err(TypesError::DecodeInteroperationSigR)?
+                    .cell_deps
+            }
+            mode => return Err(TypesError::UnsupportedInteropMode(mode).into()),
+        };
 
-            return Ok(Hasher::digest(&r.pub_key).into());
+        if cell_deps.is_empty() {
+            return Err(TypesError::EmptyInteropCellDeps.into());
         }
 
-        Err(TypesError::InvalidSignatureRType.into())
+        Ok(cell_deps)
+    }
+
+    /// The sender of an interoperation transaction is derived from the public
+    /// keys of the cell deps in order, so the sender of the single-cell-dep
+    /// mode is the hash of its public key.
+    pub(crate) fn extract_interoperation_tx_sender(&self) -> ProtocolResult<H160> {
+        let pub_keys = self
+            .interoperation_cell_deps()?
+            .into_iter()
+            .flat_map(|c| c.pub_key.to_vec())
+            .collect::<Vec<_>>();
+
+        Ok(Hasher::digest(pub_keys).into())
     }
 
     #[allow(clippy::len_without_is_empty)]
@@ -430,15 +791,10 @@ impl SignedTransaction {
             return Err(TypesError::Unsigned.into());
         }
 
-        let hash = utx.signature_hash(true);
         let sig = utx.signature.as_ref().unwrap();
 
         if sig.is_eth_sig() {
-            let public = Public::from_slice(
-                &secp256k1_recover(hash.as_bytes(), sig.as_bytes().as_ref())
-                    .map_err(TypesError::Crypto)?
-                    .serialize_uncompressed()[1..65],
-            );
+            let public = utx.recover_public(true)?;
 
             return Ok(SignedTransaction {
                 transaction: utx.calc_hash(),
@@ -455,6 +811,41 @@ impl SignedTransaction {
         })
     }
 
+    /// The public key of the sender, which is recovered once when the
+    /// transaction is built by [`SignedTransaction::from_unverified`] or
+    /// decoded, and kept in `public`. So the later checks do not pay the cost
+    /// of the ECDSA recovery again. It is recovered from the signature only
+    /// if the transaction was built without it. The public key of an
+    /// interoperation transaction is zero.
+    pub fn recover_public_cached(&self) -> ProtocolResult<Public> {
+        match self.public {
+            Some(public) => Ok(public),
+            None => self.transaction.recover_public(true),
+        }
+    }
+
+    /// The same as [`SignedTransaction::from_unverified`], but rejects the
+    /// transaction whose chain id differs from the `expected_chain_id` to
+    /// prevent the cross-chain replay. The legacy transactions without chain
+    /// id (pre-EIP155) are still accepted.
+    pub fn from_unverified_checked(
+        utx: UnverifiedTransaction,
+        expected_chain_id: Option<u64>,
+    ) -> ProtocolResult<Self> {
+        if let (Some(expect), Some(actual)) = (expected_chain_id, utx.chain_id) {
+            if expect != actual {
+                return Err(TypesError::ChainIdMismatch { expect, actual }.into());
+            }
+        }
+
+        Self::from_unverified(utx)
+    }
+
+    /// The sender which has been derived while constructing the transaction.
+    pub fn sender(&self) -> H160 {
+        self.sender
+    }
+
     pub fn type_(&self) -> u64 {
         self.transaction.unsigned.type_()
     }
@@ -463,10 +854,45 @@ impl SignedTransaction {
         self.transaction.unsigned.to()
     }
 
+    pub fn value(&self) -> U256 {
+        *self.transaction.unsigned.value()
+    }
+
+    pub fn nonce(&self) -> U256 {
+        *self.transaction.unsigned.nonce()
+    }
+
+    pub fn gas_limit(&self) -> U256 {
+        *self.transaction.unsigned.gas_limit()
+    }
+
+    pub fn gas_price(&self) -> U256 {
+        self.transaction.unsigned.gas_price()
+    }
+
+    /// The fee per gas paid to the proposer under the given block base fee,
+    /// which is the effective gas price minus the burnt base fee. For an
+    /// EIP-1559 transaction it is `min(max_priority_fee_per_gas,
+    /// max_fee_per_gas - base_fee)`, other transactions pay `gas_price -
+    /// base_fee`. Zero is returned if the base fee can not be covered.
+    pub fn effective_priority_fee(&self, base_fee: U256) -> U256 {
+        self.transaction
+            .unsigned
+            .effective_gas_price(&base_fee)
+            .map(|price| price.saturating_sub(base_fee))
+            .unwrap_or_default()
+    }
+
     pub fn is_eip155(&self) -> bool {
         self.transaction.chain_id.is_some()
     }
 
+    /// Whether the transaction burns its value by sending it to the zero
+    /// address, which is distinguished from a contract creation.
+    pub fn is_burn(&self) -> bool {
+        self.get_to() == Some(H160::zero()) && !self.value().is_zero()
+    }
+
     /// Encode a transaction receipt into bytes.
     ///
     /// According to [`EIP-2718`]:
@@ -486,7 +912,24 @@ impl SignedTransaction {
     /// [`EIP-2718`]: https://eips.ethereum.org/EIPS/eip-2718#receipts
     /// [`EIP-2930`]: https://eips.ethereum.org/EIPS/eip-2930#parameters
     /// [`EIP-1559`]: https://eips.ethereum.org/EIPS/eip-1559#specification
+    ///
+    /// The gas used by the transaction itself is taken as the
+    /// `cumulativeGasUsed`, which is only correct for a single transaction
+    /// block. Use [`SignedTransaction::encode_receipt_with_cumulative`] when
+    /// the transaction is one of a block.
     pub fn encode_receipt(&self, r: &TxResp, logs_bloom: Bloom) -> Bytes {
+        self.encode_receipt_with_cumulative(r, logs_bloom, r.gas_used.into())
+    }
+
+    /// Encode a transaction receipt into bytes with the `cumulativeGasUsed`,
+    /// which is the sum of the gas used by the transaction and all the
+    /// transactions before it in the block.
+    pub fn encode_receipt_with_cumulative(
+        &self,
+        r: &TxResp,
+        logs_bloom: Bloom,
+        cumulative: U256,
+    ) -> Bytes {
         // Status: either 1 (success) or 0 (failure).
         // Only present after activation of [EIP-658](https://eips.ethereum.org/EIPS/eip-658)
         let status: u64 = if matches!(r.exit_reason, ExitReason::Succeed(_)) {
@@ -494,23 +937,58 @@ impl SignedTransaction {
         } else {
             0
         };
-        let used_gas = U256::from(r.gas_used);
         let legacy_receipt = {
             let mut rlp = RlpStream::new();
             rlp.begin_list(4);
             rlp.append(&status);
-            rlp.append(&used_gas);
+            rlp.append(&cumulative);
             rlp.append(&logs_bloom);
             rlp.append_list(&r.logs);
             rlp.out().freeze()
         };
         match self.type_() {
-            x if x == 0x01 || x == 0x02 => [&x.to_be_bytes()[7..], &legacy_receipt].concat().into(),
+            x @ (0x01..=0x03) => [&x.to_be_bytes()[7..], &legacy_receipt].concat().into(),
             _ => legacy_receipt, // legacy (0x00) or undefined type
         }
     }
 }
 
+/// Decode a transaction receipt encoded by
+/// [`SignedTransaction::encode_receipt`].
+///
+/// The leading byte is inspected to tell a typed receipt (`0x01` to `0x03`)
+/// from a legacy one, whose first byte is always an RLP list header (`>=
+/// 0xc0`). Returns `(status, cumulative_gas_used, logs_bloom, logs)`.
+pub fn decode_receipt(bytes: &[u8]) -> ProtocolResult<(u64, U256, Bloom, Vec<Log>)> {
+    let header = *bytes.first().ok_or(TypesError::TruncatedReceipt)?;
+    let payload = match header {
+        x if x >= 0xc0 => bytes,
+        0x01..=0x03 => &bytes[1..],
+        x => return Err(TypesError::InvalidReceiptType(x).into()),
+    };
+
+    let map_err = |e: DecoderError| match e {
+        DecoderError::RlpIsTooShort => TypesError::TruncatedReceipt,
+        e => TypesError::DecodeReceipt(e),
+    };
+
+    if payload.is_empty() {
+        return Err(TypesError::TruncatedReceipt.into());
+    }
+
+    let rlp = Rlp::new(payload);
+    if rlp.item_count().map_err(map_err)? != 4 {
+        return Err(TypesError::DecodeReceipt(DecoderError::RlpIncorrectListLen).into());
+    }
+
+    Ok((
+        rlp.val_at(0).map_err(map_err)?,
+        rlp.val_at(1).map_err(map_err)?,
+        rlp.val_at(2).map_err(map_err)?,
+        rlp.list_at(3).map_err(map_err)?,
+    ))
+}
+
 pub fn public_to_address(public: &Public) -> H160 {
     let hash = Hasher::digest(public);
     let mut ret = H160::zero();
@@ -523,3 +1001,921 @@ pub fn recover_intact_pub_key(public: &Public) -> H520 {
     inner.extend_from_slice(public.as_bytes());
     H520::from_slice(&inner[0..65])
 }
+
+/// Deduplicate the addresses and the storage keys of an access list while
+/// preserving the order of their first occurrences. The storage keys of a
+/// repeated address are merged into the first item of the address, so that
+/// each address and each `(address, key)` pair is warmed up and charged only
+/// once.
+pub fn normalize_access_list(list: AccessList) -> AccessList {
+    let mut ret: AccessList = Vec::with_capacity(list.len());
+    let mut positions = HashMap::new();
+    let mut seen_keys = HashSet::new();
+
+    for item in list.into_iter() {
+        let pos = *positions.entry(item.address).or_insert_with(|| {
+            ret.push(AccessListItem {
+                address:      item.address,
+                storage_keys: Vec::new(),
+            });
+            ret.len() - 1
+        });
+
+        for key in item.storage_keys.into_iter() {
+            if seen_keys.insert((item.address, key)) {
+                ret[pos].storage_keys.push(key);
+            }
+        }
+    }
+
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use rlp::Decodable;
+
+    use super::*;
+    use crate::codec::hex_decode;
+    use crate::types::{logs_bloom, CellDep, ExitError};
+
+    fn mock_signed_tx(unsigned: UnsignedTransaction) -> SignedTransaction {
+        SignedTransaction {
+            transaction: UnverifiedTransaction::new(
+                unsigned,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            ),
+            sender:      Default::default(),
+            public:      Default::default(),
+        }
+    }
+
+    fn mock_tx_resp() -> TxResp {
+        TxResp {
+            gas_used: 21_000,
+            logs: vec![Log {
+                address: H160::random(),
+                topics:  vec![H256::random()],
+                data:    vec![1, 2, 3],
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_receipt_codec() {
+        let legacy = UnsignedTransaction::Legacy(LegacyTransaction {
+            nonce:     Default::default(),
+            gas_price: Default::default(),
+            gas_limit: Default::default(),
+            action:    TransactionAction::Create,
+            value:     Default::default(),
+            data:      Default::default(),
+        });
+        let eip1559 = UnsignedTransaction::Eip1559(Eip1559Transaction {
+            nonce:                    Default::default(),
+            max_priority_fee_per_gas: Default::default(),
+            gas_price:                Default::default(),
+            gas_limit:                Default::default(),
+            action:                   TransactionAction::Create,
+            value:                    Default::default(),
+            data:                     Default::default(),
+            access_list:              Default::default(),
+        });
+
+        let eip4844 = UnsignedTransaction::Eip4844(Eip4844Transaction {
+            nonce:                    Default::default(),
+            max_priority_fee_per_gas: Default::default(),
+            gas_price:                Default::default(),
+            gas_limit:                Default::default(),
+            action:                   TransactionAction::Call(H160::random()),
+            value:                    Default::default(),
+            data:                     Default::default(),
+            access_list:              Default::default(),
+            max_fee_per_blob_gas:     Default::default(),
+            blob_versioned_hashes:    vec![H256::random()],
+        });
+
+        for (unsigned, type_byte) in [(legacy, None), (eip1559, Some(0x02)), (eip4844, Some(0x03))]
+        {
+            let tx = mock_signed_tx(unsigned);
+            let resp = mock_tx_resp();
+            let bloom = logs_bloom(resp.logs.iter());
+            let encoded = tx.encode_receipt(&resp, bloom);
+            match type_byte {
+                Some(x) => assert_eq!(encoded[0], x),
+                None => assert!(encoded[0] >= 0xc0),
+            }
+
+            let (status, used_gas, decoded_bloom, logs) = decode_receipt(&encoded).unwrap();
+            assert_eq!(status, 1);
+            assert_eq!(used_gas, U256::from(resp.gas_used));
+            assert_eq!(decoded_bloom, bloom);
+            assert_eq!(logs, resp.logs);
+        }
+
+        let tx = mock_signed_tx(UnsignedTransaction::Eip2930(Eip2930Transaction {
+            nonce:       Default::default(),
+            gas_price:   Default::default(),
+            gas_limit:   Default::default(),
+            action:      TransactionAction::Create,
+            value:       Default::default(),
+            data:        Default::default(),
+            access_list: Default::default(),
+        }));
+        let resp = TxResp {
+            exit_reason: ExitReason::Error(ExitError::OutOfGas),
+            ..mock_tx_resp()
+        };
+        let encoded = tx.encode_receipt(&resp, Bloom::zero());
+        assert_eq!(encoded[0], 0x01);
+        assert_eq!(decode_receipt(&encoded).unwrap().0, 0);
+    }
+
+    #[test]
+    fn test_receipt_cumulative_gas_used() {
+        let tx = mock_signed_tx(UnsignedTransaction::Eip1559(Eip1559Transaction {
+            nonce:                    Default::default(),
+            max_priority_fee_per_gas: Default::default(),
+            gas_price:                Default::default(),
+            gas_limit:                Default::default(),
+            action:                   TransactionAction::Create,
+            value:                    Default::default(),
+            data:                     Default::default(),
+            access_list:              Default::default(),
+        }));
+
+        let mut cumulative = U256::zero();
+        let mut last = U256::zero();
+        for gas_used in [21_000u64, 50_000, 30_000] {
+            let resp = TxResp {
+                gas_used,
+                ..mock_tx_resp()
+            };
+            cumulative += gas_used.into();
+            let encoded = tx.encode_receipt_with_cumulative(&resp, Bloom::zero(), cumulative);
+
+            let (_, decoded, _, _) = decode_receipt(&encoded).unwrap();
+            assert_eq!(decoded, cumulative);
+            assert!(decoded > last);
+            last = decoded;
+        }
+        assert_eq!(last, U256::from(101_000u64));
+    }
+
+    #[test]
+    fn test_decode_invalid_receipt() {
+        assert!(decode_receipt(&[]).is_err());
+        assert!(decode_receipt(&[0x02]).is_err());
+        assert!(decode_receipt(&[0x04, 0xc0]).is_err());
+
+        let tx = mock_signed_tx(UnsignedTransaction::Legacy(LegacyTransaction {
+            nonce:     Default::default(),
+            gas_price: Default::default(),
+            gas_limit: Default::default(),
+            action:    TransactionAction::Create,
+            value:     Default::default(),
+            data:      Default::default(),
+        }));
+        let encoded = tx.encode_receipt(&mock_tx_resp(), Bloom::zero());
+        assert!(decode_receipt(&encoded[..encoded.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_recover_public_cached() {
+        let bytes = hex_decode("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        let tx = UnverifiedTransaction::decode(&Rlp::new(&bytes)).unwrap();
+        let public = tx.recover_public(true).unwrap();
+
+        let mut stx = SignedTransaction::from_unverified(tx).unwrap();
+        assert_eq!(stx.public, Some(public));
+        assert_eq!(stx.sender, public_to_address(&public));
+        assert_eq!(stx.recover_public_cached().unwrap(), public);
+
+        // The public key is recovered if the transaction is built without it.
+        stx.public = None;
+        assert_eq!(stx.recover_public_cached().unwrap(), public);
+        stx.transaction.signature = None;
+        assert!(stx.recover_public_cached().is_err());
+    }
+
+    #[test]
+    fn test_full_v_round_trip() {
+        let chain_ids = [
+            None,
+            Some(0),
+            Some(1),
+            Some(5),
+            Some(2022),
+            Some(u32::MAX as u64),
+            Some((u64::MAX - 36) / 2),
+        ];
+
+        for chain_id in chain_ids {
+            for standard_v in [0u8, 1] {
+                let sig = SignatureComponents {
+                    standard_v,
+                    ..Default::default()
+                };
+                let v = sig.full_v(chain_id);
+                assert_eq!(v, sig.add_chain_replay_protection(chain_id));
+                assert_eq!(SignatureComponents::extract_standard_v(v), Some(standard_v));
+                assert_eq!(SignatureComponents::extract_chain_id(v), chain_id);
+            }
+        }
+
+        assert_eq!(SignatureComponents::extract_standard_v(29), None);
+        assert_eq!(SignatureComponents::extract_standard_v(34), None);
+    }
+
+    #[test]
+    fn test_pre_eip155_signature_hash() {
+        // A legacy transaction signed without chain id, whose v is 0x1b.
+        let bytes = hex_decode("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        let tx = UnverifiedTransaction::decode(&Rlp::new(&bytes)).unwrap();
+        assert_eq!(tx.chain_id, None);
+        assert_eq!(SignatureComponents::extract_standard_v(0x1b), Some(0));
+        assert_eq!(tx.signature.as_ref().unwrap().standard_v, 0);
+
+        // Without chain id, both flags result in the pre-EIP-155 hash.
+        assert_eq!(tx.signature_hash(false), tx.signature_hash(true));
+        assert_eq!(
+            tx.recover_public(false).unwrap(),
+            tx.recover_public(true).unwrap()
+        );
+
+        // With a chain id, only the EIP-155 hash recovers the right sender.
+        let mut eip155_tx = tx.clone();
+        eip155_tx.chain_id = Some(5);
+        assert_ne!(
+            eip155_tx.signature_hash(false),
+            eip155_tx.signature_hash(true)
+        );
+        assert_eq!(eip155_tx.signature_hash(false), tx.signature_hash(false));
+        assert_eq!(
+            SignatureComponents::extract_standard_v(
+                tx.signature
+                    .as_ref()
+                    .unwrap()
+                    .add_chain_replay_protection(Some(5))
+            ),
+            Some(0)
+        );
+
+        // The non-legacy transactions ignore the flag.
+        let mut eip1559_tx = tx;
+        eip1559_tx.unsigned = UnsignedTransaction::Eip1559(Eip1559Transaction {
+            nonce:                    Default::default(),
+            max_priority_fee_per_gas: Default::default(),
+            gas_price:                Default::default(),
+            gas_limit:                Default::default(),
+            action:                   TransactionAction::Create,
+            value:                    Default::default(),
+            data:                     Default::default(),
+            access_list:              vec![],
+        });
+        eip1559_tx.chain_id = Some(5);
+        assert_eq!(
+            eip1559_tx.signature_hash(false),
+            eip1559_tx.signature_hash(true)
+        );
+    }
+
+    #[test]
+    fn test_content_hash() {
+        use common_crypto::{
+            Crypto, PrivateKey, Secp256k1Recoverable, Secp256k1RecoverablePrivateKey, Signature,
+        };
+        use rand::rngs::OsRng;
+
+        let mut tx = UnverifiedTransaction::new(
+            UnsignedTransaction::Eip1559(Eip1559Transaction {
+                nonce:                    U256::one(),
+                max_priority_fee_per_gas: U256::one(),
+                gas_price:                U256::one(),
+                gas_limit:                U256::from(21000u64),
+                action:                   TransactionAction::Call(H160::random()),
+                value:                    U256::one(),
+                data:                     Bytes::new(),
+                access_list:              vec![],
+            }),
+            None,
+            Some(5),
+            Default::default(),
+        );
+
+        let signed = (0..2)
+            .map(|_| {
+                let priv_key = Secp256k1RecoverablePrivateKey::generate(&mut OsRng);
+                let signature = Secp256k1Recoverable::sign_message(
+                    tx.signature_hash(true).as_bytes(),
+                    &priv_key.to_bytes(),
+                )
+                .unwrap()
+                .to_bytes();
+                tx.signature = Some(signature.into());
+                assert!(tx.recover_public(true).is_ok());
+                tx.clone().calc_hash()
+            })
+            .collect::<Vec<_>>();
+
+        assert_ne!(signed[0].get_hash(), signed[1].get_hash());
+        assert_eq!(
+            signed[0].unsigned.content_hash(signed[0].chain_id),
+            signed[1].unsigned.content_hash(signed[1].chain_id)
+        );
+        assert_ne!(
+            signed[0].unsigned.content_hash(Some(5)),
+            signed[0].unsigned.content_hash(Some(6))
+        );
+    }
+
+    #[test]
+    fn test_sign() {
+        use common_crypto::{
+            PrivateKey, Secp256k1RecoverablePrivateKey, ToPublicKey, UncompressedPublicKey,
+        };
+        use rand::rngs::OsRng;
+
+        let priv_key = Secp256k1RecoverablePrivateKey::generate(&mut OsRng);
+        let public = Public::from_slice(&priv_key.pub_key().to_uncompressed_bytes()[1..65]);
+        let legacy = UnsignedTransaction::Legacy(LegacyTransaction {
+            nonce:     U256::one(),
+            gas_price: U256::one(),
+            gas_limit: U256::from(21000u64),
+            action:    TransactionAction::Call(H160::random()),
+            value:     U256::one(),
+            data:      Default::default(),
+        });
+        let eip1559 = UnsignedTransaction::Eip1559(Eip1559Transaction {
+            nonce:                    U256::one(),
+            max_priority_fee_per_gas: U256::one(),
+            gas_price:                U256::one(),
+            gas_limit:                U256::from(21000u64),
+            action:                   TransactionAction::Create,
+            value:                    Default::default(),
+            data:                     Default::default(),
+            access_list:              vec![],
+        });
+
+        for (unsigned, chain_id) in [
+            (legacy.clone(), None),
+            (legacy, Some(5)),
+            (eip1559, Some(5)),
+        ] {
+            let stx = unsigned.sign(&priv_key, chain_id).unwrap();
+            assert_eq!(stx.sender, public_to_address(&public));
+            assert_eq!(stx.transaction.hash, stx.transaction.get_hash());
+
+            let decoded = SignedTransaction::from_unverified(stx.transaction.clone()).unwrap();
+            assert_eq!(decoded.sender, stx.sender);
+            assert_eq!(decoded.transaction.chain_id, chain_id);
+        }
+    }
+
+    #[test]
+    fn test_tx_action() {
+        let to = H160::random();
+        let mut tx = LegacyTransaction {
+            nonce:     Default::default(),
+            gas_price: Default::default(),
+            gas_limit: Default::default(),
+            action:    TransactionAction::Call(to),
+            value:     Default::default(),
+            data:      Default::default(),
+        };
+        let call = UnsignedTransaction::Legacy(tx.clone());
+        assert!(!call.is_create());
+        assert_eq!(call.is_call(), Some(to));
+
+        tx.action = TransactionAction::Create;
+        let create = UnsignedTransaction::Legacy(tx);
+        assert!(create.is_create());
+        assert_eq!(create.is_call(), None);
+    }
+
+    #[test]
+    fn test_may_cost() {
+        let mut tx = LegacyTransaction {
+            nonce:     Default::default(),
+            gas_price: 2u64.into(),
+            gas_limit: 21_000u64.into(),
+            action:    TransactionAction::Call(H160::random()),
+            value:     100u64.into(),
+            data:      Default::default(),
+        };
+        let utx = UnsignedTransaction::Legacy(tx.clone());
+        assert_eq!(utx.may_cost().unwrap(), U256::from(42_100u64));
+
+        // The gas price multiplies the gas limit overflows.
+        tx.gas_price = U256::max_value();
+        let utx = UnsignedTransaction::Legacy(tx.clone());
+        assert!(utx.may_cost().is_err());
+
+        // Adding the value overflows.
+        tx.gas_price = U256::one();
+        tx.value = U256::max_value();
+        let utx = UnsignedTransaction::Legacy(tx);
+        assert!(utx.may_cost().is_err());
+    }
+
+    #[test]
+    fn test_min_gas_price_and_fee_cap() {
+        let legacy = UnsignedTransaction::Legacy(LegacyTransaction {
+            nonce:     Default::default(),
+            gas_price: 10u64.into(),
+            gas_limit: 21_000u64.into(),
+            action:    TransactionAction::Call(H160::random()),
+            value:     Default::default(),
+            data:      Default::default(),
+        });
+        assert!(legacy.meets_min_gas_price(10u64.into()));
+        assert!(!legacy.meets_min_gas_price(11u64.into()));
+        assert!(legacy.check_fee_cap().is_ok());
+
+        let mut tx = Eip1559Transaction {
+            nonce:                    Default::default(),
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS.into(),
+            gas_price:                MAX_PRIORITY_FEE_PER_GAS.into(),
+            gas_limit:                21_000u64.into(),
+            action:                   TransactionAction::Call(H160::random()),
+            value:                    Default::default(),
+            data:                     Default::default(),
+            access_list:              Default::default(),
+        };
+        let utx = UnsignedTransaction::Eip1559(tx.clone());
+        assert!(utx.meets_min_gas_price(MAX_PRIORITY_FEE_PER_GAS.into()));
+        assert!(!utx.meets_min_gas_price((MAX_PRIORITY_FEE_PER_GAS + 1).into()));
+        assert!(utx.check_fee_cap().is_ok());
+
+        // The max fee per gas is less than the max priority fee per gas.
+        tx.gas_price = (MAX_PRIORITY_FEE_PER_GAS - 1).into();
+        let utx = UnsignedTransaction::Eip1559(tx);
+        assert!(utx.meets_min_gas_price(MAX_PRIORITY_FEE_PER_GAS.into()));
+        let expect = TypesError::InvalidFeeCap {
+            max_fee_per_gas:          (MAX_PRIORITY_FEE_PER_GAS - 1).into(),
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS.into(),
+        };
+        assert!(utx
+            .check_fee_cap()
+            .unwrap_err()
+            .to_string()
+            .contains(&expect.to_string()));
+    }
+
+    #[test]
+    fn test_validate_nonce() {
+        let mut tx = LegacyTransaction {
+            nonce:     Default::default(),
+            gas_price: 10u64.into(),
+            gas_limit: 21_000u64.into(),
+            action:    TransactionAction::Call(H160::random()),
+            value:     Default::default(),
+            data:      Default::default(),
+        };
+
+        for (nonce, valid) in [
+            (U256::zero(), true),
+            (U256::from(u64::MAX - 1), true),
+            (U256::from(u64::MAX), false),
+            (U256::from(u64::MAX) + 1, false),
+        ] {
+            tx.nonce = nonce;
+            let res = UnsignedTransaction::Legacy(tx.clone()).validate_nonce();
+            if valid {
+                assert!(res.is_ok());
+            } else {
+                assert!(res
+                    .unwrap_err()
+                    .to_string()
+                    .contains(&TypesError::NonceTooHigh(nonce).to_string()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_effective_priority_fee() {
+        let legacy = UnsignedTransaction::Legacy(LegacyTransaction {
+            nonce:     Default::default(),
+            gas_price: 10u64.into(),
+            gas_limit: 21_000u64.into(),
+            action:    TransactionAction::Call(H160::random()),
+            value:     Default::default(),
+            data:      Default::default(),
+        });
+        let eip2930 = UnsignedTransaction::Eip2930(Eip2930Transaction {
+            nonce:       Default::default(),
+            gas_price:   10u64.into(),
+            gas_limit:   21_000u64.into(),
+            action:      TransactionAction::Call(H160::random()),
+            value:       Default::default(),
+            data:        Default::default(),
+            access_list: Default::default(),
+        });
+
+        // The legacy and EIP-2930 transactions pay the gas price above the base
+        // fee, and they can not be charged below the base fee.
+        for unsigned in [legacy, eip2930] {
+            assert_eq!(
+                unsigned.effective_gas_price(&10u64.into()),
+                Some(10u64.into())
+            );
+            assert!(unsigned.effective_gas_price(&11u64.into()).is_none());
+
+            let tx = mock_signed_tx(unsigned);
+            assert_eq!(tx.effective_priority_fee(U256::zero()), 10u64.into());
+            assert_eq!(tx.effective_priority_fee(4u64.into()), 6u64.into());
+            assert_eq!(tx.effective_priority_fee(11u64.into()), U256::zero());
+        }
+
+        // The EIP-1559 transaction pays the max priority fee per gas unless the
+        // max fee per gas caps it.
+        let tx = mock_signed_tx(UnsignedTransaction::Eip1559(Eip1559Transaction {
+            nonce:                    Default::default(),
+            max_priority_fee_per_gas: 3u64.into(),
+            gas_price:                10u64.into(),
+            gas_limit:                21_000u64.into(),
+            action:                   TransactionAction::Call(H160::random()),
+            value:                    Default::default(),
+            data:                     Default::default(),
+            access_list:              Default::default(),
+        }));
+        assert_eq!(tx.effective_priority_fee(U256::zero()), 3u64.into());
+        assert_eq!(tx.effective_priority_fee(7u64.into()), 3u64.into());
+        assert_eq!(tx.effective_priority_fee(8u64.into()), 2u64.into());
+        assert_eq!(tx.effective_priority_fee(10u64.into()), U256::zero());
+        assert_eq!(tx.effective_priority_fee(11u64.into()), U256::zero());
+    }
+
+    #[test]
+    fn test_eip2930_new() {
+        let (address, key) = (H160::random(), H256::random());
+        let access_list = vec![
+            AccessListItem {
+                address,
+                storage_keys: vec![key],
+            },
+            AccessListItem {
+                address,
+                storage_keys: vec![key],
+            },
+        ];
+
+        let tx = Eip2930Transaction::new(
+            Default::default(),
+            10u64.into(),
+            21_000u64.into(),
+            TransactionAction::Call(H160::random()),
+            Default::default(),
+            Default::default(),
+            access_list.clone(),
+        )
+        .unwrap();
+        assert_eq!(tx.access_list, vec![AccessListItem {
+            address,
+            storage_keys: vec![key],
+        }]);
+
+        let err = Eip2930Transaction::new(
+            Default::default(),
+            10u64.into(),
+            U256::zero(),
+            TransactionAction::Call(H160::random()),
+            Default::default(),
+            Default::default(),
+            access_list,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&TypesError::ZeroGasLimit.to_string()));
+    }
+
+    #[test]
+    fn test_eip1559_new() {
+        let new_tx = |max_priority_fee_per_gas: u64, gas_price: u64, gas_limit: u64| {
+            Eip1559Transaction::new(
+                Default::default(),
+                max_priority_fee_per_gas.into(),
+                gas_price.into(),
+                gas_limit.into(),
+                TransactionAction::Call(H160::random()),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            )
+        };
+
+        // The max fee per gas equals to or is greater than the max priority fee
+        // per gas.
+        let tx = new_tx(MAX_PRIORITY_FEE_PER_GAS, MAX_PRIORITY_FEE_PER_GAS, 21_000).unwrap();
+        assert!(UnsignedTransaction::Eip1559(tx).check_fee_cap().is_ok());
+        assert!(new_tx(
+            MAX_PRIORITY_FEE_PER_GAS,
+            MAX_PRIORITY_FEE_PER_GAS + 1,
+            21_000
+        )
+        .is_ok());
+        assert!(new_tx(0, 0, 21_000).is_ok());
+
+        // The max fee per gas is less than the max priority fee per gas.
+        let expect = TypesError::InvalidFeeCap {
+            max_fee_per_gas:          (MAX_PRIORITY_FEE_PER_GAS - 1).into(),
+            max_priority_fee_per_gas: MAX_PRIORITY_FEE_PER_GAS.into(),
+        };
+        assert!(new_tx(
+            MAX_PRIORITY_FEE_PER_GAS,
+            MAX_PRIORITY_FEE_PER_GAS - 1,
+            21_000
+        )
+        .unwrap_err()
+        .to_string()
+        .contains(&expect.to_string()));
+
+        // The gas limit is zero.
+        assert!(
+            new_tx(MAX_PRIORITY_FEE_PER_GAS, MAX_PRIORITY_FEE_PER_GAS, 0)
+                .unwrap_err()
+                .to_string()
+                .contains(&TypesError::ZeroGasLimit.to_string())
+        );
+    }
+
+    #[test]
+    fn test_intrinsic_gas() {
+        // A simple transfer costs the base gas only.
+        let transfer = UnsignedTransaction::Legacy(LegacyTransaction {
+            nonce:     Default::default(),
+            gas_price: Default::default(),
+            gas_limit: Default::default(),
+            action:    TransactionAction::Call(H160::random()),
+            value:     1u64.into(),
+            data:      Default::default(),
+        });
+        assert_eq!(transfer.intrinsic_gas(), 21_000);
+
+        // Deploying the contract with init code `0x6000600055` which contains two
+        // zero bytes and three non-zero bytes.
+        let deploy = UnsignedTransaction::Legacy(LegacyTransaction {
+            nonce:     Default::default(),
+            gas_price: Default::default(),
+            gas_limit: Default::default(),
+            action:    TransactionAction::Create,
+            value:     Default::default(),
+            data:      Bytes::from(vec![0x60, 0x00, 0x60, 0x00, 0x55]),
+        });
+        assert_eq!(deploy.intrinsic_gas(), 53_000 + 4 * 2 + 16 * 3);
+
+        let with_access_list = UnsignedTransaction::Eip2930(Eip2930Transaction {
+            nonce:       Default::default(),
+            gas_price:   Default::default(),
+            gas_limit:   Default::default(),
+            action:      TransactionAction::Call(H160::random()),
+            value:       Default::default(),
+            data:        Default::default(),
+            access_list: vec![
+                AccessListItem {
+                    address:      H160::random(),
+                    storage_keys: vec![H256::random(), H256::random()],
+                },
+                AccessListItem {
+                    address:      H160::random(),
+                    storage_keys: vec![],
+                },
+            ],
+        });
+        assert_eq!(
+            with_access_list.intrinsic_gas(),
+            21_000 + 2_400 * 2 + 1_900 * 2
+        );
+    }
+
+    #[test]
+    fn test_access_list_gas() {
+        let with_access_list = |access_list| {
+            UnsignedTransaction::Eip1559(Eip1559Transaction {
+                nonce: Default::default(),
+                max_priority_fee_per_gas: Default::default(),
+                gas_price: Default::default(),
+                gas_limit: Default::default(),
+                action: TransactionAction::Call(H160::random()),
+                value: Default::default(),
+                data: Default::default(),
+                access_list,
+            })
+        };
+
+        let empty = with_access_list(vec![]);
+        assert_eq!(empty.access_list_len(), (0, 0));
+        assert_eq!(empty.access_list_gas(), 0);
+
+        let address_only = with_access_list(vec![AccessListItem {
+            address:      H160::random(),
+            storage_keys: vec![],
+        }]);
+        assert_eq!(address_only.access_list_len(), (1, 0));
+        assert_eq!(address_only.access_list_gas(), 2_400);
+
+        let key = H256::random();
+        let with_keys = with_access_list(vec![
+            AccessListItem {
+                address:      H160::random(),
+                storage_keys: vec![key, key],
+            },
+            AccessListItem {
+                address:      H160::random(),
+                storage_keys: vec![H256::random()],
+            },
+        ]);
+        assert_eq!(with_keys.access_list_len(), (2, 3));
+        assert_eq!(with_keys.access_list_gas(), 2_400 * 2 + 1_900 * 3);
+        assert_eq!(
+            with_keys.intrinsic_gas(),
+            21_000 + with_keys.access_list_gas()
+        );
+    }
+
+    #[test]
+    fn test_normalize_access_list() {
+        let (addr_1, addr_2) = (H160::random(), H160::random());
+        let (key_1, key_2) = (H256::random(), H256::random());
+        let list = vec![
+            AccessListItem {
+                address:      addr_1,
+                storage_keys: vec![key_1, key_2, key_1],
+            },
+            AccessListItem {
+                address:      addr_2,
+                storage_keys: vec![key_1],
+            },
+            AccessListItem {
+                address:      addr_1,
+                storage_keys: vec![key_2, key_1],
+            },
+        ];
+
+        assert_eq!(normalize_access_list(list), vec![
+            AccessListItem {
+                address:      addr_1,
+                storage_keys: vec![key_1, key_2],
+            },
+            AccessListItem {
+                address:      addr_2,
+                storage_keys: vec![key_1],
+            },
+        ]);
+        assert!(normalize_access_list(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_from_unverified_checked() {
+        // An EIP-155 transaction with chain id 1.
+        let bytes = hex_decode("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a8025a048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        let tx = UnverifiedTransaction::decode(&Rlp::new(&bytes)).unwrap();
+        assert_eq!(tx.chain_id, Some(1));
+        assert!(SignedTransaction::from_unverified_checked(tx.clone(), Some(1)).is_ok());
+        assert!(SignedTransaction::from_unverified_checked(tx.clone(), None).is_ok());
+        assert!(SignedTransaction::from_unverified_checked(tx, Some(2)).is_err());
+
+        // A pre-EIP155 transaction without chain id.
+        let bytes = hex_decode("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        let tx = UnverifiedTransaction::decode(&Rlp::new(&bytes)).unwrap();
+        assert_eq!(tx.chain_id, None);
+        assert!(SignedTransaction::from_unverified_checked(tx, Some(2)).is_ok());
+    }
+
+    #[test]
+    fn test_signed_transaction_getters() {
+        let legacy = mock_signed_tx(UnsignedTransaction::Legacy(LegacyTransaction {
+            nonce:     1u64.into(),
+            gas_price: 2u64.into(),
+            gas_limit: 3u64.into(),
+            action:    TransactionAction::Create,
+            value:     4u64.into(),
+            data:      Default::default(),
+        }));
+        assert_eq!(legacy.nonce(), 1u64.into());
+        assert_eq!(legacy.gas_price(), 2u64.into());
+        assert_eq!(legacy.gas_limit(), 3u64.into());
+        assert_eq!(legacy.value(), 4u64.into());
+
+        let eip1559 = mock_signed_tx(UnsignedTransaction::Eip1559(Eip1559Transaction {
+            nonce:                    5u64.into(),
+            max_priority_fee_per_gas: 7u64.into(),
+            gas_price:                6u64.into(),
+            gas_limit:                8u64.into(),
+            action:                   TransactionAction::Create,
+            value:                    9u64.into(),
+            data:                     Default::default(),
+            access_list:              Default::default(),
+        }));
+        assert_eq!(eip1559.nonce(), 5u64.into());
+        assert_eq!(eip1559.gas_price(), 7u64.into());
+        assert_eq!(eip1559.gas_limit(), 8u64.into());
+        assert_eq!(eip1559.value(), 9u64.into());
+    }
+
+    #[test]
+    fn test_is_burn() {
+        let tx = |action, value: u64| {
+            mock_signed_tx(UnsignedTransaction::Legacy(LegacyTransaction {
+                nonce: Default::default(),
+                gas_price: Default::default(),
+                gas_limit: Default::default(),
+                action,
+                value: value.into(),
+                data: Default::default(),
+            }))
+        };
+
+        assert!(tx(TransactionAction::Call(H160::zero()), 1).is_burn());
+        assert!(!tx(TransactionAction::Call(H160::zero()), 0).is_burn());
+        assert!(!tx(TransactionAction::Call(H160::random()), 1).is_burn());
+        assert!(!tx(TransactionAction::Create, 1).is_burn());
+    }
+
+    #[test]
+    fn test_interoperation_sender() {
+        let pub_key = Bytes::from(vec![1u8; 33]);
+        let cell_dep = CellDepWithPubKey {
+            cell_dep: CellDep {
+                tx_hash:  H256::random(),
+                index:    0,
+                dep_type: 0,
+            },
+            pub_key:  pub_key.clone(),
+        };
+        let mut r = vec![0u8];
+        r.extend_from_slice(&rlp::encode(&cell_dep));
+
+        let mut tx = mock_signed_tx(UnsignedTransaction::Legacy(LegacyTransaction {
+            nonce:     Default::default(),
+            gas_price: Default::default(),
+            gas_limit: Default::default(),
+            action:    TransactionAction::Create,
+            value:     Default::default(),
+            data:      Default::default(),
+        }))
+        .transaction;
+        tx.signature = Some(SignatureComponents {
+            standard_v: 0,
+            r:          r.into(),
+            s:          Bytes::from(vec![0u8; 8]),
+        });
+
+        let expect: H160 = Hasher::digest(&pub_key).into();
+        let stx = SignedTransaction::from_unverified(tx).unwrap();
+        assert_eq!(stx.sender(), expect);
+        assert_eq!(stx.public, Some(Public::zero()));
+        assert_eq!(stx.recover_public_cached().unwrap(), Public::zero());
+
+        // The unknown modes are rejected.
+        let sig = SignatureComponents {
+            standard_v: 0,
+            r:          Bytes::from(vec![0x02, 0x01]),
+            s:          Bytes::new(),
+        };
+        let err = sig.extract_interoperation_tx_sender().unwrap_err();
+        assert!(err.to_string().contains("0x02"));
+    }
+
+    #[test]
+    fn test_interoperation_cell_deps() {
+        let cell_deps = (1..=2u8)
+            .map(|i| CellDepWithPubKey {
+                cell_dep: CellDep {
+                    tx_hash:  H256::random(),
+                    index:    i as u32,
+                    dep_type: 0,
+                },
+                pub_key:  Bytes::from(vec![i; 33]),
+            })
+            .collect::<Vec<_>>();
+        let s = Bytes::from(vec![0u8; 8]);
+
+        // A single cell dep is built in the single-cell-dep mode.
+        let single = SignatureComponents::interoperation(cell_deps[..1].to_vec(), s.clone());
+        let mut r = vec![SignatureComponents::INTEROP_SINGLE_CELL_DEP];
+        r.extend_from_slice(&rlp::encode(&cell_deps[0]));
+        assert_eq!(single.r, Bytes::from(r));
+        assert_eq!(single.interoperation_cell_deps().unwrap(), cell_deps[..1]);
+        assert_eq!(
+            single.extract_interoperation_tx_sender().unwrap(),
+            H160::from(Hasher::digest(&cell_deps[0].pub_key))
+        );
+
+        let multi = SignatureComponents::interoperation(cell_deps.clone(), s.clone());
+        assert_eq!(multi.r[0], SignatureComponents::INTEROP_MULTI_CELL_DEP);
+        assert_eq!(multi.interoperation_cell_deps().unwrap(), cell_deps);
+        assert_eq!(
+            multi.extract_interoperation_tx_sender().unwrap(),
+            H160::from(Hasher::digest([vec![1u8; 33], vec![2u8; 33]].concat()))
+        );
+
+        // The multi-cell-dep mode requires at least one cell dep.
+        let empty = SignatureComponents::interoperation(vec![], s);
+        assert!(empty.interoperation_cell_deps().is_err());
+    }
+}
@@ -3,6 +3,7 @@ pub use core_run::KeyProvider;
 pub use protocol::{
     async_trait,
     types::{ValidatorExtend, H160, U256},
+    ProtocolResult,
 };
 
 use std::sync::Arc;